@@ -56,4 +56,76 @@ pub enum VsrError {
     InvalidLockupDuration,
     #[msg("")]
     InsufficientClaimableRewards,
+    #[msg("")]
+    VoterDelegated,
+    #[msg("")]
+    VoterNotDelegated,
+    #[msg("")]
+    InvalidDelegate,
+    #[msg("")]
+    InvalidDelegationRecord,
+    #[msg("")]
+    UnsortedDelegators,
+    #[msg("")]
+    InvalidEmissionConfig,
+    #[msg("")]
+    InvalidLockupKind,
+    #[msg("")]
+    ClawbackNotAllowedOnDeposit,
+    #[msg("")]
+    InvalidDepositEntryCount,
+    #[msg("")]
+    SameDepositEntryIndex,
+    #[msg("")]
+    InvalidNodeEndpoint,
+    #[msg("")]
+    InvalidNodeStatus,
+    #[msg("")]
+    NodeSecurityDepositSatisfied,
+    #[msg("")]
+    InvalidEarlyUnlockPenaltyConfig,
+    #[msg("")]
+    EarlyUnlockDisabled,
+    #[msg("")]
+    InvalidVoteWeightCurve,
+    #[msg("")]
+    VoterWeightActionRequired,
+    #[msg("")]
+    VoterWeightActionRequirementNotMet,
+    #[msg("")]
+    RegistrarConfigRequired,
+    #[msg("")]
+    TokenOwnerRecordRequired,
+    #[msg("")]
+    TooManyExcludedTokenAccounts,
+    #[msg("")]
+    DuplicateExcludedTokenAccount,
+    #[msg("")]
+    ExcludedSupplyRequired,
+    #[msg("")]
+    InvalidExcludedTokenAccounts,
+    #[msg("")]
+    InvalidPauseFlags,
+    #[msg("")]
+    DepositsPaused,
+    #[msg("")]
+    ReleasesPaused,
+    #[msg("")]
+    WithdrawsPaused,
+    #[msg("")]
+    ClaimsPaused,
+    #[msg("")]
+    UnsupportedLayoutVersion,
+    #[msg("")]
+    VoterHasDelegators,
+    #[msg("")]
+    VoterWeightHistoryRequired,
+    #[msg("")]
+    InvalidVoterWeightHistory,
+    #[msg("")]
+    UncountedVoters,
+    #[msg("")]
+    InvalidRewardVault,
+    #[msg("")]
+    UndelegationRequested,
 }
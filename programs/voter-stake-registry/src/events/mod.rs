@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 
-use crate::{Lockup, VOTER_DEPOSIT_ENTRY_COUNT};
+use crate::Lockup;
 
 #[event]
 pub struct NodeDepositEvent {
@@ -11,6 +11,15 @@ pub struct NodeDepositEvent {
     pub lockup: Lockup,
 }
 
+#[event]
+pub struct NodeTopUpDepositEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub amount: u64,
+    pub lockup: Lockup,
+}
+
 #[event]
 pub struct NodeReleaseDepositEvent {
     pub registrar: Pubkey,
@@ -21,6 +30,21 @@ pub struct NodeReleaseDepositEvent {
     pub amount: u64,
 }
 
+#[event]
+pub struct NodeSlashedEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub slasher: Pubkey,
+    // amount taken from the node deposit
+    pub amount: u64,
+    // whether the slashed amount was burned instead of moved to the treasury
+    pub burned: bool,
+    pub target_deposit_entry_index: u8,
+    // amount released into the target deposit entry
+    pub remaining_amount: u64,
+}
+
 #[event]
 pub struct OrdinaryDepositEvent {
     pub registrar: Pubkey,
@@ -41,6 +65,39 @@ pub struct OrdinaryReleaseDepositEvent {
     pub amount: u64,
 }
 
+#[event]
+pub struct MergeDepositEntriesEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub target_deposit_entry_index: u8,
+    // amount of the merged deposit entry
+    pub amount: u64,
+    pub lockup: Lockup,
+}
+
+#[event]
+pub struct SplitDepositEntryEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub target_deposit_entry_index: u8,
+    pub amount: u64,
+    pub lockup: Lockup,
+}
+
+#[event]
+pub struct EarlyUnlockEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub amount: u64,
+    pub penalty: u64,
+}
+
 #[event]
 pub struct WithdrawEvent {
     pub registrar: Pubkey,
@@ -58,16 +115,121 @@ pub struct ClaimRewardEvent {
     pub amount: u64,
 }
 
+#[event]
+pub struct CompoundRewardEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub amount: u64,
+    pub lockup: Lockup,
+}
+
+#[event]
+pub struct FundRewardsEvent {
+    pub registrar: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    // total amount of rewards funded so far
+    pub funded_reward_amount: u64,
+}
+
+#[event]
+pub struct GrantEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub amount: u64,
+    pub lockup: Lockup,
+    pub allow_clawback: bool,
+}
+
+#[event]
+pub struct ClawbackEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    pub deposit_entry_index: u8,
+    pub amount: u64,
+}
+
+#[event]
+pub struct DelegateEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    // voter authority address of the delegate
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct UndelegationRequestedEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    // voter authority address of the delegate
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct UndelegateEvent {
+    pub registrar: Pubkey,
+    // voter authority address
+    pub voter: Pubkey,
+    // voter authority address of the delegate
+    pub delegate: Pubkey,
+}
+
 #[event]
 pub struct VoterInfo {
     /// Voter's total voting power
     pub voting_power: u64,
     /// Voter's total voting power, when ignoring any effects from lockup
     pub voting_power_baseline: u64,
+    /// Voter's total reward weight
+    pub reward_weight: u64,
     /// Accumulated reward amount
     pub reward_amount: u64,
-    /// DepositEntry info array
-    pub deposit_entries: [Option<DepositEntryInfo>; VOTER_DEPOSIT_ENTRY_COUNT],
+    /// Amount of funded rewards that were not issued yet
+    pub reward_runway_amount: u64,
+    /// Seconds until the funded rewards are used up at the current emission rate,
+    /// None if no rewards are being issued
+    pub reward_runway_secs: Option<u64>,
+    /// DepositEntry info of every deposit entry of the voter, None if inactive
+    pub deposit_entries: Vec<Option<DepositEntryInfo>>,
+    /// True if the node deposit is below the node security deposit and
+    /// the grace period of the node to top it up has passed, false if the
+    /// node account was not given
+    pub node_non_compliant: bool,
+}
+
+#[event]
+pub struct RegistrarInfo {
+    /// Tokens in the deposit entries of all voters
+    pub deposited_amount: u64,
+    /// Tokens in constant lockups
+    pub permanently_locked_amount: u64,
+    /// Upper bound of the tokens in vesting and cliff lockups. They count as
+    /// locked when their lockups started, vesting since then is only deducted
+    /// once the deposit entry changes again
+    pub max_vesting_locked_amount: u64,
+    /// Amount of rewards issued up to now
+    pub issued_reward_amount: u64,
+    /// Upper bound of the issued rewards that were not claimed yet. Rewards
+    /// claimed before the registrar stats were created are not deducted
+    pub max_unclaimed_reward_amount: u64,
+    /// Amount of funded rewards that were not issued yet
+    pub reward_runway_amount: u64,
+    /// Seconds until the funded rewards are used up at the current emission rate,
+    /// None if no rewards are being issued
+    pub reward_runway_secs: Option<u64>,
+    /// Sum of the reward weights of all voters
+    pub total_reward_weight: u64,
+    /// Number of voters
+    pub voter_count: u64,
+    /// Number of nodes with status 'Active'
+    pub active_node_count: u64,
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
@@ -82,6 +244,8 @@ pub struct DepositEntryInfo {
     pub voting_power: u64,
     /// Voting power without any adjustments for lockup
     pub voting_power_baseline: u64,
+    /// Reward weight implied by this deposit entry
+    pub reward_weight: u64,
     /// Information about vesting, if any
     pub vesting: Option<VestingInfo>,
 }
@@ -25,6 +25,13 @@ pub struct ClaimReward<'info> {
     pub voter: AccountLoader<'info, Voter>,
     pub voter_authority: Signer<'info>,
 
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
     #[account(
         mut,
         token::authority = circuit_breaker,
@@ -55,7 +62,8 @@ pub fn claim_reward(ctx: Context<ClaimReward>, amount: Option<u64>) -> Result<()
     let claimed_amount = {
         // Load the accounts.
         let registrar = &mut ctx.accounts.registrar.load_mut()?;
-        let voter = &mut ctx.accounts.voter.load_mut()?;
+        registrar.assert_not_paused(PAUSE_CLAIMS)?;
+        let voter = &mut ctx.accounts.voter.load_voter_mut()?;
 
         // accrue rewards
         let curr_ts = registrar.clock_unix_timestamp();
@@ -63,11 +71,15 @@ pub fn claim_reward(ctx: Context<ClaimReward>, amount: Option<u64>) -> Result<()
 
         // claim reward
         let claimed_amount = voter.claim_reward(curr_ts, amount, registrar)?;
+        ctx.accounts
+            .registrar_stats
+            .load_mut()?
+            .add_claimed_reward(claimed_amount);
         claimed_amount
     };
 
     let registrar = &ctx.accounts.registrar.load()?;
-    let voter = &ctx.accounts.voter.load()?;
+    let voter = &ctx.accounts.voter.load_voter()?;
 
     transfer_v0(
         CpiContext::new_with_signer(
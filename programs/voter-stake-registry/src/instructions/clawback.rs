@@ -0,0 +1,138 @@
+use crate::error::*;
+use crate::events::ClawbackEvent;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct Clawback<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// Clawbacks must update the voter weight record, to prevent a stale
+    /// record being used to vote after the clawback.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump = voter.load()?.get_voter_weight_record_bump(),
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.get_voter_authority(),
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = registrar.load()?.governing_token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// The realm controlled account receiving the clawed back tokens.
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = realm_authority,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> Clawback<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.vault.to_account_info(),
+            to: self.destination.to_account_info(),
+            authority: self.voter.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Claws back the locked tokens of a granted deposit entry.
+///
+/// The tokens that already vested remain with the voter and can be withdrawn.
+/// Only deposit entries granted with `allow_clawback` can be clawed back.
+/// Clawbacks do not depend on the governance state of the voter, they are
+/// possible while the voter is engaged in a vote, but they are halted while
+/// withdraws are paused.
+///
+/// `deposit_entry_index`: The deposit entry to claw back from.
+pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
+    let amount = {
+        // Load the accounts.
+        let registrar = &mut ctx.accounts.registrar.load_mut()?;
+        registrar.assert_not_paused(PAUSE_WITHDRAWS)?;
+        let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+        require!(
+            voter.is_active(deposit_entry_index)?,
+            VsrError::InactiveDepositEntry
+        );
+
+        // accrue rewards
+        let curr_ts = registrar.clock_unix_timestamp();
+        registrar.accrue_rewards(curr_ts);
+
+        let amount = voter.clawback(deposit_entry_index, curr_ts, registrar)?;
+
+        // Deactivate deposit entry if no funds remains.
+        if voter
+            .deposit_entry_at(deposit_entry_index)?
+            .get_amount_deposited_native()
+            == 0
+        {
+            voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
+        }
+
+        // Update the voter weight record
+        let record = &mut ctx.accounts.voter_weight_record;
+        record.voter_weight = voter.own_vote_weight(curr_ts, registrar)?;
+        record.voter_weight_expiry = Some(Clock::get()?.slot);
+
+        // Record the vote weight
+        record_voter_weight(
+            ctx.accounts.voter_weight_history.as_ref(),
+            &ctx.accounts.registrar.key(),
+            voter,
+            curr_ts,
+            registrar,
+        )?;
+
+        amount
+    };
+
+    // Transfer the locked tokens to the destination.
+    let voter = &ctx.accounts.voter.load_voter()?;
+    token::transfer(
+        ctx.accounts
+            .transfer_ctx()
+            .with_signer(&[voter_seeds!(voter)]),
+        amount,
+    )?;
+
+    emit!(ClawbackEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        amount,
+    });
+
+    Ok(())
+}
@@ -8,6 +8,7 @@ use anchor_spl::token::{self, CloseAccount, Token, TokenAccount};
 // can then be sent back to the sol_destination
 #[derive(Accounts)]
 pub struct CloseVoter<'info> {
+    #[account(mut)]
     pub registrar: AccountLoader<'info, Registrar>,
 
     // checking the PDA address it just an extra precaution,
@@ -17,12 +18,25 @@ pub struct CloseVoter<'info> {
         seeds = [voter.load()?.get_registrar().key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
         bump = voter.load()?.get_voter_bump(),
         constraint = voter_authority.key() == voter.load()?.get_voter_authority(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
         close = sol_destination
     )]
     pub voter: AccountLoader<'info, Voter>,
 
     pub voter_authority: Signer<'info>,
 
+    #[account(
+        mut,
+        seeds = [voter.load()?.get_registrar().key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    /// Required if the voter has a vote weight history, which is closed
+    /// along with it, see `VoterWeightHistory`
+    #[account(mut, close = sol_destination)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
     #[account(mut)]
     /// CHECK:
     pub sol_destination: UncheckedAccount<'info>,
@@ -35,11 +49,24 @@ pub struct CloseVoter<'info> {
 /// Only accounts with no remaining deposits can be closed.
 pub fn close_voter<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVoter<'info>>) -> Result<()> {
     {
-        let voter = &ctx.accounts.voter.load()?;
+        let voter = &mut ctx.accounts.voter.load_voter_mut()?;
 
         let amount = voter.amount_deposited_native();
         require_eq!(amount, 0, VsrError::GoverningTokenNonZero);
         require_eq!(voter.get_reward_claimable_amount(), 0, VsrError::GoverningTokenNonZero);
+        require!(!voter.is_delegated(), VsrError::VoterDelegated);
+        // Delegators need the delegate voter to undelegate with its governance state
+        require_eq!(voter.get_delegator_count(), 0, VsrError::VoterHasDelegators);
+        load_voter_weight_history(
+            ctx.accounts.voter_weight_history.as_ref(),
+            &voter.get_registrar(),
+            voter,
+        )?;
+
+        // A voter created before the deposit totals were introduced has
+        // nothing to add to them, but is no longer uncounted
+        let registrar = &mut ctx.accounts.registrar.load_mut()?;
+        voter.initialize_deposit_totals_if_needed(registrar);
 
         for account in ctx.remaining_accounts.iter() {
             let token = Account::<TokenAccount>::try_from(account).unwrap();
@@ -64,6 +91,11 @@ pub fn close_voter<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVoter<'info>>)
 
             account.exit(ctx.program_id)?;
         }
+
+        // Voters created before the stats were introduced may not be counted
+        if voter.is_counted_in_stats() {
+            ctx.accounts.registrar_stats.load_mut()?.remove_voter();
+        }
     }
 
     Ok(())
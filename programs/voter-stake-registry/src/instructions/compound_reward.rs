@@ -0,0 +1,178 @@
+use crate::error::*;
+use crate::events::CompoundRewardEvent;
+use crate::state::*;
+use crate::{assert_can_change_lockup, deposit_into_ordinary_entry};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use circuit_breaker::cpi::accounts::TransferV0;
+use circuit_breaker::cpi::transfer_v0;
+use circuit_breaker::AccountWindowedCircuitBreakerV0;
+use circuit_breaker::CircuitBreaker;
+use circuit_breaker::TransferArgsV0;
+
+#[derive(Accounts)]
+pub struct CompoundReward<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    /// The reward vault of the registrar.
+    #[account(
+        mut,
+        token::authority = circuit_breaker,
+        token::mint = registrar.load()?.governing_token_mint,
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = ["account_windowed_breaker".as_bytes(), reward_vault.key().as_ref()],
+        seeds::program = circuit_breaker_program.key(),
+        bump = circuit_breaker.bump_seed,
+    )]
+    pub circuit_breaker: Box<Account<'info, AccountWindowedCircuitBreakerV0>>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = registrar.load()?.governing_token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    pub circuit_breaker_program: Program<'info, CircuitBreaker>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Claims rewards and deposits them into an ordinary deposit entry in one go.
+///
+/// Tokens will be transfered from the reward vault to the voter's vault, and
+/// credited to the deposit entry with the same lockup rules as a constant
+/// `ordinary_deposit`.
+///
+/// `deposit_entry_index`: Index of deposit entry.
+/// `amount`: Number of native tokens to compound, all claimable rewards if None.
+/// `duration`: New lockup duration.
+pub fn compound_reward(
+    ctx: Context<CompoundReward>,
+    deposit_entry_index: u8,
+    amount: Option<u64>,
+    duration: LockupTimeDuration,
+) -> Result<()> {
+    let compounded_amount = {
+        // Load the accounts.
+        let registrar = &mut ctx.accounts.registrar.load_mut()?;
+        registrar.assert_not_paused(PAUSE_CLAIMS)?;
+        let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+        // accrue rewards
+        let curr_ts = registrar.clock_unix_timestamp();
+        registrar.accrue_rewards(curr_ts);
+
+        // claim reward
+        voter.claim_reward(curr_ts, amount, registrar)?
+    };
+    require!(compounded_amount > 0, VsrError::ZeroAmount);
+    ctx.accounts
+        .registrar_stats
+        .load_mut()?
+        .add_claimed_reward(compounded_amount);
+
+    {
+        let registrar = &ctx.accounts.registrar.load()?;
+        transfer_v0(
+            CpiContext::new_with_signer(
+                ctx.accounts.circuit_breaker_program.to_account_info(),
+                TransferV0 {
+                    from: ctx.accounts.reward_vault.to_account_info(),
+                    to: ctx.accounts.vault.to_account_info(),
+                    owner: ctx.accounts.registrar.to_account_info(),
+                    circuit_breaker: ctx.accounts.circuit_breaker.to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                },
+                &[registrar_seeds!(registrar)],
+            ),
+            TransferArgsV0 {
+                amount: compounded_amount,
+            },
+        )?;
+    }
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Changing the duration restructures the lockup, which governance may
+    // forbid, for example when engaged in a vote.
+    if voter.is_active(deposit_entry_index)?
+        && voter
+            .deposit_entry_at(deposit_entry_index)?
+            .get_lockup()
+            .kind
+            .duration
+            != duration
+    {
+        assert_can_change_lockup(
+            ctx.accounts.token_owner_record.as_deref(),
+            voter,
+            registrar,
+        )?;
+    }
+
+    deposit_into_ordinary_entry(
+        voter,
+        registrar,
+        deposit_entry_index,
+        compounded_amount,
+        duration,
+        LockupKindKind::Constant,
+    )?;
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        registrar.clock_unix_timestamp(),
+        registrar,
+    )?;
+
+    emit!(CompoundRewardEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        amount: compounded_amount,
+        lockup: voter
+            .deposit_entry_at(deposit_entry_index)?
+            .get_lockup()
+    });
+
+    Ok(())
+}
@@ -86,17 +86,12 @@ pub fn create_registrar(
     max_voter_weight_record_bump: u8,
     voting_config: VotingConfig,
     deposit_config: DepositConfig,
+    emission_config: EmissionConfig,
     circuit_breaker_config: WindowedCircuitBreakerConfigV0,
 ) -> Result<()> {
-    require!(
-        voting_config.lockup_saturation_secs > 0,
-        VsrError::LockupSaturationMustBePositive
-    );
-
-    require!(
-        deposit_config.node_security_deposit > 0,
-        VsrError::NodeSecurityDepositMustBePositive
-    );
+    voting_config.validate()?;
+    deposit_config.validate()?;
+    emission_config.validate()?;
 
     // Verify that "realm_authority" is the expected authority on "realm"
     // and that the mint matches one of the realm mints too.
@@ -150,13 +145,25 @@ pub fn create_registrar(
     registrar.realm_authority = ctx.accounts.realm_authority.key();
     registrar.voting_config = voting_config;
     registrar.deposit_config = deposit_config;
+    registrar.emission_config = emission_config;
     registrar.current_reward_amount_per_second = u128::new(0);
     registrar.last_reward_amount_per_second_rotated_ts = 0;
     registrar.reward_accrual_ts = 0;
     registrar.reward_index = u128::new(0);
     registrar.issued_reward_amount = 0;
     registrar.permanently_locked_amount = 0;
+    registrar.total_reward_weight = 0;
+    registrar.total_reward_weight_initialized = 1;
+    registrar.funded_reward_amount = 0;
+    registrar.funded_reward_amount_initialized = 1;
+    registrar.uncounted_voter_count = 0;
+    registrar.uncounted_voter_count_initialized = 1;
+    registrar.active_node_count = 0;
     registrar.time_offset = 0;
+    registrar.max_vote_weight_mode = MaxVoteWeightMode::Supply;
+    registrar.version = REGISTRAR_VERSION;
+    registrar.total_deposited_amount = 0;
+    registrar.total_locked_amount = 0;
 
     // Initialize MaxVoterWeightRecord 
     let max_voter_weight_record = &mut ctx.accounts.max_voter_weight_record;
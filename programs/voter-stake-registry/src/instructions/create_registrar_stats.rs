@@ -0,0 +1,35 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct CreateRegistrarStats<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        init,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarStats>(),
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the registrar stats of a registrar without any voters created
+/// since they were introduced.
+///
+/// Anyone can create them. Voters and claimed rewards are counted from then
+/// on, see `RegistrarStats`.
+pub fn create_registrar_stats(ctx: Context<CreateRegistrarStats>) -> Result<()> {
+    let stats = &mut ctx.accounts.registrar_stats.load_init()?;
+    stats.registrar = ctx.accounts.registrar.key();
+    stats.bump = ctx.bumps.registrar_stats;
+
+    Ok(())
+}
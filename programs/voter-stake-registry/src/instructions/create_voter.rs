@@ -46,6 +46,16 @@ pub struct CreateVoter<'info> {
     )]
     pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
 
+    /// Created by the first voter of the registrar, see `RegistrarStats`
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarStats>(),
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
     #[account(mut)]
     pub payer: Signer<'info>,
 
@@ -91,5 +101,12 @@ pub fn create_voter(
     voter_weight_record.governing_token_mint = registrar.governing_token_mint;
     voter_weight_record.governing_token_owner = voter_authority;
 
+    let registrar_stats = &mut RegistrarStats::load_init_if_needed(
+        &ctx.accounts.registrar_stats,
+        ctx.accounts.registrar.key(),
+        ctx.bumps.registrar_stats,
+    )?;
+    voter.count_in_stats_if_needed(registrar_stats);
+
     Ok(())
 }
@@ -0,0 +1,49 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct CreateVoterWeightHistory<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        mut,
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        init,
+        seeds = [registrar.key().as_ref(), b"voter-weight-history".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<VoterWeightHistory>(),
+    )]
+    pub voter_weight_history: AccountLoader<'info, VoterWeightHistory>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Creates the vote weight history of a voter, starting with a checkpoint
+/// of its current vote weight.
+///
+/// Anyone can create it. From then on, balance-changing instructions of the
+/// voter require it and record further checkpoints.
+pub fn create_voter_weight_history(ctx: Context<CreateVoterWeightHistory>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+    voter.set_has_weight_history();
+
+    let history = &mut ctx.accounts.voter_weight_history.load_init()?;
+    history.registrar = ctx.accounts.registrar.key();
+    history.voter_authority = voter.get_voter_authority();
+    history.bump = ctx.bumps.voter_weight_history;
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    history.record(voter, curr_ts, registrar)?;
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+use crate::error::*;
+use crate::events::DelegateEvent;
+use crate::state::*;
+use crate::load_token_owner_record;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct Delegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. This is needed
+    /// to be able to forbid delegating while the voter is engaged with
+    /// a vote, since the vote weight would be used twice otherwise.
+    ///
+    /// token_owner_record is validated in the instruction:
+    /// - owned by registrar.governance_program_id
+    /// - for the registrar.realm
+    /// - for the registrar.realm_governing_token_mint
+    /// - governing_token_owner is voter_authority
+    /// CHECK: verified while loading data
+    pub token_owner_record: UncheckedAccount<'info>,
+
+    /// Delegating must reset the voter weight record, to prevent a stale
+    /// record being used to vote after the delegation.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_weight_record_bump(),
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.get_voter_authority(),
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    /// The voter receiving the vote weight.
+    #[account(
+        mut,
+        constraint = delegate_voter.key() != voter.key() @ VsrError::InvalidDelegate,
+        constraint = delegate_voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub delegate_voter: AccountLoader<'info, Voter>,
+
+    /// Required if the delegate voter has a vote weight history, see
+    /// `VoterWeightHistory`
+    #[account(mut)]
+    pub delegate_voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        init,
+        seeds = [registrar.key().as_ref(), b"delegation-record".as_ref(), voter_authority.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<DelegationRecord>(),
+    )]
+    pub delegation_record: AccountLoader<'info, DelegationRecord>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Delegates the full vote weight of the voter to the delegate voter.
+///
+/// The tokens stay in the voter's vault. Until `undelegate` is called, the
+/// vote weight is added to the delegate's voter weight record and the voter
+/// can not vote with it by itself.
+///
+/// The delegate voter must not delegate its own vote weight, and a voter
+/// that vote weight is delegated to can not delegate, since the vote weight
+/// delegated to it would be dropped.
+pub fn delegate(ctx: Context<Delegate>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+    let delegate_voter = &mut ctx.accounts.delegate_voter.load_voter_mut()?;
+    require!(!delegate_voter.is_delegated(), VsrError::InvalidDelegate);
+    require_eq!(voter.get_delegator_count(), 0, VsrError::VoterHasDelegators);
+
+    // Governance may forbid delegating, for example when engaged in a vote.
+    let token_owner_record = load_token_owner_record(
+        &ctx.accounts.token_owner_record.to_account_info(),
+        voter,
+        registrar,
+    )?;
+    token_owner_record.assert_can_withdraw_governing_tokens()?;
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    let delegated_weight = voter.weight(curr_ts, registrar)?;
+    voter.delegate()?;
+    delegate_voter.add_delegator();
+
+    let delegation_record = &mut ctx.accounts.delegation_record.load_init()?;
+    delegation_record.registrar = ctx.accounts.registrar.key();
+    delegation_record.delegator = voter.get_voter_authority();
+    delegation_record.delegate = delegate_voter.get_voter_authority();
+    delegation_record.bump = ctx.bumps.delegation_record;
+    delegation_record.delegated_weight = delegated_weight;
+
+    // Update the voter weight record
+    let record = &mut ctx.accounts.voter_weight_record;
+    record.voter_weight = 0;
+    record.voter_weight_expiry = Some(Clock::get()?.slot);
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+    if let Some(history) = &mut load_voter_weight_history(
+        ctx.accounts.delegate_voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        delegate_voter,
+    )? {
+        history.add_delegated_weight(delegate_voter, curr_ts, registrar, delegated_weight)?;
+        delegation_record.counted_in_weight_history = 1;
+    }
+
+    emit!(DelegateEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        delegate: delegate_voter.get_voter_authority(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use crate::error::*;
+use crate::events::EarlyUnlockEvent;
+use crate::state::*;
+use crate::{load_token_owner_record, NODE_DEPOSIT_ENTRY_INDEX};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct EarlyUnlock<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. This is needed
+    /// to be able to forbid early unlocks while the voter is engaged with
+    /// a vote or has an open proposal.
+    ///
+    /// token_owner_record is validated in the instruction:
+    /// - owned by registrar.governance_program_id
+    /// - for the registrar.realm
+    /// - for the registrar.realm_governing_token_mint
+    /// - governing_token_owner is voter_authority
+    /// CHECK: verified while loading data
+    pub token_owner_record: UncheckedAccount<'info>,
+
+    /// Early unlocks must update the voter weight record, to prevent a stale
+    /// record being used to vote after the unlock.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_weight_record_bump(),
+        constraint = voter_weight_record.realm == registrar.load()?.realm,
+        constraint = voter_weight_record.governing_token_owner == voter.load()?.get_voter_authority(),
+        constraint = voter_weight_record.governing_token_mint == registrar.load()?.governing_token_mint,
+    )]
+    pub voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = registrar.load()?.governing_token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// The reward vault of the registrar, receiving the penalty.
+    #[account(
+        mut,
+        address = get_associated_token_address(&registrar.key(), &registrar.load()?.governing_token_mint),
+    )]
+    pub reward_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+    )]
+    pub destination: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> EarlyUnlock<'info> {
+    pub fn transfer_ctx(
+        &self,
+        to: AccountInfo<'info>,
+    ) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.vault.to_account_info(),
+            to,
+            authority: self.voter.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Unlocks tokens of a constant lockup right away, instead of releasing
+/// them into a vesting schedule through `ordinary_release_deposit`.
+///
+/// A penalty, growing with the remaining lockup time according to the
+/// registrar's `EarlyUnlockPenaltyConfig`, is transferred to the reward vault
+/// and funds staking rewards on top of the emission schedule. The rest is
+/// transferred to the destination.
+///
+/// `deposit_entry_index`: The constant deposit entry to unlock from.
+/// `amount`: Number of native tokens to unlock, including the penalty.
+pub fn early_unlock(ctx: Context<EarlyUnlock>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+    require!(amount > 0, VsrError::ZeroAmount);
+    require!(
+        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+        VsrError::NodeDepositReservedEntryIndex
+    );
+
+    let penalty = {
+        let registrar = &ctx.accounts.registrar.load()?;
+        registrar.assert_not_paused(PAUSE_WITHDRAWS)?;
+        let penalty_config = registrar.early_unlock_penalty_config;
+        require!(penalty_config.is_enabled(), VsrError::EarlyUnlockDisabled);
+
+        let voter = &ctx.accounts.voter.load_voter()?;
+        // The delegate's voter weight record may still count the delegated tokens.
+        require!(!voter.is_delegated(), VsrError::VoterDelegated);
+
+        let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
+        require!(d_entry.is_active(), VsrError::InactiveDepositEntry);
+        let lockup = d_entry.get_lockup();
+        require!(
+            lockup.kind.kind == LockupKindKind::Constant,
+            VsrError::NotOrdinaryDepositEntry
+        );
+        require_gte!(
+            d_entry.get_amount_deposited_native(),
+            amount,
+            VsrError::InsufficientLockedTokens
+        );
+
+        let curr_ts = registrar.clock_unix_timestamp();
+        let penalty = penalty_config.penalty(amount, lockup.seconds_left(curr_ts));
+
+        // Transfer the unlocked tokens and the penalty.
+        token::transfer(
+            ctx.accounts
+                .transfer_ctx(ctx.accounts.destination.to_account_info())
+                .with_signer(&[voter_seeds!(voter)]),
+            amount - penalty,
+        )?;
+        if penalty > 0 {
+            token::transfer(
+                ctx.accounts
+                    .transfer_ctx(ctx.accounts.reward_vault.to_account_info())
+                    .with_signer(&[voter_seeds!(voter)]),
+                penalty,
+            )?;
+        }
+        penalty
+    };
+
+    // Load the accounts.
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Governance may forbid early unlocks, for example when engaged in a vote.
+    let token_owner_record = load_token_owner_record(
+        &ctx.accounts.token_owner_record.to_account_info(),
+        voter,
+        registrar,
+    )?;
+    token_owner_record.assert_can_withdraw_governing_tokens()?;
+
+    // accrue rewards
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    // Deactivating settles the voter's rewards and the permanently locked
+    // amount, the remaining tokens stay locked up as before.
+    let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
+    let lockup = d_entry.get_lockup();
+    let amount_deposited_native = d_entry.get_amount_deposited_native();
+    voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
+    if amount_deposited_native > amount {
+        voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
+        voter.deposit(
+            deposit_entry_index,
+            curr_ts,
+            amount_deposited_native - amount,
+            registrar,
+        )?;
+    }
+
+    // The penalty funds staking rewards
+    registrar.add_penalty_reward_amount(penalty);
+
+    // Update the voter weight record
+    let record = &mut ctx.accounts.voter_weight_record;
+    record.voter_weight = voter.weight(curr_ts, registrar)?;
+    record.voter_weight_expiry = Some(Clock::get()?.slot);
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(EarlyUnlockEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        amount,
+        penalty,
+    });
+
+    Ok(())
+}
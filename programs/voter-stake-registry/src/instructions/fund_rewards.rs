@@ -0,0 +1,76 @@
+use crate::error::*;
+use crate::events::FundRewardsEvent;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::{self, Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// The reward vault of the registrar.
+    #[account(
+        mut,
+        address = get_associated_token_address(&registrar.key(), &registrar.load()?.governing_token_mint),
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = vault.mint,
+        token::authority = funding_authority,
+    )]
+    pub funding_token: Box<Account<'info, TokenAccount>>,
+    pub funding_authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> FundRewards<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.funding_token.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.funding_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Funds staking rewards.
+///
+/// Tokens will be transfered from funding_token to the reward vault using the
+/// funding_authority, and added to the funded reward amount of the registrar.
+///
+/// Rewards are accrued before the funds are added, so rewards that could not
+/// be issued for the lack of funds are not issued afterwards.
+///
+/// `amount`: Number of native tokens to transfer.
+pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+    require!(amount > 0, VsrError::ZeroAmount);
+
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+
+    // accrue rewards
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    registrar.funded_reward_amount = registrar
+        .funded_reward_amount
+        .checked_add(amount)
+        .unwrap();
+
+    emit!(FundRewardsEvent {
+        registrar: ctx.accounts.registrar.key(),
+        funder: ctx.accounts.funding_authority.key(),
+        amount,
+        funded_reward_amount: registrar.funded_reward_amount,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,210 @@
+use crate::error::*;
+use crate::events::GrantEvent;
+use crate::state::*;
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct Grant<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+        has_one = governing_token_mint,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+    pub governing_token_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump,
+        payer = payer,
+        // existing voters may have been resized, see resize_voter
+        space = voter.data_len().max(8 + size_of::<Voter>()),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// The authority of the voter receiving the grant.
+    /// CHECK: the grantee does not need to sign
+    pub voter_authority: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter_authority.key().as_ref()],
+        bump,
+        payer = payer,
+        space = size_of::<VoterWeightRecord>(),
+    )]
+    pub voter_weight_record: Box<Account<'info, VoterWeightRecord>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    /// Created by the first voter of the registrar, see `RegistrarStats`
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarStats>(),
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    #[account(
+        init_if_needed,
+        associated_token::authority = voter,
+        associated_token::mint = governing_token_mint,
+        payer = payer,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::authority = realm_authority,
+        associated_token::mint = governing_token_mint,
+    )]
+    pub deposit_token: Box<Account<'info, TokenAccount>>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> Grant<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.deposit_token.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.realm_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Funds a vesting deposit entry on the voter of voter_authority, creating
+/// the voter if needed. Only the realm authority can grant.
+///
+/// Tokens will be transfered from deposit_token to vault using the realm_authority.
+///
+/// `deposit_entry_index`: Index of an inactive deposit entry.
+/// `kind`: Daily or monthly vesting.
+/// `periods`: Number of vesting periods.
+/// `start_ts`: Start of the vesting, now if None.
+/// `amount`: Number of native tokens to grant.
+/// `allow_clawback`: Whether the realm authority may claw back the locked tokens later.
+pub fn grant(
+    ctx: Context<Grant>,
+    deposit_entry_index: u8,
+    kind: LockupKindKind,
+    periods: u64,
+    start_ts: Option<u64>,
+    amount: u64,
+    allow_clawback: bool,
+) -> Result<()> {
+    require!(amount > 0, VsrError::ZeroAmount);
+    require!(
+        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+        VsrError::NodeDepositReservedEntryIndex
+    );
+    let lockup_kind = match kind {
+        LockupKindKind::Daily => LockupKind::daily(periods),
+        LockupKindKind::Monthly => LockupKind::monthly(periods),
+        LockupKindKind::Weekly => LockupKind::weekly(periods),
+        LockupKindKind::Yearly => LockupKind::yearly(periods),
+        LockupKindKind::Constant | LockupKindKind::Cliff => {
+            return Err(error!(VsrError::InvalidLockupKind))
+        }
+    };
+    require!(periods > 0, VsrError::InvalidLockupPeriod);
+    ctx.accounts
+        .registrar
+        .load()?
+        .assert_not_paused(PAUSE_DEPOSITS)?;
+
+    // Deposit tokens into the vault
+    token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter_authority = ctx.accounts.voter_authority.key();
+
+    // accrue rewards
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    // Create the voter on its first grant
+    let voter = &mut match ctx.accounts.voter.load_voter_init() {
+        Ok(mut voter) => {
+            *voter = Voter::new(
+                voter_authority,
+                ctx.accounts.registrar.key(),
+                registrar.reward_index,
+                ctx.bumps.voter,
+                ctx.bumps.voter_weight_record,
+            );
+            let registrar_stats = &mut RegistrarStats::load_init_if_needed(
+                &ctx.accounts.registrar_stats,
+                ctx.accounts.registrar.key(),
+                ctx.bumps.registrar_stats,
+            )?;
+            voter.count_in_stats_if_needed(registrar_stats);
+            voter
+        }
+        Err(_) => ctx.accounts.voter.load_voter_mut()?,
+    };
+
+    let voter_weight_record = &mut ctx.accounts.voter_weight_record;
+    if voter_weight_record.realm == Pubkey::default() {
+        voter_weight_record.account_discriminator =
+            spl_governance_addin_api::voter_weight::VoterWeightRecord::ACCOUNT_DISCRIMINATOR;
+        voter_weight_record.realm = registrar.realm;
+        voter_weight_record.governing_token_mint = registrar.governing_token_mint;
+        voter_weight_record.governing_token_owner = voter_authority;
+    }
+
+    require!(
+        !voter.is_active(deposit_entry_index)?,
+        VsrError::ActiveDepositEntryIndex
+    );
+
+    let start_ts = match start_ts {
+        Some(start_ts) => i64::try_from(start_ts).unwrap(),
+        None => curr_ts,
+    };
+    let lockup = Lockup::new_from_kind(lockup_kind, curr_ts, start_ts)?;
+
+    voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
+    voter.deposit(deposit_entry_index, curr_ts, amount, registrar)?;
+    if allow_clawback {
+        voter.allow_clawback(deposit_entry_index)?;
+    }
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(GrantEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter_authority,
+        deposit_entry_index,
+        amount,
+        lockup,
+        allow_clawback,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use crate::events::*;
+use crate::registrar_view;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct LogRegistrarInfo<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+}
+
+/// A no-effect instruction that logs registrar-wide statistics.
+pub fn log_registrar_info(ctx: Context<LogRegistrarInfo>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let stats = &ctx.accounts.registrar_stats.load()?;
+
+    emit!(RegistrarInfo::from(registrar_view(registrar, stats)));
+
+    Ok(())
+}
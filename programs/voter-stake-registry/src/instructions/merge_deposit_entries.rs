@@ -0,0 +1,122 @@
+use crate::error::*;
+use crate::events::MergeDepositEntriesEvent;
+use crate::state::*;
+use crate::{assert_can_change_lockup, NODE_DEPOSIT_ENTRY_INDEX};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct MergeDepositEntries<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+}
+
+/// Merges the constant lockup deposit entry at `deposit_entry_index` into the
+/// one at `target_deposit_entry_index`.
+///
+/// The merged deposit entry is locked for the longer duration of the two, so
+/// the lockup of no token is shortened. The source deposit entry is left
+/// inactive.
+pub fn merge_deposit_entries(
+    ctx: Context<MergeDepositEntries>,
+    deposit_entry_index: u8,
+    target_deposit_entry_index: u8,
+) -> Result<()> {
+    require!(
+        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX
+            && target_deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+        VsrError::NodeDepositReservedEntryIndex
+    );
+    require_neq!(
+        deposit_entry_index,
+        target_deposit_entry_index,
+        VsrError::SameDepositEntryIndex
+    );
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.assert_not_paused(PAUSE_RELEASES)?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Governance may forbid lockup changes, for example when engaged in a vote.
+    assert_can_change_lockup(
+        ctx.accounts.token_owner_record.as_deref(),
+        voter,
+        registrar,
+    )?;
+
+    let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
+    let target_d_entry = voter.deposit_entry_at(target_deposit_entry_index)?;
+    require!(
+        d_entry.is_active() && target_d_entry.is_active(),
+        VsrError::InactiveDepositEntry
+    );
+
+    let duration = d_entry.get_lockup().kind.duration;
+    let target_duration = target_d_entry.get_lockup().kind.duration;
+    require!(
+        d_entry.get_lockup().kind.kind == LockupKindKind::Constant
+            && target_d_entry.get_lockup().kind.kind == LockupKindKind::Constant,
+        VsrError::NotOrdinaryDepositEntry
+    );
+
+    let amount = d_entry
+        .get_amount_deposited_native()
+        .checked_add(target_d_entry.get_amount_deposited_native())
+        .unwrap();
+    let curr_ts = registrar.clock_unix_timestamp();
+    let merged_duration = if duration.seconds(curr_ts) > target_duration.seconds(curr_ts) {
+        duration
+    } else {
+        target_duration
+    };
+
+    // accrue rewards
+    registrar.accrue_rewards(curr_ts);
+
+    let lockup = Lockup::new_from_kind(LockupKind::constant(merged_duration), curr_ts, curr_ts)?;
+    voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
+    voter.deactivate(target_deposit_entry_index, curr_ts, registrar)?;
+    voter.activate(target_deposit_entry_index, curr_ts, lockup, registrar)?;
+    voter.deposit(target_deposit_entry_index, curr_ts, amount, registrar)?;
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(MergeDepositEntriesEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        target_deposit_entry_index,
+        amount,
+        lockup,
+    });
+
+    Ok(())
+}
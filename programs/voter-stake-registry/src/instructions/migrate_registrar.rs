@@ -0,0 +1,84 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+
+#[derive(Accounts)]
+pub struct MigrateRegistrar<'info> {
+    /// Not loaded before it is reallocated, older layouts may be shorter.
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// The reward vault of the registrar, its balance funds the staking
+    /// rewards of registrars created before 'fund_rewards' was introduced.
+    /// Its address is checked once the registrar is reallocated.
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// Bounds the rewards the vault still owes to voters
+    #[account(
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Brings the registrar to the current layout version.
+///
+/// The account is grown to the current size first, the payer pays for the
+/// additional rent. Anyone can migrate, see `Registrar::migrate`. The
+/// registrar stats must exist, see 'create_registrar_stats'.
+pub fn migrate_registrar(ctx: Context<MigrateRegistrar>) -> Result<()> {
+    grow_account(
+        &ctx.accounts.registrar.to_account_info(),
+        8 + std::mem::size_of::<Registrar>(),
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let registrar_stats = &ctx.accounts.registrar_stats.load()?;
+    require_keys_eq!(
+        ctx.accounts.vault.key(),
+        get_associated_token_address(&ctx.accounts.registrar.key(), &registrar.governing_token_mint),
+        VsrError::InvalidRewardVault
+    );
+    registrar.migrate(ctx.accounts.vault.amount, registrar_stats)
+}
+
+/// Reallocates the account to `min_len` bytes unless it is that long already,
+/// the payer tops up the rent. The added bytes are zeroed.
+pub fn grow_account<'info>(
+    account_info: &AccountInfo<'info>,
+    min_len: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    if account_info.data_len() >= min_len {
+        return Ok(());
+    }
+
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(min_len);
+    let lamports = account_info.lamports();
+    if rent_exempt_lamports > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: account_info.clone(),
+                },
+            ),
+            rent_exempt_lamports - lamports,
+        )?;
+    }
+
+    account_info.realloc(min_len, true)?;
+    Ok(())
+}
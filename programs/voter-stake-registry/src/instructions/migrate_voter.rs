@@ -0,0 +1,68 @@
+use crate::error::*;
+use crate::grow_account;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct MigrateVoter<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// Not loaded before it is reallocated, older layouts may be shorter.
+    #[account(mut)]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// Counts voters created before the stats were introduced, see
+    /// `RegistrarStats`
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarStats>(),
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Brings the voter and its deposit entries to the current layout versions.
+///
+/// The account is grown to the current size first, the payer pays for the
+/// additional rent. Anyone can migrate, see `LoadedVoter::migrate`.
+///
+/// Voters created before the registrar stats were introduced are counted in
+/// them, which may also be the only reason to migrate a current voter.
+pub fn migrate_voter(ctx: Context<MigrateVoter>) -> Result<()> {
+    grow_account(
+        &ctx.accounts.voter.to_account_info(),
+        8 + std::mem::size_of::<Voter>(),
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+    )?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+    require_keys_eq!(
+        voter.get_registrar(),
+        ctx.accounts.registrar.key(),
+        VsrError::InvalidAuthority
+    );
+
+    // accrue rewards
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    let registrar_stats = &mut RegistrarStats::load_init_if_needed(
+        &ctx.accounts.registrar_stats,
+        ctx.accounts.registrar.key(),
+        ctx.bumps.registrar_stats,
+    )?;
+    voter.count_in_stats_if_needed(registrar_stats);
+
+    voter.migrate(curr_ts, registrar)
+}
@@ -1,31 +1,107 @@
 pub use claim_reward::*;
+pub use clawback::*;
 pub use close_voter::*;
+pub use compound_reward::*;
 pub use create_registrar::*;
+pub use create_registrar_stats::*;
 pub use create_voter::*;
+pub use create_voter_weight_history::*;
+pub use delegate::*;
+pub use early_unlock::*;
+pub use fund_rewards::*;
+pub use grant::*;
+pub use log_registrar_info::*;
 pub use log_voter_info::*;
+pub use merge_deposit_entries::*;
+pub use migrate_registrar::*;
+pub use migrate_voter::*;
 pub use node_deposit::*;
 pub use node_release_deposit::*;
+pub use node_top_up_deposit::*;
 pub use ordinary_deposit::*;
 pub use ordinary_release_deposit::*;
+pub use refresh_reward_weight::*;
+pub use request_undelegate::*;
+pub use resize_voter::*;
+pub use set_node_status::*;
+pub use set_paused::*;
 pub use set_time_offset::*;
+pub use slash_node_deposit::*;
+pub use split_deposit_entry::*;
+pub use undelegate::*;
 pub use update_deposit_config::*;
+pub use update_early_unlock_penalty_config::*;
+pub use update_emission_config::*;
+pub use update_guardian::*;
 pub use update_max_vote_weight::*;
+pub use update_max_vote_weight_mode::*;
+pub use update_max_voting_time::*;
+pub use update_node::*;
+pub use update_node_compliance::*;
+pub use update_registrar_authority::*;
+pub use update_release_cliff_config::*;
+pub use update_restrict_lockup_changes::*;
+pub use update_reward_boost_config::*;
+pub use update_slasher::*;
+pub use update_uncounted_voter_count::*;
+pub use update_voter_weight_action_requirement::*;
 pub use update_voter_weight_record::*;
 pub use update_voting_config::*;
+pub use view_registrar_info::*;
+pub use view_voter_info::*;
+pub use view_voter_weight_at::*;
 pub use withdraw::*;
 
 mod claim_reward;
+mod clawback;
 mod close_voter;
+mod compound_reward;
 mod create_registrar;
+mod create_registrar_stats;
 mod create_voter;
+mod create_voter_weight_history;
+mod delegate;
+mod early_unlock;
+mod fund_rewards;
+mod grant;
+mod log_registrar_info;
 mod log_voter_info;
+mod merge_deposit_entries;
+mod migrate_registrar;
+mod migrate_voter;
 mod node_deposit;
 mod node_release_deposit;
+mod node_top_up_deposit;
 mod ordinary_deposit;
 mod ordinary_release_deposit;
+mod refresh_reward_weight;
+mod request_undelegate;
+mod resize_voter;
+mod set_node_status;
+mod set_paused;
 mod set_time_offset;
+mod slash_node_deposit;
+mod split_deposit_entry;
+mod undelegate;
 mod update_deposit_config;
+mod update_early_unlock_penalty_config;
+mod update_emission_config;
+mod update_guardian;
 mod update_max_vote_weight;
+mod update_max_vote_weight_mode;
+mod update_max_voting_time;
+mod update_node;
+mod update_node_compliance;
+mod update_registrar_authority;
+mod update_release_cliff_config;
+mod update_restrict_lockup_changes;
+mod update_reward_boost_config;
+mod update_slasher;
+mod update_uncounted_voter_count;
+mod update_voter_weight_action_requirement;
 mod update_voter_weight_record;
 mod update_voting_config;
+mod view_registrar_info;
+mod view_voter_info;
+mod view_voter_weight_at;
 mod withdraw;
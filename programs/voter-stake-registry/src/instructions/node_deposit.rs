@@ -1,6 +1,7 @@
 use crate::{error::VsrError, events::NodeDepositEvent, state::*};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
+use std::mem::size_of;
 
 /// Deposit entry index for node deposit
 pub const NODE_DEPOSIT_ENTRY_INDEX: u8 = 0;
@@ -20,6 +21,10 @@ pub struct NodeDeposit<'info> {
     )]
     pub voter: AccountLoader<'info, Voter>,
 
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
     #[account(
         mut,
         associated_token::authority = voter,
@@ -35,6 +40,19 @@ pub struct NodeDeposit<'info> {
     pub deposit_token: Box<Account<'info, TokenAccount>>,
     pub deposit_authority: Signer<'info>,
 
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<Node>(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
 }
 
@@ -53,9 +71,12 @@ impl<'info> NodeDeposit<'info> {
 /// Deposit tokens and become a node.
 ///
 /// Tokens will be transfered from deposit_token to vault using the deposit_authority.
+/// The node is registered as active, unless it is still jailed from a slashed
+/// node deposit.
 pub fn node_deposit(ctx: Context<NodeDeposit>) -> Result<()> {
     {
         let registrar = &ctx.accounts.registrar.load()?;
+        registrar.assert_not_paused(PAUSE_DEPOSITS)?;
         let node_security_deposit = registrar.deposit_config.node_security_deposit;
 
         // Deposit tokens into the vault
@@ -63,7 +84,7 @@ pub fn node_deposit(ctx: Context<NodeDeposit>) -> Result<()> {
     }
 
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
-    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
     require!(
         !(voter.is_active(NODE_DEPOSIT_ENTRY_INDEX)?),
         VsrError::DuplicateNodeDeposit
@@ -86,6 +107,31 @@ pub fn node_deposit(ctx: Context<NodeDeposit>) -> Result<()> {
     )?;
     voter.deposit(NODE_DEPOSIT_ENTRY_INDEX, curr_ts, node_security_deposit, registrar)?;
 
+    // Register the node, a jailed node stays jailed
+    match ctx.accounts.node.load_init() {
+        Ok(mut node) => node.init(
+            ctx.accounts.registrar.key(),
+            voter.get_voter_authority(),
+            ctx.bumps.node,
+            curr_ts,
+            registrar,
+        ),
+        Err(_) => ctx.accounts.node.load_mut()?.update_compliance(
+            node_security_deposit,
+            curr_ts,
+            registrar,
+        ),
+    }
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
     emit!(NodeDepositEvent {
         registrar: ctx.accounts.registrar.key(),
         voter: voter.get_voter_authority(),
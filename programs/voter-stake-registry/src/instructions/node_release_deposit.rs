@@ -1,8 +1,9 @@
 use crate::error::*;
 use crate::events::NodeReleaseDepositEvent;
 use crate::state::*;
-use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use crate::{assert_can_change_lockup, NODE_DEPOSIT_ENTRY_INDEX};
 use anchor_lang::prelude::*;
+use std::mem::size_of;
 
 #[derive(Accounts)]
 pub struct NodeReleaseDeposit<'info> {
@@ -19,7 +20,31 @@ pub struct NodeReleaseDeposit<'info> {
         constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
     )]
     pub voter: AccountLoader<'info, Voter>,
+
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+    #[account(mut)]
     pub voter_authority: Signer<'info>,
+
+    /// Created if the node deposit was made before nodes were registered,
+    /// see `Node`
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter_authority.key().as_ref()],
+        bump,
+        payer = voter_authority,
+        space = 8 + size_of::<Node>(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    pub system_program: Program<'info, System>,
 }
 
 pub fn node_release_deposit(
@@ -27,7 +52,15 @@ pub fn node_release_deposit(
     target_deposit_entry_index: u8,
 ) -> Result<()> {
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
-    let voter = &mut ctx.accounts.voter.load_mut()?;
+    registrar.assert_not_paused(PAUSE_RELEASES)?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Governance may forbid lockup changes, for example when engaged in a vote.
+    assert_can_change_lockup(
+        ctx.accounts.token_owner_record.as_deref(),
+        voter,
+        registrar,
+    )?;
 
     let d_entry = voter.deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?;
     require!(d_entry.is_active(), VsrError::InactiveDepositEntry);
@@ -50,7 +83,23 @@ pub fn node_release_deposit(
 
         voter.deactivate(NODE_DEPOSIT_ENTRY_INDEX, curr_ts, registrar)?;
 
-        let target_lockup = Lockup::new_from_duration(lockup_kind.duration, curr_ts, curr_ts)?;
+        // Unregister and close the node
+        if ctx.accounts.node.load_init().is_err() {
+            ctx.accounts
+                .node
+                .load_mut()?
+                .set_status(NodeStatus::Exiting, false, registrar)?;
+        }
+        ctx.accounts
+            .node
+            .close(ctx.accounts.voter_authority.to_account_info())?;
+
+        let target_lockup = Lockup::new_from_duration(
+            lockup_kind.duration,
+            registrar.release_cliff_config.node_release_cliff_periods as u64,
+            curr_ts,
+            curr_ts,
+        )?;
 
         voter.activate(
             target_deposit_entry_index,
@@ -65,6 +114,15 @@ pub fn node_release_deposit(
             registrar,
         )?;
 
+        // Record the vote weight
+        record_voter_weight(
+            ctx.accounts.voter_weight_history.as_ref(),
+            &ctx.accounts.registrar.key(),
+            voter,
+            curr_ts,
+            registrar,
+        )?;
+
         emit!(NodeReleaseDepositEvent {
         registrar: ctx.accounts.registrar.key(),
             voter: voter.get_voter_authority(),
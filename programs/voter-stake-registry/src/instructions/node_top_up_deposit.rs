@@ -0,0 +1,143 @@
+use crate::{error::VsrError, events::NodeTopUpDepositEvent, state::*, NODE_DEPOSIT_ENTRY_INDEX};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount};
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct NodeTopUpDeposit<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter.load()?.get_voter_authority().key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key()
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = registrar.load()?.governing_token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        associated_token::authority = deposit_authority,
+        associated_token::mint = vault.mint,
+    )]
+    pub deposit_token: Box<Account<'info, TokenAccount>>,
+    pub deposit_authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<Node>(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> NodeTopUpDeposit<'info> {
+    pub fn transfer_ctx(&self) -> CpiContext<'_, '_, '_, 'info, token::Transfer<'info>> {
+        let program = self.token_program.to_account_info();
+        let accounts = token::Transfer {
+            from: self.deposit_token.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.deposit_authority.to_account_info(),
+        };
+        CpiContext::new(program, accounts)
+    }
+}
+
+/// Tops up the node deposit to the current node security deposit, after
+/// `update_deposit_config` raised it.
+///
+/// Tokens will be transfered from deposit_token to vault using the deposit_authority.
+/// The node is compliant again afterwards. Nodes that made their node deposit
+/// before nodes were registered on-chain are registered by their top-up.
+pub fn node_top_up_deposit(ctx: Context<NodeTopUpDeposit>) -> Result<()> {
+    let amount = {
+        let registrar = &ctx.accounts.registrar.load()?;
+        registrar.assert_not_paused(PAUSE_DEPOSITS)?;
+        let voter = &ctx.accounts.voter.load_voter()?;
+        require!(
+            voter.is_active(NODE_DEPOSIT_ENTRY_INDEX)?,
+            VsrError::InactiveDepositEntry
+        );
+
+        let amount = registrar.deposit_config.node_security_deposit.saturating_sub(
+            voter
+                .deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?
+                .get_amount_deposited_native(),
+        );
+        require!(amount > 0, VsrError::NodeSecurityDepositSatisfied);
+
+        // Deposit tokens into the vault
+        token::transfer(ctx.accounts.transfer_ctx(), amount)?;
+        amount
+    };
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // accrue rewards
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    voter.deposit(NODE_DEPOSIT_ENTRY_INDEX, curr_ts, amount, registrar)?;
+
+    let node = &mut match ctx.accounts.node.load_init() {
+        Ok(mut node) => {
+            node.init(
+                ctx.accounts.registrar.key(),
+                voter.get_voter_authority(),
+                ctx.bumps.node,
+                curr_ts,
+                registrar,
+            );
+            node
+        }
+        Err(_) => ctx.accounts.node.load_mut()?,
+    };
+    node.update_compliance(
+        voter
+            .deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?
+            .get_amount_deposited_native(),
+        curr_ts,
+        registrar,
+    );
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(NodeTopUpDepositEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        amount,
+        lockup: voter.deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?.get_lockup()
+    });
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use crate::{error::VsrError, events::OrdinaryDepositEvent, state::*, NODE_DEPOSIT_ENTRY_INDEX};
+use crate::{
+    assert_can_change_lockup, error::VsrError, events::OrdinaryDepositEvent, state::*,
+    NODE_DEPOSIT_ENTRY_INDEX,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount};
 
@@ -17,6 +20,16 @@ pub struct OrdinaryDeposit<'info> {
     )]
     pub voter: AccountLoader<'info, Voter>,
 
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
     #[account(
         mut,
         associated_token::authority = voter,
@@ -51,19 +64,24 @@ impl<'info> OrdinaryDeposit<'info> {
 ///
 /// Tokens will be transfered from deposit_token to vault using the deposit_authority.
 ///
+/// 'ordinary_deposit' deposits into constant lockups, 'ordinary_cliff_deposit'
+/// into cliff lockups.
+///
 /// `deposit_entry_index`: Index of deposit entry.
 /// `amount`: Number of native tokens to transfer.
 /// `duration`: New lockup duration.
+/// `lockup_kind`: Constant or cliff lockup.
 pub fn ordinary_deposit(
     ctx: Context<OrdinaryDeposit>,
     deposit_entry_index: u8,
     amount: u64,
     duration: LockupTimeDuration,
+    lockup_kind: LockupKindKind,
 ) -> Result<()> {
-    require!(
-        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
-        VsrError::NodeDepositReservedEntryIndex
-    );
+    ctx.accounts
+        .registrar
+        .load()?
+        .assert_not_paused(PAUSE_DEPOSITS)?;
 
     if amount > 0 {
         // Deposit tokens into the vault
@@ -71,18 +89,94 @@ pub fn ordinary_deposit(
     }
 
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
-    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Changing the duration restructures the lockup, which governance may
+    // forbid, for example when engaged in a vote.
+    if voter.is_active(deposit_entry_index)?
+        && voter
+            .deposit_entry_at(deposit_entry_index)?
+            .get_lockup()
+            .kind
+            .duration
+            != duration
+    {
+        assert_can_change_lockup(
+            ctx.accounts.token_owner_record.as_deref(),
+            voter,
+            registrar,
+        )?;
+    }
+
+    deposit_into_ordinary_entry(
+        voter,
+        registrar,
+        deposit_entry_index,
+        amount,
+        duration,
+        lockup_kind,
+    )?;
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        registrar.clock_unix_timestamp(),
+        registrar,
+    )?;
+
+    emit!(OrdinaryDepositEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        amount,
+        lockup: voter
+            .deposit_entry_at(deposit_entry_index)?
+            .get_lockup()
+    });
+
+    Ok(())
+}
+
+/// Credits `amount` tokens, which are already in the voter's vault, to a
+/// constant or cliff lockup deposit entry with the lockup rules of ordinary
+/// deposits.
+///
+/// An inactive deposit entry is activated with `duration`. The duration of an
+/// active constant one can only be extended. The duration of an active cliff
+/// one can not change, every deposit restarts its cliff.
+pub fn deposit_into_ordinary_entry(
+    voter: &mut VoterRefMut,
+    registrar: &mut Registrar,
+    deposit_entry_index: u8,
+    amount: u64,
+    duration: LockupTimeDuration,
+    lockup_kind: LockupKindKind,
+) -> Result<()> {
+    require!(
+        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+        VsrError::NodeDepositReservedEntryIndex
+    );
+    let curr_ts = registrar.clock_unix_timestamp();
     require_gte!(
-        duration.seconds(),
+        duration.seconds(curr_ts),
         registrar
             .deposit_config
             .ordinary_deposit_min_lockup_duration
-            .seconds(),
+            .seconds(curr_ts),
         VsrError::InvalidLockupDuration
     );
 
-    let curr_ts = registrar.clock_unix_timestamp();
-    let lockup = Lockup::new_from_kind(LockupKind::constant(duration), curr_ts, curr_ts)?;
+    let kind = match lockup_kind {
+        LockupKindKind::Constant => LockupKind::constant(duration),
+        LockupKindKind::Cliff => LockupKind::cliff(duration),
+        LockupKindKind::Daily
+        | LockupKindKind::Monthly
+        | LockupKindKind::Weekly
+        | LockupKindKind::Yearly => return Err(error!(VsrError::InvalidLockupKind)),
+    };
+    let lockup = Lockup::new_from_kind(kind, curr_ts, curr_ts)?;
 
     // accrue rewards
     registrar.accrue_rewards(curr_ts);
@@ -90,30 +184,48 @@ pub fn ordinary_deposit(
     let mut amount_to_deposit: u64 = amount;
     if voter.is_active(deposit_entry_index)? {
         let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
-        let lockup_kind = d_entry.get_lockup().kind;
-        if let LockupKindKind::Constant = lockup_kind.kind {
-            require_eq!(
-                d_entry.get_amount_deposited_native(),
-                d_entry.get_amount_initially_locked_native(),
-                VsrError::InternalProgramError
-            );
-
-            let old_duration = lockup_kind.duration;
-            if old_duration.seconds() > duration.seconds() {
-                return Err(error!(VsrError::CanNotShortenLockupDuration));
+        let old_kind = d_entry.get_lockup().kind;
+        match old_kind.kind {
+            LockupKindKind::Constant => {
+                require!(
+                    lockup_kind == LockupKindKind::Constant,
+                    VsrError::InvalidLockupKind
+                );
+                require_eq!(
+                    d_entry.get_amount_deposited_native(),
+                    d_entry.get_amount_initially_locked_native(),
+                    VsrError::InternalProgramError
+                );
+
+                let old_duration = old_kind.duration;
+                if old_duration.seconds(curr_ts) > duration.seconds(curr_ts) {
+                    return Err(error!(VsrError::CanNotShortenLockupDuration));
+                }
+
+                if old_duration != duration {
+                    amount_to_deposit = d_entry
+                        .get_amount_deposited_native()
+                        .checked_add(amount)
+                        .unwrap();
+
+                    voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
+                    voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
+                }
             }
-
-            if old_duration != duration {
-                amount_to_deposit = d_entry
-                    .get_amount_deposited_native()
-                    .checked_add(amount)
-                    .unwrap();
-
-                voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
-                voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
+            LockupKindKind::Cliff => {
+                require!(
+                    lockup_kind == LockupKindKind::Cliff,
+                    VsrError::InvalidLockupKind
+                );
+                require!(
+                    old_kind.duration == duration,
+                    VsrError::InvalidLockupDuration
+                );
             }
-        } else {
-            return Err(error!(VsrError::InternalProgramError));
+            LockupKindKind::Daily
+            | LockupKindKind::Monthly
+            | LockupKindKind::Weekly
+            | LockupKindKind::Yearly => return Err(error!(VsrError::InternalProgramError)),
         }
     } else {
         voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
@@ -121,15 +233,5 @@ pub fn ordinary_deposit(
 
     voter.deposit(deposit_entry_index, curr_ts, amount_to_deposit, registrar)?;
 
-    emit!(OrdinaryDepositEvent {
-        registrar: ctx.accounts.registrar.key(),
-        voter: voter.get_voter_authority(),
-        deposit_entry_index,
-        amount,
-        lockup: voter
-            .deposit_entry_at(deposit_entry_index)?
-            .get_lockup()
-    });
-
     Ok(())
 }
@@ -1,7 +1,7 @@
 use crate::error::*;
 use crate::events::OrdinaryReleaseDepositEvent;
 use crate::state::*;
-use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use crate::{assert_can_change_lockup, NODE_DEPOSIT_ENTRY_INDEX};
 use anchor_lang::prelude::*;
 
 #[derive(Accounts)]
@@ -20,6 +20,16 @@ pub struct OrdinaryReleaseDeposit<'info> {
     )]
     pub voter: AccountLoader<'info, Voter>,
     pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
 }
 
 pub fn ordinary_release_deposit(
@@ -36,7 +46,15 @@ pub fn ordinary_release_deposit(
     );
 
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
-    let voter = &mut ctx.accounts.voter.load_mut()?;
+    registrar.assert_not_paused(PAUSE_RELEASES)?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Governance may forbid lockup changes, for example when engaged in a vote.
+    assert_can_change_lockup(
+        ctx.accounts.token_owner_record.as_deref(),
+        voter,
+        registrar,
+    )?;
 
     let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
     require!(d_entry.is_active(), VsrError::InactiveDepositEntry);
@@ -71,11 +89,25 @@ pub fn ordinary_release_deposit(
             VsrError::ActiveDepositEntryIndex
         );
 
-        let target_lockup = Lockup::new_from_duration(lockup_kind.duration, curr_ts, curr_ts)?;
+        let target_lockup = Lockup::new_from_duration(
+            lockup_kind.duration,
+            registrar.release_cliff_config.ordinary_release_cliff_periods as u64,
+            curr_ts,
+            curr_ts,
+        )?;
 
         voter.activate(target_deposit_entry_index, curr_ts, target_lockup, registrar)?;
         voter.deposit(target_deposit_entry_index, curr_ts, amount, registrar)?;
 
+        // Record the vote weight
+        record_voter_weight(
+            ctx.accounts.voter_weight_history.as_ref(),
+            &ctx.accounts.registrar.key(),
+            voter,
+            curr_ts,
+            registrar,
+        )?;
+
         emit!(OrdinaryReleaseDepositEvent {
         registrar: ctx.accounts.registrar.key(),
             voter: voter.get_voter_authority(),
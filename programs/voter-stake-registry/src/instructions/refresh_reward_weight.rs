@@ -0,0 +1,29 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RefreshRewardWeight<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        mut,
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+}
+
+/// Brings the reward weight of a voter up to date.
+///
+/// The reward weight of vesting deposits decays over time while the total
+/// reward weight of the registrar only follows on balance-changing actions.
+/// Anyone may refresh a voter, so that decayed reward weights do not dilute
+/// the rewards of others.
+pub fn refresh_reward_weight(ctx: Context<RefreshRewardWeight>) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+    voter.refresh_reward_weight(curr_ts, registrar)
+}
@@ -0,0 +1,87 @@
+use crate::error::*;
+use crate::events::UndelegationRequestedEvent;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct RequestUndelegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"delegation-record".as_ref(), voter_authority.key().as_ref()],
+        bump = delegation_record.load()?.bump,
+        constraint = delegation_record.load()?.delegate == delegate_voter.load()?.get_voter_authority() @ VsrError::InvalidDelegationRecord,
+    )]
+    pub delegation_record: AccountLoader<'info, DelegationRecord>,
+
+    /// The voter the vote weight was delegated to.
+    #[account(
+        constraint = delegate_voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub delegate_voter: AccountLoader<'info, Voter>,
+
+    /// Required if the delegate voter has a vote weight history, see
+    /// `VoterWeightHistory`
+    #[account(mut)]
+    pub delegate_voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+}
+
+/// Stops counting the vote weight delegated by the voter towards the
+/// delegate, in preparation of 'undelegate'.
+///
+/// A delegate engaged in a vote forbids undelegating, since the vote weight
+/// would be used twice otherwise. Once the max voting time of the registrar
+/// config passed since the request, no vote cast with the delegated vote
+/// weight can still be ongoing and 'undelegate' no longer depends on the
+/// delegate's governance state, see
+/// `DelegationRecord::can_undelegate_without_delegate`.
+pub fn request_undelegate(ctx: Context<RequestUndelegate>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &ctx.accounts.voter.load_voter()?;
+    let delegate_voter = &ctx.accounts.delegate_voter.load_voter()?;
+    let delegation_record = &mut ctx.accounts.delegation_record.load_mut()?;
+    require!(
+        !delegation_record.is_undelegation_requested(),
+        VsrError::UndelegationRequested
+    );
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    delegation_record.undelegation_requested_ts = curr_ts;
+
+    // Record the delegate's vote weight without the delegated weight
+    if let Some(history) = &mut load_voter_weight_history(
+        ctx.accounts.delegate_voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        delegate_voter,
+    )? {
+        if delegation_record.is_counted_in_weight_history() {
+            history.remove_delegated_weight(
+                delegate_voter,
+                curr_ts,
+                registrar,
+                delegation_record.delegated_weight,
+            )?;
+            delegation_record.counted_in_weight_history = 0;
+        }
+    }
+
+    emit!(UndelegationRequestedEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        delegate: delegate_voter.get_voter_authority(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{self, Transfer};
+
+#[derive(Accounts)]
+pub struct ResizeVoter<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Changes the number of deposit entries of the voter.
+///
+/// Deposit entries beyond the VOTER_DEPOSIT_ENTRY_COUNT entries of the voter
+/// are appended to the voter account. The payer pays for the rent of added
+/// entries and receives the rent of removed ones, only inactive deposit
+/// entries can be removed.
+pub fn resize_voter(ctx: Context<ResizeVoter>, deposit_entry_count: u8) -> Result<()> {
+    let deposit_entry_count = deposit_entry_count as usize;
+    require!(
+        (VOTER_DEPOSIT_ENTRY_COUNT..=MAX_VOTER_DEPOSIT_ENTRY_COUNT).contains(&deposit_entry_count),
+        VsrError::InvalidDepositEntryCount
+    );
+
+    {
+        let voter = ctx.accounts.voter.load_voter()?;
+        require!(
+            voter.deposits().skip(deposit_entry_count).all(|d| !d.is_active()),
+            VsrError::ActiveDepositEntryIndex
+        );
+    }
+
+    let voter_info = ctx.accounts.voter.to_account_info();
+    let new_len = Voter::account_size(deposit_entry_count);
+    let rent_exempt_lamports = Rent::get()?.minimum_balance(new_len);
+    let lamports = voter_info.lamports();
+    if rent_exempt_lamports > lamports {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.payer.to_account_info(),
+                    to: voter_info.clone(),
+                },
+            ),
+            rent_exempt_lamports - lamports,
+        )?;
+    } else if lamports > rent_exempt_lamports {
+        let refund = lamports - rent_exempt_lamports;
+        **voter_info.try_borrow_mut_lamports()? -= refund;
+        **ctx.accounts.payer.try_borrow_mut_lamports()? += refund;
+    }
+
+    voter_info.realloc(new_len, true)?;
+
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use crate::error::*;
+use crate::state::*;
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetNodeStatus<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        mut,
+        constraint = node.load()?.registrar == registrar.key(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    /// The voter running the node
+    #[account(
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), node.load()?.voter_authority.as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// The realm authority, or the voter authority of the node
+    pub authority: Signer<'info>,
+}
+
+/// Changes the status of a node.
+///
+/// The realm authority may set any status. The voter authority of the node
+/// may only switch between 'Active' and 'Exiting', a jailed node can only be
+/// released by the realm authority. Only nodes with an active node deposit
+/// can become active.
+pub fn set_node_status(ctx: Context<SetNodeStatus>, status: NodeStatus) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let node = &mut ctx.accounts.node.load_mut()?;
+
+    let authority = ctx.accounts.authority.key();
+    if authority != registrar.realm_authority {
+        require_keys_eq!(authority, node.voter_authority, VsrError::InvalidAuthority);
+        require!(
+            node.status != NodeStatus::Jailed && status != NodeStatus::Jailed,
+            VsrError::InvalidNodeStatus
+        );
+    }
+
+    let voter = &ctx.accounts.voter.load_voter()?;
+    node.set_status(
+        status,
+        voter.is_active(NODE_DEPOSIT_ENTRY_INDEX)?,
+        registrar,
+    )
+}
@@ -0,0 +1,49 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// Required if the authority is not the realm authority.
+    pub registrar_config: Option<AccountLoader<'info, RegistrarConfig>>,
+
+    pub authority: Signer<'info>,
+}
+
+/// Sets the bitmask of the paused instruction families, see `PAUSE_ALL`.
+///
+/// The realm authority can set any flags. The guardian of the registrar
+/// config can only pause further, unpausing is left to the realm authority.
+/// 'update_voter_weight_record' is never paused, so that governance can
+/// still vote on a fix.
+pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+    require!(paused & !PAUSE_ALL == 0, VsrError::InvalidPauseFlags);
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+    if authority != registrar.realm_authority {
+        let is_guardian = match &ctx.accounts.registrar_config {
+            Some(registrar_config) => {
+                let registrar_config = registrar_config.load()?;
+                require_keys_eq!(
+                    registrar_config.registrar,
+                    ctx.accounts.registrar.key(),
+                    VsrError::InvalidAuthority
+                );
+                registrar_config.is_guardian(&authority)
+            }
+            None => false,
+        };
+        require!(is_guardian, VsrError::InvalidAuthority);
+        require!(
+            paused & registrar.paused == registrar.paused,
+            VsrError::InvalidAuthority
+        );
+    }
+
+    registrar.paused = paused;
+    Ok(())
+}
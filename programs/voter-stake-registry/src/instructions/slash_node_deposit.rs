@@ -0,0 +1,211 @@
+use crate::error::*;
+use crate::events::NodeSlashedEvent;
+use crate::state::*;
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct SlashNodeDeposit<'info> {
+    #[account(
+        mut,
+        has_one = governing_token_mint,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// Required if the slasher is not the realm authority.
+    pub registrar_config: Option<AccountLoader<'info, RegistrarConfig>>,
+
+    #[account(
+        mut,
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        associated_token::authority = voter,
+        associated_token::mint = governing_token_mint,
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    /// Burned from if there is no treasury
+    #[account(mut)]
+    pub governing_token_mint: Box<Account<'info, Mint>>,
+
+    /// Realm controlled account receiving the slashed tokens, they are
+    /// burned if omitted
+    #[account(
+        mut,
+        token::mint = governing_token_mint,
+        token::authority = registrar.load()?.realm_authority,
+    )]
+    pub treasury: Option<Box<Account<'info, TokenAccount>>>,
+
+    /// Created if the node deposit was made before nodes were registered,
+    /// see `Node`
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump,
+        payer = slasher,
+        space = 8 + size_of::<Node>(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    #[account(mut)]
+    pub slasher: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Slashes `amount` tokens of the node deposit of a voter.
+///
+/// Only the realm authority and the slasher of the registrar config can
+/// slash. The node deposit entry is deactivated, the slashed tokens are
+/// moved to the treasury or burned and the remaining tokens are released
+/// into `target_deposit_entry_index` like `node_release_deposit` does.
+/// The node is jailed until the realm authority releases it. Slashing is
+/// halted while releases are paused.
+pub fn slash_node_deposit(
+    ctx: Context<SlashNodeDeposit>,
+    amount: u64,
+    target_deposit_entry_index: u8,
+) -> Result<()> {
+    require!(amount > 0, VsrError::ZeroAmount);
+
+    let remaining_amount = {
+        let registrar = &mut ctx.accounts.registrar.load_mut()?;
+        registrar.assert_not_paused(PAUSE_RELEASES)?;
+        let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+        let slasher = ctx.accounts.slasher.key();
+        let is_slasher = match &ctx.accounts.registrar_config {
+            Some(registrar_config) => {
+                let registrar_config = registrar_config.load()?;
+                require_keys_eq!(
+                    registrar_config.registrar,
+                    ctx.accounts.registrar.key(),
+                    VsrError::InvalidAuthority
+                );
+                registrar_config.is_slasher(&slasher)
+            }
+            None => false,
+        };
+        require!(
+            slasher == registrar.realm_authority || is_slasher,
+            VsrError::InvalidAuthority
+        );
+
+        let d_entry = voter.deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?;
+        require!(d_entry.is_active(), VsrError::InactiveDepositEntry);
+        let amount_deposited = d_entry.get_amount_deposited_native();
+        require_gte!(
+            amount_deposited,
+            amount,
+            VsrError::InsufficientLockedTokens
+        );
+        let duration = d_entry.get_lockup().kind.duration;
+
+        // accrue rewards
+        let curr_ts = registrar.clock_unix_timestamp();
+        registrar.accrue_rewards(curr_ts);
+
+        voter.deactivate(NODE_DEPOSIT_ENTRY_INDEX, curr_ts, registrar)?;
+
+        let node = &mut match ctx.accounts.node.load_init() {
+            Ok(mut node) => {
+                node.init(
+                    ctx.accounts.registrar.key(),
+                    voter.get_voter_authority(),
+                    ctx.bumps.node,
+                    curr_ts,
+                    registrar,
+                );
+                node
+            }
+            Err(_) => ctx.accounts.node.load_mut()?,
+        };
+        node.set_status(NodeStatus::Jailed, false, registrar)?;
+
+        let remaining_amount = amount_deposited - amount;
+        if remaining_amount > 0 {
+            require!(
+                target_deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+                VsrError::NodeDepositReservedEntryIndex
+            );
+            require!(
+                !voter.is_active(target_deposit_entry_index)?,
+                VsrError::ActiveDepositEntryIndex
+            );
+
+            let target_lockup = Lockup::new_from_duration(
+                duration,
+                registrar.release_cliff_config.node_release_cliff_periods as u64,
+                curr_ts,
+                curr_ts,
+            )?;
+            voter.activate(target_deposit_entry_index, curr_ts, target_lockup, registrar)?;
+            voter.deposit(target_deposit_entry_index, curr_ts, remaining_amount, registrar)?;
+        }
+
+        // Record the vote weight
+        record_voter_weight(
+            ctx.accounts.voter_weight_history.as_ref(),
+            &ctx.accounts.registrar.key(),
+            voter,
+            curr_ts,
+            registrar,
+        )?;
+
+        remaining_amount
+    };
+
+    let voter = &ctx.accounts.voter.load_voter()?;
+    let token_program = ctx.accounts.token_program.to_account_info();
+    match &ctx.accounts.treasury {
+        Some(treasury) => token::transfer(
+            CpiContext::new_with_signer(
+                token_program,
+                token::Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: treasury.to_account_info(),
+                    authority: ctx.accounts.voter.to_account_info(),
+                },
+                &[voter_seeds!(voter)],
+            ),
+            amount,
+        )?,
+        None => token::burn(
+            CpiContext::new_with_signer(
+                token_program,
+                token::Burn {
+                    mint: ctx.accounts.governing_token_mint.to_account_info(),
+                    from: ctx.accounts.vault.to_account_info(),
+                    authority: ctx.accounts.voter.to_account_info(),
+                },
+                &[voter_seeds!(voter)],
+            ),
+            amount,
+        )?,
+    }
+
+    emit!(NodeSlashedEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        slasher: ctx.accounts.slasher.key(),
+        amount,
+        burned: ctx.accounts.treasury.is_none(),
+        target_deposit_entry_index,
+        remaining_amount,
+    });
+
+    Ok(())
+}
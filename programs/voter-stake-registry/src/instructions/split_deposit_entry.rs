@@ -0,0 +1,123 @@
+use crate::error::*;
+use crate::events::SplitDepositEntryEvent;
+use crate::state::*;
+use crate::{assert_can_change_lockup, NODE_DEPOSIT_ENTRY_INDEX};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct SplitDepositEntry<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// The token_owner_record for the voter_authority. Required if the
+    /// registrar restricts lockup changes, see `assert_can_change_lockup`.
+    ///
+    /// CHECK: token_owner_record is validated in the instruction, see withdraw
+    pub token_owner_record: Option<UncheckedAccount<'info>>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+}
+
+/// Moves `amount` tokens of the constant lockup deposit entry at
+/// `deposit_entry_index` into the free `target_deposit_entry_index`, keeping
+/// the lockup of the source deposit entry.
+pub fn split_deposit_entry(
+    ctx: Context<SplitDepositEntry>,
+    deposit_entry_index: u8,
+    target_deposit_entry_index: u8,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, VsrError::ZeroAmount);
+    require!(
+        deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX
+            && target_deposit_entry_index != NODE_DEPOSIT_ENTRY_INDEX,
+        VsrError::NodeDepositReservedEntryIndex
+    );
+    require_neq!(
+        deposit_entry_index,
+        target_deposit_entry_index,
+        VsrError::SameDepositEntryIndex
+    );
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.assert_not_paused(PAUSE_RELEASES)?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+
+    // Governance may forbid lockup changes, for example when engaged in a vote.
+    assert_can_change_lockup(
+        ctx.accounts.token_owner_record.as_deref(),
+        voter,
+        registrar,
+    )?;
+
+    let d_entry = voter.deposit_entry_at(deposit_entry_index)?;
+    require!(d_entry.is_active(), VsrError::InactiveDepositEntry);
+    require!(
+        d_entry.get_lockup().kind.kind == LockupKindKind::Constant,
+        VsrError::NotOrdinaryDepositEntry
+    );
+    require!(
+        !voter.is_active(target_deposit_entry_index)?,
+        VsrError::ActiveDepositEntryIndex
+    );
+
+    let lockup = d_entry.get_lockup();
+    let amount_deposited_native = d_entry.get_amount_deposited_native();
+    require_gte!(
+        amount_deposited_native,
+        amount,
+        VsrError::InsufficientLockedTokens
+    );
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    // accrue rewards
+    registrar.accrue_rewards(curr_ts);
+
+    voter.deactivate(deposit_entry_index, curr_ts, registrar)?;
+    if amount_deposited_native > amount {
+        voter.activate(deposit_entry_index, curr_ts, lockup, registrar)?;
+        voter.deposit(
+            deposit_entry_index,
+            curr_ts,
+            amount_deposited_native - amount,
+            registrar,
+        )?;
+    }
+
+    voter.activate(target_deposit_entry_index, curr_ts, lockup, registrar)?;
+    voter.deposit(target_deposit_entry_index, curr_ts, amount, registrar)?;
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(SplitDepositEntryEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        deposit_entry_index,
+        target_deposit_entry_index,
+        amount,
+        lockup,
+    });
+
+    Ok(())
+}
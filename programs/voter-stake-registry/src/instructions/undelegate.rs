@@ -0,0 +1,162 @@
+use crate::error::*;
+use crate::events::UndelegateEvent;
+use crate::state::*;
+use crate::load_token_owner_record;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Undelegate<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    // checking the PDA address it just an extra precaution,
+    // the other constraints must be exhaustive
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+        constraint = voter.load()?.get_voter_authority() == voter_authority.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"delegation-record".as_ref(), voter_authority.key().as_ref()],
+        bump = delegation_record.load()?.bump,
+        constraint = delegation_record.load()?.delegate == delegate_voter.load()?.get_voter_authority() @ VsrError::InvalidDelegationRecord,
+        close = sol_destination
+    )]
+    pub delegation_record: AccountLoader<'info, DelegationRecord>,
+
+    /// The voter the vote weight was delegated to.
+    #[account(
+        mut,
+        constraint = delegate_voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub delegate_voter: AccountLoader<'info, Voter>,
+
+    /// The token_owner_record for the delegate. This is needed to be able
+    /// to forbid undelegating while the delegate is engaged with a vote,
+    /// since the vote weight would be used twice otherwise.
+    ///
+    /// delegate_token_owner_record is validated in the instruction:
+    /// - owned by registrar.governance_program_id
+    /// - for the registrar.realm
+    /// - for the registrar.realm_governing_token_mint
+    /// - governing_token_owner is the delegate's voter authority
+    /// CHECK: verified while loading data
+    pub delegate_token_owner_record: UncheckedAccount<'info>,
+
+    /// Undelegating must update the delegate's voter weight record, to prevent
+    /// a stale record that still contains the delegated vote weight being used
+    /// to vote after the undelegation.
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), delegate_voter.load()?.get_voter_authority().as_ref()],
+        bump = delegate_voter.load()?.get_voter_weight_record_bump(),
+        constraint = delegate_voter_weight_record.realm == registrar.load()?.realm,
+        constraint = delegate_voter_weight_record.governing_token_owner == delegate_voter.load()?.get_voter_authority(),
+        constraint = delegate_voter_weight_record.governing_token_mint == registrar.load()?.governing_token_mint,
+    )]
+    pub delegate_voter_weight_record: Account<'info, VoterWeightRecord>,
+
+    /// Required if the delegate voter has a vote weight history, see
+    /// `VoterWeightHistory`
+    #[account(mut)]
+    pub delegate_voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
+    /// Required to undelegate regardless of the delegate's governance state
+    /// after 'request_undelegate', see `RegistrarConfig::max_voting_time_secs`
+    pub registrar_config: Option<AccountLoader<'info, RegistrarConfig>>,
+
+    #[account(mut)]
+    /// CHECK:
+    pub sol_destination: UncheckedAccount<'info>,
+}
+
+/// Takes back the vote weight delegated by the voter and closes the
+/// delegation record.
+///
+/// The delegate's voter weight record is reset to the delegate's own vote
+/// weight, it needs to call `update_voter_weight_record` again to include
+/// the weight delegated by other voters.
+///
+/// Undelegating is forbidden while the delegate is engaged in a vote, unless
+/// the undelegation was requested through 'request_undelegate' at least the
+/// registrar config's max voting time ago.
+pub fn undelegate(ctx: Context<Undelegate>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
+    let delegate_voter = &mut ctx.accounts.delegate_voter.load_voter_mut()?;
+    let delegation_record = &ctx.accounts.delegation_record.load()?;
+    let curr_ts = registrar.clock_unix_timestamp();
+
+    let max_voting_time_secs = match &ctx.accounts.registrar_config {
+        Some(registrar_config) => {
+            let registrar_config = registrar_config.load()?;
+            require_keys_eq!(
+                registrar_config.registrar,
+                ctx.accounts.registrar.key(),
+                VsrError::RegistrarConfigRequired
+            );
+            registrar_config.max_voting_time_secs
+        }
+        None => 0,
+    };
+
+    // Governance may forbid undelegating, for example when the delegate is engaged in a vote.
+    let token_owner_record = load_token_owner_record(
+        &ctx.accounts.delegate_token_owner_record.to_account_info(),
+        delegate_voter,
+        registrar,
+    )?;
+    if !delegation_record.can_undelegate_without_delegate(max_voting_time_secs, curr_ts) {
+        token_owner_record.assert_can_withdraw_governing_tokens()?;
+    }
+
+    voter.undelegate()?;
+    delegate_voter.remove_delegator();
+
+    // Update the delegate's voter weight record
+    let record = &mut ctx.accounts.delegate_voter_weight_record;
+    record.voter_weight = delegate_voter.own_vote_weight(curr_ts, registrar)?;
+    record.voter_weight_expiry = Some(Clock::get()?.slot);
+
+    // Record the delegate's vote weight without the delegated weight
+    if let Some(history) = &mut load_voter_weight_history(
+        ctx.accounts.delegate_voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        delegate_voter,
+    )? {
+        if delegation_record.is_counted_in_weight_history() {
+            history.remove_delegated_weight(
+                delegate_voter,
+                curr_ts,
+                registrar,
+                delegation_record.delegated_weight,
+            )?;
+        }
+    }
+
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
+    emit!(UndelegateEvent {
+        registrar: ctx.accounts.registrar.key(),
+        voter: voter.get_voter_authority(),
+        delegate: delegate_voter.get_voter_authority(),
+    });
+
+    Ok(())
+}
@@ -1,4 +1,3 @@
-use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
@@ -14,14 +13,15 @@ pub struct UpdateDepositConfig<'info> {
 }
 
 /// Update deposit configurations
+///
+/// Raising the node security deposit gives existing nodes
+/// 'node_security_deposit_grace_period_secs' to call `node_top_up_deposit`,
+/// from the time `update_node_compliance` finds their deposit below it.
 pub fn update_deposit_config(
     ctx: Context<UpdateDepositConfig>,
     deposit_config: DepositConfig,
 ) -> Result<()> {
-    require!(
-        deposit_config.node_security_deposit > 0,
-        VsrError::NodeSecurityDepositMustBePositive
-    );
+    deposit_config.validate()?;
 
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
     registrar.deposit_config = deposit_config;
@@ -0,0 +1,26 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateEarlyUnlockPenaltyConfig<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Update the penalty of early unlocks, the zeroed config disables them.
+pub fn update_early_unlock_penalty_config(
+    ctx: Context<UpdateEarlyUnlockPenaltyConfig>,
+    early_unlock_penalty_config: EarlyUnlockPenaltyConfig,
+) -> Result<()> {
+    early_unlock_penalty_config.validate()?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.early_unlock_penalty_config = early_unlock_penalty_config;
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateEmissionConfig<'info> {
+    #[account(
+        mut, 
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Update the emission schedule of staking rewards.
+///
+/// Rewards are accrued with the previous config up to now, then the reward
+/// amount per second is rotated immediately with the new config.
+pub fn update_emission_config(
+    ctx: Context<UpdateEmissionConfig>,
+    emission_config: EmissionConfig,
+) -> Result<()> {
+    emission_config.validate()?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    require_gte!(
+        emission_config.total_reward_amount,
+        registrar.issued_reward_amount,
+        VsrError::InvalidEmissionConfig
+    );
+
+    registrar.emission_config = emission_config;
+    registrar.rotate_reward_amount_per_second(curr_ts);
+
+    Ok(())
+}
@@ -1,15 +1,23 @@
+use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
-use anchor_spl::token::Mint;
+use anchor_spl::token::{Mint, TokenAccount};
 
-// Remaining accounts should all the token mints that have registered
-// exchange rates.
+// Remaining accounts must be the token accounts of the excluded supply, in
+// the same order, if the max vote weight mode is 'CirculatingSupply'.
 #[derive(Accounts)]
 pub struct UpdateMaxVoteWeight<'info> {
     pub registrar: AccountLoader<'info, Registrar>,
     /// Registrar.realm_governing_token_mint
     pub governing_token_mint: Account<'info, Mint>,
 
+    /// Required if the max vote weight mode is 'CirculatingSupply'
+    #[account(
+        seeds = [registrar.key().as_ref(), b"excluded-supply".as_ref()],
+        bump = excluded_supply.load()?.bump,
+    )]
+    pub excluded_supply: Option<AccountLoader<'info, ExcludedSupply>>,
+
     #[account(
         mut,
         seeds = [registrar.load()?.realm.key().as_ref(), b"max-voter-weight-record".as_ref(), registrar.load()?.governing_token_mint.key().as_ref()],
@@ -20,18 +28,68 @@ pub struct UpdateMaxVoteWeight<'info> {
     pub max_voter_weight_record: Account<'info, MaxVoterWeightRecord>,
 }
 
-/// Calculates the max vote weight for the registry. This is a function
-/// of the total supply of all exchange rate mints, converted into a
-/// common currency with a common number of decimals.
+/// Calculates the max vote weight for the registry, assuming all counted
+/// tokens are locked up for the saturation period or longer.
 ///
-/// Note that this method is only safe to use if the cumulative supply for
-/// all tokens fits into a u64 *after* converting into common decimals, as
-/// defined by the registrar's `rate_decimal` field.
-pub fn update_max_vote_weight(ctx: Context<UpdateMaxVoteWeight>) -> Result<()> {
+/// Which tokens are counted depends on the registrar's max vote weight mode,
+/// see `MaxVoteWeightMode`.
+pub fn update_max_vote_weight<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateMaxVoteWeight<'info>>,
+) -> Result<()> {
     let registrar = &ctx.accounts.registrar.load()?;
+    let mint = &ctx.accounts.governing_token_mint;
+
+    let max_voter_weight = match registrar.max_vote_weight_mode {
+        MaxVoteWeightMode::Supply => registrar.max_vote_weight(mint)?,
+        MaxVoteWeightMode::Deposited => {
+            registrar.assert_deposit_totals_complete()?;
+            registrar.max_vote_weight_for(
+                registrar.total_deposited_amount,
+                registrar.total_locked_amount,
+            )?
+        }
+        MaxVoteWeightMode::CirculatingSupply => {
+            require_keys_eq!(
+                mint.key(),
+                registrar.governing_token_mint,
+                VsrError::InvalidGoverningMint
+            );
+            let excluded_supply = ctx
+                .accounts
+                .excluded_supply
+                .as_ref()
+                .ok_or_else(|| error!(VsrError::ExcludedSupplyRequired))?
+                .load()?;
+            let token_accounts = excluded_supply.token_accounts();
+            require_eq!(
+                ctx.remaining_accounts.len(),
+                token_accounts.len(),
+                VsrError::InvalidExcludedTokenAccounts
+            );
+
+            let mut excluded_amount: u64 = 0;
+            for (account_info, key) in ctx.remaining_accounts.iter().zip(token_accounts) {
+                require_keys_eq!(
+                    *account_info.key,
+                    *key,
+                    VsrError::InvalidExcludedTokenAccounts
+                );
+                let token_account = Account::<TokenAccount>::try_from(account_info)?;
+                require_keys_eq!(
+                    token_account.mint,
+                    registrar.governing_token_mint,
+                    VsrError::InvalidExcludedTokenAccounts
+                );
+                excluded_amount = excluded_amount.checked_add(token_account.amount).unwrap();
+            }
+
+            let circulating_supply = mint.supply.checked_sub(excluded_amount).unwrap();
+            registrar.max_vote_weight_for(circulating_supply, circulating_supply)?
+        }
+    };
 
     let record = &mut ctx.accounts.max_voter_weight_record;
-    record.max_voter_weight = registrar.max_vote_weight(&ctx.accounts.governing_token_mint)?;
+    record.max_voter_weight = max_voter_weight;
     record.max_voter_weight_expiry = Some(Clock::get()?.slot);
 
     Ok(())
@@ -0,0 +1,57 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct UpdateMaxVoteWeightMode<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"excluded-supply".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<ExcludedSupply>(),
+    )]
+    pub excluded_supply: AccountLoader<'info, ExcludedSupply>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Selects how 'update_max_vote_weight' computes the max vote weight, see
+/// `MaxVoteWeightMode`.
+///
+/// `MaxVoteWeightMode::Deposited` requires the deposit totals to cover all
+/// voters, see `Registrar::assert_deposit_totals_complete`.
+///
+/// `excluded_token_accounts`: Token accounts of the governing token mint
+///   whose balances `MaxVoteWeightMode::CirculatingSupply` does not count.
+pub fn update_max_vote_weight_mode(
+    ctx: Context<UpdateMaxVoteWeightMode>,
+    mode: MaxVoteWeightMode,
+    excluded_token_accounts: Vec<Pubkey>,
+) -> Result<()> {
+    let excluded_supply = &mut match ctx.accounts.excluded_supply.load_init() {
+        Ok(mut excluded_supply) => {
+            excluded_supply.registrar = ctx.accounts.registrar.key();
+            excluded_supply.bump = ctx.bumps.excluded_supply;
+            excluded_supply
+        }
+        Err(_) => ctx.accounts.excluded_supply.load_mut()?,
+    };
+    excluded_supply.set_token_accounts(&excluded_token_accounts)?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    if mode == MaxVoteWeightMode::Deposited {
+        registrar.assert_deposit_totals_complete()?;
+    }
+    registrar.max_vote_weight_mode = mode;
+
+    Ok(())
+}
@@ -0,0 +1,48 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct UpdateMaxVotingTime<'info> {
+    #[account(has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-config".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarConfig>(),
+    )]
+    pub registrar_config: AccountLoader<'info, RegistrarConfig>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the upper bound of the voting time plus the cool off time of the
+/// realm's governances.
+///
+/// Voters that requested an undelegation through 'request_undelegate' can
+/// undelegate regardless of the delegate's governance state once it passed.
+/// Pass 0 to disable that.
+pub fn update_max_voting_time(
+    ctx: Context<UpdateMaxVotingTime>,
+    max_voting_time_secs: u32,
+) -> Result<()> {
+    let registrar_config = &mut match ctx.accounts.registrar_config.load_init() {
+        Ok(mut registrar_config) => {
+            registrar_config.registrar = ctx.accounts.registrar.key();
+            registrar_config.bump = ctx.bumps.registrar_config;
+            registrar_config
+        }
+        Err(_) => ctx.accounts.registrar_config.load_mut()?,
+    };
+
+    registrar_config.max_voting_time_secs = max_voting_time_secs;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use crate::error::*;
+use crate::state::*;
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct UpdateNode<'info> {
+    #[account(mut)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        seeds = [registrar.key().as_ref(), b"voter".as_ref(), voter_authority.key().as_ref()],
+        bump = voter.load()?.get_voter_bump(),
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+    pub voter_authority: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter_authority.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<Node>(),
+    )]
+    pub node: AccountLoader<'info, Node>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the operator and the endpoint of a node.
+///
+/// Nodes that made their node deposit before nodes were registered on-chain
+/// are registered on their first update.
+pub fn update_node(ctx: Context<UpdateNode>, operator: Pubkey, endpoint: String) -> Result<()> {
+    let voter = &ctx.accounts.voter.load_voter()?;
+    require!(
+        voter.is_active(NODE_DEPOSIT_ENTRY_INDEX)?,
+        VsrError::InactiveDepositEntry
+    );
+
+    let node = &mut match ctx.accounts.node.load_init() {
+        Ok(mut node) => {
+            let registrar = &mut ctx.accounts.registrar.load_mut()?;
+            let curr_ts = registrar.clock_unix_timestamp();
+            node.init(
+                ctx.accounts.registrar.key(),
+                voter.get_voter_authority(),
+                ctx.bumps.node,
+                curr_ts,
+                registrar,
+            );
+            node
+        }
+        Err(_) => ctx.accounts.node.load_mut()?,
+    };
+
+    node.operator = operator;
+    node.set_endpoint(&endpoint)
+}
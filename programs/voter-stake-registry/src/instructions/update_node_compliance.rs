@@ -0,0 +1,46 @@
+use crate::state::*;
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateNodeCompliance<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        constraint = voter.load()?.get_registrar() == registrar.key(),
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    #[account(
+        mut,
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump = node.load()?.bump,
+    )]
+    pub node: AccountLoader<'info, Node>,
+}
+
+/// Flags the node as non-compliant if its node deposit is below the node
+/// security deposit and the grace period has passed, or clears the flag
+/// otherwise. The grace period of the node starts the first time this finds
+/// its node deposit below the node security deposit.
+///
+/// Anyone can call this.
+pub fn update_node_compliance(ctx: Context<UpdateNodeCompliance>) -> Result<()> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &ctx.accounts.voter.load_voter()?;
+
+    let node_deposit = voter.deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?;
+    let amount_deposited_native = if node_deposit.is_active() {
+        node_deposit.get_amount_deposited_native()
+    } else {
+        0
+    };
+
+    let curr_ts = registrar.clock_unix_timestamp();
+    ctx.accounts
+        .node
+        .load_mut()?
+        .update_compliance(amount_deposited_native, curr_ts, registrar);
+
+    Ok(())
+}
@@ -0,0 +1,83 @@
+use crate::error::*;
+use crate::state::*;
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
+use circuit_breaker::cpi::accounts::UpdateAccountWindowedBreakerV0;
+use circuit_breaker::cpi::update_account_windowed_breaker_v0;
+use circuit_breaker::AccountWindowedCircuitBreakerV0;
+use circuit_breaker::CircuitBreaker;
+use circuit_breaker::UpdateAccountWindowedBreakerArgsV0;
+use spl_governance::state::realm;
+
+#[derive(Accounts)]
+pub struct UpdateRegistrarAuthority<'info> {
+    #[account(
+        mut,
+        has_one = realm,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    /// The spl-governance realm of the registrar, its current authority
+    /// becomes the new realm authority of the registrar.
+    /// CHECK: validated in the instruction
+    pub realm: UncheckedAccount<'info>,
+
+    /// The reward vault of the registrar.
+    #[account(
+        address = get_associated_token_address(&registrar.key(), &registrar.load()?.governing_token_mint),
+    )]
+    pub vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = ["account_windowed_breaker".as_bytes(), vault.key().as_ref()],
+        seeds::program = circuit_breaker_program.key(),
+        bump = circuit_breaker.bump_seed,
+    )]
+    pub circuit_breaker: Box<Account<'info, AccountWindowedCircuitBreakerV0>>,
+
+    /// The previous realm authority, which is also the authority of the
+    /// circuit breaker.
+    pub realm_authority: Signer<'info>,
+
+    pub circuit_breaker_program: Program<'info, CircuitBreaker>,
+}
+
+/// Moves the registrar to the current authority of its realm.
+///
+/// The realm authority of the registrar is only copied from the realm in
+/// 'create_registrar'. Once the realm authority changed, the previous one
+/// hands the registrar over through this instruction, which also makes the
+/// new one the authority of the reward vault's circuit breaker.
+pub fn update_registrar_authority(ctx: Context<UpdateRegistrarAuthority>) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+
+    // Verify the realm and read its current authority.
+    let realm = realm::get_realm_data_for_governing_token_mint(
+        &registrar.governance_program_id,
+        &ctx.accounts.realm.to_account_info(),
+        &registrar.governing_token_mint,
+    )?;
+    let new_realm_authority = realm
+        .authority
+        .ok_or_else(|| error!(VsrError::InvalidRealmAuthority))?;
+
+    update_account_windowed_breaker_v0(
+        CpiContext::new(
+            ctx.accounts.circuit_breaker_program.to_account_info(),
+            UpdateAccountWindowedBreakerV0 {
+                circuit_breaker: ctx.accounts.circuit_breaker.to_account_info(),
+                authority: ctx.accounts.realm_authority.to_account_info(),
+            },
+        ),
+        UpdateAccountWindowedBreakerArgsV0 {
+            new_authority: Some(new_realm_authority),
+            config: None,
+        },
+    )?;
+
+    registrar.realm_authority = new_realm_authority;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateReleaseCliffConfig<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Update the vesting cliffs of released deposits.
+///
+/// Only deposits released afterwards are affected.
+pub fn update_release_cliff_config(
+    ctx: Context<UpdateReleaseCliffConfig>,
+    release_cliff_config: ReleaseCliffConfig,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.release_cliff_config = release_cliff_config;
+
+    Ok(())
+}
@@ -0,0 +1,26 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateRestrictLockupChanges<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Sets whether instructions that reduce or restructure locked tokens are
+/// forbidden while governance forbids withdraws, for example when the voter
+/// is engaged in a vote, see `assert_can_change_lockup`.
+pub fn update_restrict_lockup_changes(
+    ctx: Context<UpdateRestrictLockupChanges>,
+    restrict_lockup_changes: bool,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.restrict_lockup_changes = restrict_lockup_changes as u8;
+
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateRewardBoostConfig<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Update the boost of reward weights by lockup time.
+///
+/// Rewards are accrued with the previous config up to now. The reward weight of
+/// each voter follows the new config on its next balance-changing action or
+/// 'refresh_reward_weight'.
+pub fn update_reward_boost_config(
+    ctx: Context<UpdateRewardBoostConfig>,
+    reward_boost_config: RewardBoostConfig,
+) -> Result<()> {
+    reward_boost_config.validate()?;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    let curr_ts = registrar.clock_unix_timestamp();
+    registrar.accrue_rewards(curr_ts);
+
+    registrar.reward_boost_config = reward_boost_config;
+
+    Ok(())
+}
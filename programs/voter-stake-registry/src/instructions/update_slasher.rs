@@ -0,0 +1,42 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct UpdateSlasher<'info> {
+    #[account(has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-config".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarConfig>(),
+    )]
+    pub registrar_config: AccountLoader<'info, RegistrarConfig>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the key that may slash node deposits besides the realm authority.
+///
+/// Pass Pubkey::default() to remove the slasher.
+pub fn update_slasher(ctx: Context<UpdateSlasher>, slasher: Pubkey) -> Result<()> {
+    let registrar_config = &mut match ctx.accounts.registrar_config.load_init() {
+        Ok(mut registrar_config) => {
+            registrar_config.registrar = ctx.accounts.registrar.key();
+            registrar_config.bump = ctx.bumps.registrar_config;
+            registrar_config
+        }
+        Err(_) => ctx.accounts.registrar_config.load_mut()?,
+    };
+
+    registrar_config.slasher = slasher;
+    Ok(())
+}
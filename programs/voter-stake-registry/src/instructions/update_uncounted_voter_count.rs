@@ -0,0 +1,31 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateUncountedVoterCount<'info> {
+    #[account(
+        mut,
+        has_one = realm_authority,
+    )]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    pub realm_authority: Signer<'info>,
+}
+
+/// Sets the number of voters whose deposits are not counted in the deposit
+/// totals of the registrar yet, the voters created before the totals were
+/// introduced that had no balance-changing action since.
+///
+/// Registrars created before the deposit totals can not use
+/// `MaxVoteWeightMode::Deposited` until it is set and these voters are
+/// counted, see `Registrar::assert_deposit_totals_complete`.
+pub fn update_uncounted_voter_count(
+    ctx: Context<UpdateUncountedVoterCount>,
+    uncounted_voter_count: u64,
+) -> Result<()> {
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.uncounted_voter_count = uncounted_voter_count;
+    registrar.uncounted_voter_count_initialized = 1;
+
+    Ok(())
+}
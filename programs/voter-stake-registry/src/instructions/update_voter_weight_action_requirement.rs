@@ -0,0 +1,54 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+use std::mem::size_of;
+
+#[derive(Accounts)]
+pub struct UpdateVoterWeightActionRequirement<'info> {
+    #[account(mut, has_one = realm_authority)]
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        init_if_needed,
+        seeds = [registrar.key().as_ref(), b"registrar-config".as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + size_of::<RegistrarConfig>(),
+    )]
+    pub registrar_config: AccountLoader<'info, RegistrarConfig>,
+
+    pub realm_authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Sets the requirements a voter must meet to get a voter weight record for
+/// `action`, see `VoterWeightActionRequirement`.
+///
+/// Pass a zeroed requirement to remove them. While any action has
+/// requirements, 'update_voter_weight_record' needs the registrar config and
+/// an action.
+pub fn update_voter_weight_action_requirement(
+    ctx: Context<UpdateVoterWeightActionRequirement>,
+    action: VoterWeightAction,
+    requirement: VoterWeightActionRequirement,
+) -> Result<()> {
+    let registrar_config = &mut match ctx.accounts.registrar_config.load_init() {
+        Ok(mut registrar_config) => {
+            registrar_config.registrar = ctx.accounts.registrar.key();
+            registrar_config.bump = ctx.bumps.registrar_config;
+            registrar_config
+        }
+        Err(_) => ctx.accounts.registrar_config.load_mut()?,
+    };
+
+    registrar_config.voter_weight_action_requirements[action as usize] = requirement;
+
+    let registrar = &mut ctx.accounts.registrar.load_mut()?;
+    registrar.has_voter_weight_action_requirements =
+        registrar_config.has_voter_weight_action_requirements() as u8;
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 
@@ -14,6 +15,9 @@ pub struct UpdateVoterWeightRecord<'info> {
     )]
     pub voter: AccountLoader<'info, Voter>,
 
+    /// Required if the registrar has voter weight action requirements.
+    pub registrar_config: Option<AccountLoader<'info, RegistrarConfig>>,
+
     #[account(
         mut,
         seeds = [registrar.key().as_ref(), b"voter-weight-record".as_ref(), voter.load()?.get_voter_authority().key().as_ref()],
@@ -31,15 +35,130 @@ pub struct UpdateVoterWeightRecord<'info> {
 /// voter and writes it into a `VoteWeightRecord` account to be used by
 /// the SPL governance program.
 ///
+/// Remaining accounts may be pairs of (delegation_record, delegator voter)
+/// for the voters delegating to this voter, ordered by delegator authority.
+/// Their vote weight is added to the voter's own weight. A voter that
+/// delegated its vote weight always gets a weight of zero. Delegations with a
+/// pending 'request_undelegate' are rejected.
+///
+/// `weight_action`: The action the record is valid for, any if None.
+/// `weight_action_target`: The target of the action, e.g. the proposal to
+///   vote on, any if None.
+///
+/// If the registrar has voter weight action requirements, an action is needed
+/// and the voter must meet its requirement, see `VoterWeightActionRequirement`.
+///
 /// This "revise" instruction must be called immediately before voting, in
 /// the same transaction.
-pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
+pub fn update_voter_weight_record<'info>(
+    ctx: Context<'_, '_, 'info, 'info, UpdateVoterWeightRecord<'info>>,
+    weight_action: Option<VoterWeightAction>,
+    weight_action_target: Option<Pubkey>,
+) -> Result<()> {
     let registrar = &ctx.accounts.registrar.load()?;
-    let voter = &ctx.accounts.voter.load()?;
-    let record = &mut ctx.accounts.voter_weight_record;
+    let voter = &ctx.accounts.voter.load_voter()?;
     let curr_ts = registrar.clock_unix_timestamp();
-    record.voter_weight = voter.weight(curr_ts, registrar)?;
+
+    if registrar.has_voter_weight_action_requirements != 0 {
+        let action = weight_action.ok_or_else(|| error!(VsrError::VoterWeightActionRequired))?;
+        let registrar_config = ctx
+            .accounts
+            .registrar_config
+            .as_ref()
+            .ok_or_else(|| error!(VsrError::RegistrarConfigRequired))?
+            .load()?;
+        require_keys_eq!(
+            registrar_config.registrar,
+            ctx.accounts.registrar.key(),
+            VsrError::RegistrarConfigRequired
+        );
+        registrar_config
+            .voter_weight_action_requirement(action)
+            .check(voter, curr_ts, registrar)?;
+    }
+
+    let mut voter_weight = voter.own_vote_weight(curr_ts, registrar)?;
+    if !voter.is_delegated() {
+        let delegated_weight = delegated_vote_weight(
+            ctx.remaining_accounts,
+            &ctx.accounts.registrar.key(),
+            registrar,
+            voter.get_voter_authority(),
+            curr_ts,
+        )?;
+        voter_weight = voter_weight
+            .checked_add(delegated_weight)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
+    }
+
+    let record = &mut ctx.accounts.voter_weight_record;
+    record.voter_weight = voter_weight;
     record.voter_weight_expiry = Some(Clock::get()?.slot);
+    record.weight_action = weight_action.map(Into::into);
+    record.weight_action_target = weight_action_target;
 
     Ok(())
 }
+
+/// Sums up the vote weight delegated to `delegate` by the given
+/// (delegation_record, delegator voter) account pairs.
+///
+/// Delegators must be strictly ordered by their voter authority, so that
+/// no vote weight can be counted twice.
+fn delegated_vote_weight<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    registrar_key: &Pubkey,
+    registrar: &Registrar,
+    delegate: Pubkey,
+    curr_ts: i64,
+) -> Result<u64> {
+    require!(accounts.len() % 2 == 0, VsrError::InvalidDelegationRecord);
+
+    let mut last_delegator: Option<Pubkey> = None;
+    let mut sum = 0u64;
+    for pair in accounts.chunks(2) {
+        let delegation_record_loader = AccountLoader::<DelegationRecord>::try_from(&pair[0])?;
+        let delegation_record = delegation_record_loader.load()?;
+        require_keys_eq!(
+            delegation_record.registrar,
+            *registrar_key,
+            VsrError::InvalidDelegationRecord
+        );
+        require_keys_eq!(
+            delegation_record.delegate,
+            delegate,
+            VsrError::InvalidDelegationRecord
+        );
+        require!(
+            !delegation_record.is_undelegation_requested(),
+            VsrError::UndelegationRequested
+        );
+        if let Some(last_delegator) = last_delegator {
+            require!(
+                last_delegator < delegation_record.delegator,
+                VsrError::UnsortedDelegators
+            );
+        }
+        last_delegator = Some(delegation_record.delegator);
+
+        let delegator_loader = AccountLoader::<Voter>::try_from(&pair[1])?;
+        let delegator = delegator_loader.load_voter()?;
+        require_keys_eq!(
+            delegator.get_registrar(),
+            *registrar_key,
+            VsrError::InvalidDelegationRecord
+        );
+        require_keys_eq!(
+            delegator.get_voter_authority(),
+            delegation_record.delegator,
+            VsrError::InvalidDelegationRecord
+        );
+        require!(delegator.is_delegated(), VsrError::InvalidDelegationRecord);
+
+        sum = sum
+            .checked_add(delegator.weight(curr_ts, registrar)?)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
+    }
+
+    Ok(sum)
+}
@@ -1,4 +1,3 @@
-use crate::error::*;
 use crate::state::*;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
@@ -21,10 +20,7 @@ pub fn update_voting_config(
     ctx: Context<UpdateVotingConfig>,
     voting_config: VotingConfig,
 ) -> Result<()> {
-    require!(
-        voting_config.lockup_saturation_secs > 0,
-        VsrError::LockupSaturationMustBePositive
-    );
+    voting_config.validate()?;
 
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
     registrar.voting_config = voting_config;
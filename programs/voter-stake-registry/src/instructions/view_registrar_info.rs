@@ -0,0 +1,47 @@
+use crate::state::*;
+use crate::views::RegistrarView;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ViewRegistrarInfo<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        seeds = [registrar.key().as_ref(), b"registrar-stats".as_ref()],
+        bump = registrar_stats.load()?.bump,
+    )]
+    pub registrar_stats: AccountLoader<'info, RegistrarStats>,
+}
+
+/// A no-effect instruction that returns registrar-wide statistics through
+/// `set_return_data`, see `views::cpi::view_registrar_info`.
+pub fn view_registrar_info(ctx: Context<ViewRegistrarInfo>) -> Result<RegistrarView> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let stats = &ctx.accounts.registrar_stats.load()?;
+    Ok(registrar_view(registrar, stats))
+}
+
+/// The registrar's current deposit totals, rewards and counts.
+pub fn registrar_view(registrar: &Registrar, stats: &RegistrarStats) -> RegistrarView {
+    let curr_ts = registrar.clock_unix_timestamp();
+
+    // project the reward accrual up to now
+    let mut accrued_registrar = *registrar;
+    accrued_registrar.accrue_rewards(curr_ts);
+
+    RegistrarView {
+        deposited_amount: registrar.total_deposited_amount,
+        permanently_locked_amount: registrar.permanently_locked_amount,
+        max_vesting_locked_amount: registrar
+            .total_locked_amount
+            .saturating_sub(registrar.permanently_locked_amount),
+        issued_reward_amount: accrued_registrar.issued_reward_amount,
+        max_unclaimed_reward_amount: stats
+            .max_unclaimed_reward_amount(accrued_registrar.issued_reward_amount),
+        reward_runway_amount: accrued_registrar.unissued_funded_reward_amount(),
+        reward_runway_secs: accrued_registrar.reward_runway_secs(),
+        total_reward_weight: accrued_registrar.total_reward_weight,
+        voter_count: stats.voter_count,
+        active_node_count: registrar.active_node_count,
+    }
+}
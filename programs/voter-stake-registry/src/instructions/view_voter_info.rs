@@ -0,0 +1,137 @@
+use crate::error::*;
+use crate::events::*;
+use crate::state::*;
+use crate::views::{VoterView, VOTER_VIEW_DEPOSIT_ENTRY_COUNT};
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ViewVoterInfo<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        constraint = voter.load()?.get_registrar() == registrar.key()
+    )]
+    pub voter: AccountLoader<'info, Voter>,
+
+    /// The node of the voter, if any, to report its compliance
+    #[account(
+        seeds = [registrar.key().as_ref(), b"node".as_ref(), voter.load()?.get_voter_authority().as_ref()],
+        bump = node.load()?.bump,
+    )]
+    pub node: Option<AccountLoader<'info, Node>>,
+}
+
+/// A no-effect instruction that returns information about the voter and
+/// deposits through `set_return_data`, see `views::cpi::view_voter_info`.
+///
+/// Only the deposit entries from `first_deposit_entry_index` on are
+/// returned, at most `VOTER_VIEW_DEPOSIT_ENTRY_COUNT` of them, to stay
+/// within the return data limit. The other values cover all deposit entries.
+pub fn view_voter_info(ctx: Context<ViewVoterInfo>, first_deposit_entry_index: u8) -> Result<VoterView> {
+    let registrar = &ctx.accounts.registrar.load()?;
+    let voter = &ctx.accounts.voter.load_voter()?;
+    let node = ctx.accounts.node.as_ref().map(|node| node.load()).transpose()?;
+
+    let first_deposit_entry_index = first_deposit_entry_index as usize;
+    require_gt!(
+        voter.deposit_entry_count(),
+        first_deposit_entry_index,
+        VsrError::OutOfBoundsDepositEntryIndex
+    );
+    voter_view(
+        registrar,
+        voter,
+        node.as_deref(),
+        first_deposit_entry_index,
+        VOTER_VIEW_DEPOSIT_ENTRY_COUNT,
+    )
+}
+
+/// The voter's current vote weight, rewards and up to
+/// `deposit_entry_count` deposit entries from `first_deposit_entry_index` on.
+pub fn voter_view(
+    registrar: &Registrar,
+    voter: &VoterRef,
+    node: Option<&Node>,
+    first_deposit_entry_index: usize,
+    deposit_entry_count: usize,
+) -> Result<VoterView> {
+    let curr_ts = registrar.clock_unix_timestamp();
+    let mut deposit_entries: Vec<Option<DepositEntryInfo>> = Vec::with_capacity(deposit_entry_count);
+    for d_entry in voter
+        .deposits()
+        .skip(first_deposit_entry_index)
+        .take(deposit_entry_count)
+    {
+        if d_entry.is_active() {
+            let lockup = &d_entry.get_lockup();
+            let periods_total = lockup.periods_total();
+            let periods_left = lockup.periods_left(curr_ts)?;
+            let amount_locked = d_entry.amount_locked(curr_ts)?;
+            let amount_unlocked = d_entry.amount_unlocked(curr_ts)?;
+            let voting_power = d_entry.voting_power(&registrar.voting_config, curr_ts)?;
+            let reward_weight = d_entry.reward_weight(&registrar.reward_boost_config, curr_ts)?;
+            let voting_power_baseline = registrar
+                .voting_config
+                .baseline_vote_weight(d_entry.get_amount_deposited_native())?;
+            let vesting = lockup.kind.is_vesting().then(|| VestingInfo {
+                rate: d_entry
+                    .get_amount_initially_locked_native()
+                    .checked_div(periods_total)
+                    .unwrap(),
+                // nothing vests before the end of the cliff
+                next_timestamp: lockup
+                    .period_end_ts(periods_total - periods_left + 1)
+                    .max(lockup.cliff_end_ts()) as u64,
+            });
+
+            deposit_entries.push(Some(DepositEntryInfo {
+                lockup: d_entry.get_lockup(),
+                amount_locked,
+                amount_unlocked,
+                voting_power,
+                voting_power_baseline,
+                reward_weight,
+                vesting,
+            }));
+        } else {
+            deposit_entries.push(None);
+        }
+    }
+
+    // project the reward accrual up to now
+    let mut accrued_registrar = *registrar;
+    accrued_registrar.accrue_rewards(curr_ts);
+
+    let reward_amount = voter
+        .get_reward_claimable_amount()
+        .checked_add(
+            accrued_registrar
+                .reward_index
+                .sub(voter.get_reward_index())
+                .mul_scalar(voter.accrual_reward_weight(curr_ts, registrar)? as core::primitive::u128)
+                .truncate() as u64,
+        )
+        .unwrap();
+
+    let node_deposit = voter.deposit_entry_at(NODE_DEPOSIT_ENTRY_INDEX)?;
+    let node_non_compliant = match node {
+        Some(node) if node_deposit.is_active() => {
+            !node.is_deposit_compliant(node_deposit.get_amount_deposited_native(), curr_ts, registrar)
+        }
+        _ => false,
+    };
+
+    Ok(VoterView {
+        voting_power: voter.weight(curr_ts, registrar)?,
+        voting_power_baseline: voter.weight_baseline(registrar)?,
+        reward_weight: voter.reward_weight(curr_ts, registrar)?,
+        reward_amount,
+        reward_runway_amount: accrued_registrar.unissued_funded_reward_amount(),
+        reward_runway_secs: accrued_registrar.reward_runway_secs(),
+        deposit_entry_count: voter.deposit_entry_count() as u8,
+        deposit_entries,
+        node_non_compliant,
+    })
+}
@@ -0,0 +1,20 @@
+use crate::state::*;
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct ViewVoterWeightAt<'info> {
+    pub registrar: AccountLoader<'info, Registrar>,
+
+    #[account(
+        constraint = voter_weight_history.load()?.registrar == registrar.key()
+    )]
+    pub voter_weight_history: AccountLoader<'info, VoterWeightHistory>,
+}
+
+/// A no-effect instruction that returns the vote weight the voter had at `ts`
+/// through `set_return_data`, None if `ts` is before the oldest checkpoint
+/// that is still kept. See `VoterWeightHistory::get_weight_at` and
+/// `views::cpi::view_voter_weight_at`.
+pub fn view_voter_weight_at(ctx: Context<ViewVoterWeightAt>, ts: i64) -> Result<Option<u64>> {
+    Ok(ctx.accounts.voter_weight_history.load()?.get_weight_at(ts))
+}
@@ -45,6 +45,10 @@ pub struct Withdraw<'info> {
     )]
     pub voter_weight_record: Account<'info, VoterWeightRecord>,
 
+    /// Required if the voter has a vote weight history, see `VoterWeightHistory`
+    #[account(mut)]
+    pub voter_weight_history: Option<AccountLoader<'info, VoterWeightHistory>>,
+
     #[account(
         mut,
         associated_token::authority = voter,
@@ -79,13 +83,20 @@ impl<'info> Withdraw<'info> {
 /// `deposit_entry_index`: The deposit entry to withdraw from.
 /// `amount` is in units of the native currency being withdrawn.
 pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+    ctx.accounts
+        .registrar
+        .load()?
+        .assert_not_paused(PAUSE_WITHDRAWS)?;
+
     {
         // Transfer the tokens to withdraw.
-        let voter = &ctx.accounts.voter.load()?;
+        let voter = &ctx.accounts.voter.load_voter()?;
         require!(
             voter.is_active(deposit_entry_index)?,
             VsrError::InactiveDepositEntry
         );
+        // The delegate's voter weight record may still count the delegated tokens.
+        require!(!voter.is_delegated(), VsrError::VoterDelegated);
 
         token::transfer(
             ctx.accounts
@@ -97,7 +108,7 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
 
     // Load the accounts.
     let registrar = &mut ctx.accounts.registrar.load_mut()?;
-    let voter = &mut ctx.accounts.voter.load_mut()?;
+    let voter = &mut ctx.accounts.voter.load_voter_mut()?;
 
     // Governance may forbid withdraws, for example when engaged in a vote.
     let token_owner_record = load_token_owner_record(
@@ -124,6 +135,15 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
     record.voter_weight = voter.weight(curr_ts, registrar)?;
     record.voter_weight_expiry = Some(Clock::get()?.slot);
 
+    // Record the vote weight
+    record_voter_weight(
+        ctx.accounts.voter_weight_history.as_ref(),
+        &ctx.accounts.registrar.key(),
+        voter,
+        curr_ts,
+        registrar,
+    )?;
+
     emit!(WithdrawEvent {
         registrar: ctx.accounts.registrar.key(),
         voter: voter.get_voter_authority(),
@@ -134,6 +154,27 @@ pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) ->
     Ok(())
 }
 
+/// Forbids reducing or restructuring locked tokens while governance forbids
+/// withdrawing them, for example when the voter is engaged in a vote.
+///
+/// Only applies if the registrar has 'restrict_lockup_changes' set, the
+/// voter's token_owner_record is required then.
+pub fn assert_can_change_lockup(
+    token_owner_record: Option<&AccountInfo>,
+    voter: &Voter,
+    registrar: &Registrar,
+) -> Result<()> {
+    if registrar.restrict_lockup_changes == 0 {
+        return Ok(());
+    }
+
+    let account_info =
+        token_owner_record.ok_or_else(|| error!(VsrError::TokenOwnerRecordRequired))?;
+    load_token_owner_record(account_info, voter, registrar)?
+        .assert_can_withdraw_governing_tokens()?;
+    Ok(())
+}
+
 pub fn load_token_owner_record(
     account_info: &AccountInfo,
     voter: &Voter,
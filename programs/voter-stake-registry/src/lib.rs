@@ -1,6 +1,7 @@
 use anchor_lang::prelude::*;
 use instructions::*;
 use state::*;
+use views::*;
 use crate::circuit_breaker::WindowedCircuitBreakerConfigV0;
 
 pub mod error;
@@ -8,6 +9,7 @@ pub mod events;
 pub mod governance;
 pub mod instructions;
 pub mod state;
+pub mod views;
 pub mod circuit_breaker;
 
 #[macro_use]
@@ -70,9 +72,14 @@ pub mod voter_stake_registry {
         max_voter_weight_record_bump: u8,
         voting_config: VotingConfig,
         deposit_config: DepositConfig,
+        emission_config: EmissionConfig,
         circuit_breaker_config: WindowedCircuitBreakerConfigV0,
     ) -> Result<()> {
-        instructions::create_registrar(ctx, registrar_bump, max_voter_weight_record_bump, voting_config, deposit_config, circuit_breaker_config)
+        instructions::create_registrar(ctx, registrar_bump, max_voter_weight_record_bump, voting_config, deposit_config, emission_config, circuit_breaker_config)
+    }
+
+    pub fn create_registrar_stats(ctx: Context<CreateRegistrarStats>) -> Result<()> {
+        instructions::create_registrar_stats(ctx)
     }
 
     pub fn create_voter(
@@ -83,6 +90,10 @@ pub mod voter_stake_registry {
         instructions::create_voter(ctx, voter_bump, voter_weight_record_bump)
     }
 
+    pub fn create_voter_weight_history(ctx: Context<CreateVoterWeightHistory>) -> Result<()> {
+        instructions::create_voter_weight_history(ctx)
+    }
+
     pub fn node_deposit(ctx: Context<NodeDeposit>) -> Result<()> {
         instructions::node_deposit(ctx)
     }
@@ -94,13 +105,58 @@ pub mod voter_stake_registry {
         instructions::node_release_deposit(ctx, target_deposit_entry_index)
     }
 
+    pub fn node_top_up_deposit(ctx: Context<NodeTopUpDeposit>) -> Result<()> {
+        instructions::node_top_up_deposit(ctx)
+    }
+
+    pub fn slash_node_deposit(
+        ctx: Context<SlashNodeDeposit>,
+        amount: u64,
+        target_deposit_entry_index: u8,
+    ) -> Result<()> {
+        instructions::slash_node_deposit(ctx, amount, target_deposit_entry_index)
+    }
+
+    pub fn update_node(ctx: Context<UpdateNode>, operator: Pubkey, endpoint: String) -> Result<()> {
+        instructions::update_node(ctx, operator, endpoint)
+    }
+
+    pub fn update_node_compliance(ctx: Context<UpdateNodeCompliance>) -> Result<()> {
+        instructions::update_node_compliance(ctx)
+    }
+
+    pub fn set_node_status(ctx: Context<SetNodeStatus>, status: NodeStatus) -> Result<()> {
+        instructions::set_node_status(ctx, status)
+    }
+
     pub fn ordinary_deposit(
         ctx: Context<OrdinaryDeposit>,
         deposit_entry_index: u8,
         amount: u64,
-        duration: LockupTimeDuration
+        duration: LockupTimeDuration,
     ) -> Result<()> {
-        instructions::ordinary_deposit(ctx, deposit_entry_index, amount, duration)
+        instructions::ordinary_deposit(
+            ctx,
+            deposit_entry_index,
+            amount,
+            duration,
+            LockupKindKind::Constant,
+        )
+    }
+
+    pub fn ordinary_cliff_deposit(
+        ctx: Context<OrdinaryDeposit>,
+        deposit_entry_index: u8,
+        amount: u64,
+        duration: LockupTimeDuration,
+    ) -> Result<()> {
+        instructions::ordinary_deposit(
+            ctx,
+            deposit_entry_index,
+            amount,
+            duration,
+            LockupKindKind::Cliff,
+        )
     }
 
     pub fn ordinary_release_deposit(
@@ -117,22 +173,105 @@ pub mod voter_stake_registry {
         )
     }
 
-    pub fn update_voter_weight_record(ctx: Context<UpdateVoterWeightRecord>) -> Result<()> {
-        instructions::update_voter_weight_record(ctx)
+    pub fn merge_deposit_entries(
+        ctx: Context<MergeDepositEntries>,
+        deposit_entry_index: u8,
+        target_deposit_entry_index: u8,
+    ) -> Result<()> {
+        instructions::merge_deposit_entries(ctx, deposit_entry_index, target_deposit_entry_index)
+    }
+
+    pub fn split_deposit_entry(
+        ctx: Context<SplitDepositEntry>,
+        deposit_entry_index: u8,
+        target_deposit_entry_index: u8,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::split_deposit_entry(
+            ctx,
+            deposit_entry_index,
+            target_deposit_entry_index,
+            amount,
+        )
     }
 
-    pub fn update_max_vote_weight(ctx: Context<UpdateMaxVoteWeight>) -> Result<()> {
+    pub fn grant(
+        ctx: Context<Grant>,
+        deposit_entry_index: u8,
+        kind: LockupKindKind,
+        periods: u64,
+        start_ts: Option<u64>,
+        amount: u64,
+        allow_clawback: bool,
+    ) -> Result<()> {
+        instructions::grant(
+            ctx,
+            deposit_entry_index,
+            kind,
+            periods,
+            start_ts,
+            amount,
+            allow_clawback,
+        )
+    }
+
+    pub fn clawback(ctx: Context<Clawback>, deposit_entry_index: u8) -> Result<()> {
+        instructions::clawback(ctx, deposit_entry_index)
+    }
+
+    pub fn resize_voter(ctx: Context<ResizeVoter>, deposit_entry_count: u8) -> Result<()> {
+        instructions::resize_voter(ctx, deposit_entry_count)
+    }
+
+    pub fn update_voter_weight_record<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateVoterWeightRecord<'info>>,
+        weight_action: Option<VoterWeightAction>,
+        weight_action_target: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_voter_weight_record(ctx, weight_action, weight_action_target)
+    }
+
+    pub fn update_max_vote_weight<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdateMaxVoteWeight<'info>>,
+    ) -> Result<()> {
         instructions::update_max_vote_weight(ctx)
     }
 
+    pub fn update_max_vote_weight_mode(
+        ctx: Context<UpdateMaxVoteWeightMode>,
+        mode: MaxVoteWeightMode,
+        excluded_token_accounts: Vec<Pubkey>,
+    ) -> Result<()> {
+        instructions::update_max_vote_weight_mode(ctx, mode, excluded_token_accounts)
+    }
+
+    pub fn update_uncounted_voter_count(
+        ctx: Context<UpdateUncountedVoterCount>,
+        uncounted_voter_count: u64,
+    ) -> Result<()> {
+        instructions::update_uncounted_voter_count(ctx, uncounted_voter_count)
+    }
+
     pub fn close_voter<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVoter<'info>>) -> Result<()> {
         instructions::close_voter(ctx)
     }
 
+    pub fn migrate_registrar(ctx: Context<MigrateRegistrar>) -> Result<()> {
+        instructions::migrate_registrar(ctx)
+    }
+
+    pub fn migrate_voter(ctx: Context<MigrateVoter>) -> Result<()> {
+        instructions::migrate_voter(ctx)
+    }
+
     pub fn set_time_offset(ctx: Context<SetTimeOffset>, time_offset: i64) -> Result<()> {
         instructions::set_time_offset(ctx, time_offset)
     }
 
+    pub fn update_registrar_authority(ctx: Context<UpdateRegistrarAuthority>) -> Result<()> {
+        instructions::update_registrar_authority(ctx)
+    }
+
     pub fn update_deposit_config(ctx: Context<UpdateDepositConfig>, deposit_config: DepositConfig) -> Result<()> {
         instructions::update_deposit_config(ctx, deposit_config)
     }
@@ -141,15 +280,123 @@ pub mod voter_stake_registry {
         instructions::update_voting_config(ctx, voting_config)
     }
 
+    pub fn update_emission_config(ctx: Context<UpdateEmissionConfig>, emission_config: EmissionConfig) -> Result<()> {
+        instructions::update_emission_config(ctx, emission_config)
+    }
+
+    pub fn update_restrict_lockup_changes(
+        ctx: Context<UpdateRestrictLockupChanges>,
+        restrict_lockup_changes: bool,
+    ) -> Result<()> {
+        instructions::update_restrict_lockup_changes(ctx, restrict_lockup_changes)
+    }
+
+    pub fn update_reward_boost_config(
+        ctx: Context<UpdateRewardBoostConfig>,
+        reward_boost_config: RewardBoostConfig,
+    ) -> Result<()> {
+        instructions::update_reward_boost_config(ctx, reward_boost_config)
+    }
+
+    pub fn update_early_unlock_penalty_config(
+        ctx: Context<UpdateEarlyUnlockPenaltyConfig>,
+        early_unlock_penalty_config: EarlyUnlockPenaltyConfig,
+    ) -> Result<()> {
+        instructions::update_early_unlock_penalty_config(ctx, early_unlock_penalty_config)
+    }
+
+    pub fn update_release_cliff_config(
+        ctx: Context<UpdateReleaseCliffConfig>,
+        release_cliff_config: ReleaseCliffConfig,
+    ) -> Result<()> {
+        instructions::update_release_cliff_config(ctx, release_cliff_config)
+    }
+
+    pub fn update_slasher(ctx: Context<UpdateSlasher>, slasher: Pubkey) -> Result<()> {
+        instructions::update_slasher(ctx, slasher)
+    }
+
+    pub fn update_guardian(ctx: Context<UpdateGuardian>, guardian: Pubkey) -> Result<()> {
+        instructions::update_guardian(ctx, guardian)
+    }
+
+    pub fn update_max_voting_time(
+        ctx: Context<UpdateMaxVotingTime>,
+        max_voting_time_secs: u32,
+    ) -> Result<()> {
+        instructions::update_max_voting_time(ctx, max_voting_time_secs)
+    }
+
+    pub fn set_paused(ctx: Context<SetPaused>, paused: u8) -> Result<()> {
+        instructions::set_paused(ctx, paused)
+    }
+
+    pub fn update_voter_weight_action_requirement(
+        ctx: Context<UpdateVoterWeightActionRequirement>,
+        action: VoterWeightAction,
+        requirement: VoterWeightActionRequirement,
+    ) -> Result<()> {
+        instructions::update_voter_weight_action_requirement(ctx, action, requirement)
+    }
+
     pub fn withdraw(ctx: Context<Withdraw>, deposit_entry_index: u8, amount: u64) -> Result<()> {
         instructions::withdraw(ctx, deposit_entry_index, amount)
     }
 
+    pub fn early_unlock(ctx: Context<EarlyUnlock>, deposit_entry_index: u8, amount: u64) -> Result<()> {
+        instructions::early_unlock(ctx, deposit_entry_index, amount)
+    }
+
     pub fn claim_reward(ctx: Context<ClaimReward>, amount: Option<u64>) -> Result<()> {
         instructions::claim_reward(ctx, amount)
     }
 
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        instructions::fund_rewards(ctx, amount)
+    }
+
+    pub fn compound_reward(
+        ctx: Context<CompoundReward>,
+        deposit_entry_index: u8,
+        amount: Option<u64>,
+        duration: LockupTimeDuration,
+    ) -> Result<()> {
+        instructions::compound_reward(ctx, deposit_entry_index, amount, duration)
+    }
+
+    pub fn refresh_reward_weight(ctx: Context<RefreshRewardWeight>) -> Result<()> {
+        instructions::refresh_reward_weight(ctx)
+    }
+
+    pub fn log_registrar_info(ctx: Context<LogRegistrarInfo>) -> Result<()> {
+        instructions::log_registrar_info(ctx)
+    }
+
     pub fn log_voter_info(ctx: Context<LogVoterInfo>) -> Result<()> {
         instructions::log_voter_info(ctx)
     }
+
+    pub fn view_registrar_info(ctx: Context<ViewRegistrarInfo>) -> Result<RegistrarView> {
+        instructions::view_registrar_info(ctx)
+    }
+
+    pub fn view_voter_info(ctx: Context<ViewVoterInfo>, first_deposit_entry_index: u8) -> Result<VoterView> {
+        instructions::view_voter_info(ctx, first_deposit_entry_index)
+    }
+
+    pub fn view_voter_weight_at(ctx: Context<ViewVoterWeightAt>, ts: i64) -> Result<Option<u64>> {
+        instructions::view_voter_weight_at(ctx, ts)
+    }
+
+    pub fn delegate(ctx: Context<Delegate>) -> Result<()> {
+        instructions::delegate(ctx)
+    }
+
+    pub fn request_undelegate(ctx: Context<RequestUndelegate>) -> Result<()> {
+        instructions::request_undelegate(ctx)
+    }
+
+    pub fn undelegate(ctx: Context<Undelegate>) -> Result<()> {
+        instructions::undelegate(ctx)
+    }
 }
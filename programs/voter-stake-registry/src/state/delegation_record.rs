@@ -0,0 +1,94 @@
+use anchor_lang::prelude::*;
+
+/// Record of a voter delegating its vote weight to another voter.
+///
+/// There can only be a single delegation record per delegator. While it
+/// exists, the delegator's vote weight is counted towards the delegate
+/// and the delegator itself can not vote with it.
+///
+/// Once the delegator requested the undelegation through 'request_undelegate',
+/// the vote weight is no longer counted towards the delegate, see
+/// `DelegationRecord::can_undelegate_without_delegate`.
+#[account(zero_copy)]
+pub struct DelegationRecord {
+    pub registrar: Pubkey,
+    /// Voter authority of the delegating voter
+    pub delegator: Pubkey,
+    /// Voter authority of the voter receiving the vote weight
+    pub delegate: Pubkey,
+
+    pub bump: u8,
+    /// Non-zero while delegated_weight is included in the delegate's vote
+    /// weight history. Zero if the delegate had no history when delegating,
+    /// or once 'request_undelegate' removed it.
+    pub counted_in_weight_history: u8,
+    pub reserved1: [u8; 6],
+    /// Vote weight of the delegator when delegating, which was added to the
+    /// delegate's vote weight history, see `VoterWeightCheckpoint`
+    pub delegated_weight: u64,
+    /// Time of the 'request_undelegate', zero if not requested
+    pub undelegation_requested_ts: i64,
+    pub reserved2: [u64; 2],
+}
+const_assert!(std::mem::size_of::<DelegationRecord>() == 3 * 32 + 1 + 7 + 8 + 8 + 2 * 8);
+const_assert!(std::mem::size_of::<DelegationRecord>() % 8 == 0);
+
+impl DelegationRecord {
+    #[inline(always)]
+    pub fn is_counted_in_weight_history(&self) -> bool {
+        self.counted_in_weight_history != 0
+    }
+
+    #[inline(always)]
+    pub fn is_undelegation_requested(&self) -> bool {
+        self.undelegation_requested_ts != 0
+    }
+
+    /// True if the delegate can no longer be engaged in a vote with the
+    /// delegated vote weight, so the delegator may undelegate regardless of
+    /// the delegate's governance state.
+    ///
+    /// The vote weight stops counting towards the delegate when the
+    /// undelegation is requested, any vote cast with it ended once the max
+    /// voting time of the realm's governances passed since then. Never true
+    /// while no max voting time is configured.
+    pub fn can_undelegate_without_delegate(&self, max_voting_time_secs: u32, curr_ts: i64) -> bool {
+        max_voting_time_secs > 0
+            && self.is_undelegation_requested()
+            && curr_ts
+                >= self
+                    .undelegation_requested_ts
+                    .checked_add(max_voting_time_secs as i64)
+                    .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    #[test]
+    fn can_undelegate_without_delegate_test() {
+        let mut record = DelegationRecord::zeroed();
+        let max_voting_time_secs = 3 * 86_400;
+        assert!(!record.can_undelegate_without_delegate(max_voting_time_secs, i64::MAX));
+
+        // a delegate engaged in a vote can not block the undelegation forever
+        let requested_ts = 1_700_000_000;
+        record.undelegation_requested_ts = requested_ts;
+        assert!(record.is_undelegation_requested());
+        assert!(!record.can_undelegate_without_delegate(max_voting_time_secs, requested_ts));
+        assert!(!record.can_undelegate_without_delegate(
+            max_voting_time_secs,
+            requested_ts + max_voting_time_secs as i64 - 1
+        ));
+        assert!(record.can_undelegate_without_delegate(
+            max_voting_time_secs,
+            requested_ts + max_voting_time_secs as i64
+        ));
+
+        // not without a max voting time
+        assert!(!record.can_undelegate_without_delegate(0, i64::MAX));
+    }
+}
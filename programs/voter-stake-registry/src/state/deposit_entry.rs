@@ -1,5 +1,5 @@
-use crate::state::lockup::Lockup;
-use crate::state::registrar::VotingConfig;
+use crate::state::lockup::{Lockup, LockupKindKind};
+use crate::state::registrar::{RewardBoostConfig, VoteWeightCurve, VotingConfig, BPS_BASE};
 use crate::error::*;
 use anchor_lang::prelude::*;
 use std::cmp::min;
@@ -8,6 +8,13 @@ use std::convert::TryFrom;
 const ACTIVE_FLAG: u8 = 1;
 const INACTIVE_FLAG: u8 = 0;
 
+const CLAWBACK_ALLOWED_FLAG: u8 = 1;
+const CLAWBACK_DISALLOWED_FLAG: u8 = 0;
+
+/// Layout version of deposit entries activated by this program, see
+/// `DepositEntry::migrate`
+pub const DEPOSIT_ENTRY_VERSION: u8 = 1;
+
 /// Bookkeeping for a single deposit for a given mint and lockup schedule.
 // #[derive(AnchorSerialize, AnchorDeserialize, Default, Clone, Copy)]
 #[zero_copy]
@@ -36,10 +43,18 @@ pub struct DepositEntry {
     // True if the deposit entry is being active.
     is_active: u8,
 
-    reserved1: [u8; 7],
+    // True if the realm authority may claw back the locked tokens.
+    // Only set for deposit entries funded through 'grant'.
+    allow_clawback: u8,
+
+    // Layout version of an active deposit entry, zero for deposit entries
+    // activated before it was introduced. Migrated along with the voter.
+    version: u8,
+
+    reserved1: [u8; 5],
     reserved2: [u64; 4],
 }
-const_assert!(std::mem::size_of::<DepositEntry>() == 32 + 2 * 8 + 1 + 7 + 4 * 8);
+const_assert!(std::mem::size_of::<DepositEntry>() == 32 + 2 * 8 + 1 + 1 + 1 + 5 + 4 * 8);
 const_assert!(std::mem::size_of::<DepositEntry>() % 8 == 0);
 
 /// impl: factory function and getters
@@ -50,7 +65,9 @@ impl DepositEntry {
             amount_deposited_native: 0,
             amount_initially_locked_native: 0,
             is_active: ACTIVE_FLAG,
-            reserved1: [0; 7],
+            allow_clawback: CLAWBACK_DISALLOWED_FLAG,
+            version: DEPOSIT_ENTRY_VERSION,
+            reserved1: [0; 5],
             reserved2: [0; 4],
         })
     }
@@ -78,6 +95,43 @@ impl DepositEntry {
         self.is_active == ACTIVE_FLAG
     }
 
+    #[inline(always)]
+    pub fn is_clawback_allowed(&self) -> bool {
+        self.allow_clawback == CLAWBACK_ALLOWED_FLAG
+    }
+
+    pub fn allow_clawback(&mut self) -> Result<()> {
+        require!(self.is_active(), VsrError::InternalProgramError);
+
+        self.allow_clawback = CLAWBACK_ALLOWED_FLAG;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    /// Transforms the deposit entry from its layout version up to
+    /// `DEPOSIT_ENTRY_VERSION`, one version at a time.
+    pub fn migrate(&mut self) -> Result<()> {
+        require_gte!(
+            DEPOSIT_ENTRY_VERSION,
+            self.version,
+            VsrError::UnsupportedLayoutVersion
+        );
+
+        while self.version < DEPOSIT_ENTRY_VERSION {
+            match self.version {
+                // version 1 only introduced the version itself
+                0 => {}
+                _ => unreachable!(),
+            }
+            self.version += 1;
+        }
+        Ok(())
+    }
+
     /// Caution: this is a dangerous operation
     pub fn deactivate(&mut self) -> Result<()> {
         require!(self.is_active(), VsrError::InternalProgramError);
@@ -86,6 +140,7 @@ impl DepositEntry {
         self.amount_deposited_native = 0;
         self.amount_initially_locked_native = 0;
         self.is_active = INACTIVE_FLAG;
+        self.allow_clawback = CLAWBACK_DISALLOWED_FLAG;
         Ok(())
     }
 
@@ -135,6 +190,28 @@ impl DepositEntry {
         Ok(())
     }
 
+    /// Takes the locked tokens out of the deposit, returns the amount taken.
+    ///
+    /// The remaining tokens are unlocked and can be withdrawn.
+    pub fn clawback(&mut self, curr_ts: i64) -> Result<u64> {
+        require!(self.is_active(), VsrError::InternalProgramError);
+        require!(
+            self.is_clawback_allowed(),
+            VsrError::ClawbackNotAllowedOnDeposit
+        );
+
+        let amount_locked = self.amount_locked(curr_ts)?;
+        self.amount_deposited_native = self
+            .amount_deposited_native
+            .checked_sub(amount_locked)
+            .unwrap();
+
+        // Nothing is locked anymore
+        self.amount_initially_locked_native = 0;
+        self.allow_clawback = CLAWBACK_DISALLOWED_FLAG;
+        Ok(amount_locked)
+    }
+
     /// # Voting Power Caclulation
     ///
     /// Returns the voting power for the deposit, giving locked tokens boosted
@@ -172,6 +249,12 @@ impl DepositEntry {
     /// Daily/monthly linear vesting can be calculated with series sum, see
     /// voting_power_linear_vesting() below.
     ///
+    /// ## Vote Weight Curve
+    ///
+    /// With a piecewise-linear VoteWeightCurve, lockup_duration_factor is the
+    /// curve's value for the remaining lockup time instead, for cliff-locked
+    /// tokens and each vesting cliff of linear vesting alike.
+    ///
     pub fn voting_power(&self, voting_config: &VotingConfig, curr_ts: i64) -> Result<u64> {
         require!(self.is_active(), VsrError::InternalProgramError);
 
@@ -183,6 +266,7 @@ impl DepositEntry {
             curr_ts,
             max_locked_vote_weight,
             voting_config.lockup_saturation_secs,
+            &voting_config.vote_weight_curve,
         )?;
         require_gte!(
             max_locked_vote_weight,
@@ -194,12 +278,147 @@ impl DepositEntry {
             .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
     }
 
+    /// Returns the reward weight for the deposit.
+    ///
+    /// Only locked tokens earn rewards. Each locked token has a reward weight
+    /// of 1, plus an extra reward weight that scales linearly with the lockup
+    /// time just like the extra lockup vote weight, see voting_power():
+    ///
+    ///    reward_weight = amount_locked
+    ///                    + lockup_duration_factor * max_extra_lockup_reward_weight
+    ///
+    /// Constant lockups never decay, hence keep their reward weight, while
+    /// vesting lockups lose it as their tokens unlock. Cliff lockups lose the
+    /// extra reward weight as their end approaches, and all of it at the end.
+    pub fn reward_weight(&self, reward_boost_config: &RewardBoostConfig, curr_ts: i64) -> Result<u64> {
+        require!(self.is_active(), VsrError::InternalProgramError);
+
+        let max_locked_reward_weight = reward_boost_config
+            .max_extra_lockup_reward_weight(self.amount_initially_locked_native)?;
+        let locked_reward_weight = self.voting_power_locked(
+            curr_ts,
+            max_locked_reward_weight,
+            reward_boost_config.lockup_saturation_secs,
+            &VoteWeightCurve::default(),
+        )?;
+        self.amount_locked(curr_ts)?
+            .checked_add(locked_reward_weight)
+            .ok_or_else(|| error!(VsrError::VoterWeightOverflow))
+    }
+
+    /// Returns the reward weight of the deposit averaged over the time from
+    /// from_ts to to_ts, see reward_weight().
+    ///
+    /// The locked tokens and the extra reward weight of every vesting cliff
+    /// (just the one at the end for cliff lockups) are linear in time between
+    /// the points where the remaining time reaches lockup_saturation_secs and
+    /// where the cliff vests. Their average is the mean of the reward weights
+    /// at the start and end of each such segment, weighted by its length,
+    /// which the sums of `vesting_cliff_secs` add up for many cliffs at once.
+    pub fn average_reward_weight(
+        &self,
+        reward_boost_config: &RewardBoostConfig,
+        from_ts: i64,
+        to_ts: i64,
+    ) -> Result<u64> {
+        require!(self.is_active(), VsrError::InternalProgramError);
+        require_gte!(to_ts, from_ts, VsrError::InternalProgramError);
+
+        if to_ts == from_ts || self.lockup.is_constant() {
+            return self.reward_weight(reward_boost_config, to_ts);
+        }
+
+        let saturation_secs = reward_boost_config.lockup_saturation_secs;
+        // How much the sums of the vesting cliffs shrink from from_ts to to_ts
+        let elapsed_cliff_secs = |vesting_ts: i64, count: u64, step: u64| {
+            let from = vesting_cliff_secs(vesting_ts - from_ts, count, step, saturation_secs);
+            let to = vesting_cliff_secs(vesting_ts - to_ts, count, step, saturation_secs);
+            (from.0 - to.0, from.1 - to.1)
+        };
+
+        let periods_total = self.lockup.periods_total();
+        let (cliff_count, (locked_secs, lockup_secs_doubled)) = if !self.lockup.is_vesting() {
+            (1, elapsed_cliff_secs(self.lockup.end_ts(), 1, 0))
+        } else if self.lockup.kind.duration.is_calendar_mode() {
+            (periods_total, self.calendar_elapsed_cliff_secs(from_ts, to_ts, saturation_secs)?)
+        } else {
+            // The first cliff_periods vesting cliffs all vest at the end of
+            // the cliff, the others follow one period apart.
+            let cliff_periods = self.lockup.kind.cliff_periods();
+            let period_secs = self.lockup.kind.period_secs();
+            let cliff = elapsed_cliff_secs(self.lockup.cliff_end_ts(), cliff_periods, 0);
+            let vesting = elapsed_cliff_secs(
+                self.lockup.period_end_ts(cliff_periods + 1),
+                periods_total - cliff_periods,
+                period_secs,
+            );
+            (periods_total, (cliff.0 + vesting.0, cliff.1 + vesting.1))
+        };
+        if cliff_count == 0 {
+            return Ok(0);
+        }
+
+        let secs = (to_ts - from_ts) as u128;
+        let amount_locked = (self.amount_initially_locked_native as u128)
+            .checked_mul(locked_secs)
+            .unwrap()
+            .checked_div(secs.checked_mul(cliff_count as u128).unwrap())
+            .unwrap();
+        let max_locked_reward_weight = reward_boost_config
+            .max_extra_lockup_reward_weight(self.amount_initially_locked_native)?;
+        let locked_reward_weight = if max_locked_reward_weight == 0 {
+            0
+        } else {
+            (max_locked_reward_weight as u128)
+                .checked_mul(lockup_secs_doubled / secs)
+                .unwrap()
+                .checked_div(2 * cliff_count as u128 * saturation_secs as u128)
+                .unwrap()
+        };
+        u64::try_from(amount_locked + locked_reward_weight)
+            .map_err(|_| error!(VsrError::VoterWeightOverflow))
+    }
+
+    /// How much the sums of `vesting_cliff_secs` for a calendar mode lockup
+    /// shrink from from_ts to to_ts. Calendar periods vary in length, so the
+    /// vesting cliffs are summed up one by one until they are saturated.
+    fn calendar_elapsed_cliff_secs(
+        &self,
+        from_ts: i64,
+        to_ts: i64,
+        saturation_secs: u64,
+    ) -> Result<(u128, u128)> {
+        let periods_total = self.lockup.periods_total();
+        let cliff_periods = self.lockup.kind.cliff_periods();
+        let secs = (to_ts - from_ts) as u128;
+        let mut sums = (0u128, 0u128);
+
+        let mut period = self.lockup.period_current(from_ts)? + 1;
+        while period <= periods_total {
+            let vesting_ts = self.lockup.period_end_ts(period.max(cliff_periods));
+            if vesting_ts - to_ts >= saturation_secs as i64 {
+                // Saturated until to_ts, as are all later vesting cliffs
+                let count = (periods_total - period + 1) as u128;
+                sums.0 += count * secs;
+                sums.1 += count * 2 * saturation_secs as u128 * secs;
+                break;
+            }
+            let from = vesting_cliff_secs(vesting_ts - from_ts, 1, 0, saturation_secs);
+            let to = vesting_cliff_secs(vesting_ts - to_ts, 1, 0, saturation_secs);
+            sums.0 += from.0 - to.0;
+            sums.1 += from.1 - to.1;
+            period += 1;
+        }
+        Ok(sums)
+    }
+
     /// Vote power contribution from locked funds only.
     pub fn voting_power_locked(
         &self,
         curr_ts: i64,
         max_locked_vote_weight: u64,
         lockup_saturation_secs: u64,
+        curve: &VoteWeightCurve,
     ) -> Result<u64> {
         require!(self.is_active(), VsrError::InternalProgramError);
 
@@ -211,25 +430,40 @@ impl DepositEntry {
                 curr_ts,
                 max_locked_vote_weight,
                 lockup_saturation_secs,
+                curve,
             )
         } else {
-            self.voting_power_cliff(curr_ts, max_locked_vote_weight, lockup_saturation_secs)
+            self.voting_power_cliff(
+                curr_ts,
+                max_locked_vote_weight,
+                lockup_saturation_secs,
+                curve,
+            )
         }
     }
 
     /// Vote power contribution from funds with linear vesting.
+    ///
+    /// Constant lockups keep their full remaining time, cliff lockups decay
+    /// to zero at their end.
     fn voting_power_cliff(
         &self,
         curr_ts: i64,
         max_locked_vote_weight: u64,
         lockup_saturation_secs: u64,
+        curve: &VoteWeightCurve,
     ) -> Result<u64> {
         let remaining = min(self.lockup.seconds_left(curr_ts), lockup_saturation_secs);
+        let (numerator, denominator) = if curve.is_linear() {
+            (remaining, lockup_saturation_secs)
+        } else {
+            (curve.weight_bps(remaining, lockup_saturation_secs), BPS_BASE)
+        };
         Ok(u64::try_from(
             (max_locked_vote_weight as u128)
-                .checked_mul(remaining as u128)
+                .checked_mul(numerator as u128)
                 .unwrap()
-                .checked_div(lockup_saturation_secs as u128)
+                .checked_div(denominator as u128)
                 .unwrap(),
         )
         .unwrap())
@@ -241,6 +475,7 @@ impl DepositEntry {
         curr_ts: i64,
         max_locked_vote_weight: u64,
         lockup_saturation_secs: u64,
+        curve: &VoteWeightCurve,
     ) -> Result<u64> {
         let periods_left = self.lockup.periods_left(curr_ts)?;
         let periods_total = self.lockup.periods_total();
@@ -289,51 +524,90 @@ impl DepositEntry {
         //    sum_full_periods := \sum_0^q (p-1)
         //                      = q * (q - 1) / 2
         //
+        // With a vesting cliff, the first cliff_periods vesting cliffs all vest
+        // at the end of the cliff, each having secs_left_for_cliff_p =
+        // min(secs_to_cliff_end, lockup_saturation_secs) until then.
+        //
 
-        let secs_to_closest_cliff = self
-            .lockup
-            .seconds_left(curr_ts)
-            .checked_sub(
-                period_secs
-                    .checked_mul(periods_left.saturating_sub(1))
+        // With a piecewise-linear curve, each vesting cliff contributes
+        //    curve.weight_bps(secs_left_for_cliff_p) / BPS_BASE
+        // instead, and the sum is taken per segment of the curve, see
+        // VoteWeightCurve::sum_weight_bps().
+
+        let cliff_periods = self.lockup.kind.cliff_periods();
+        let cliff_end_ts = self.lockup.cliff_end_ts();
+        let in_cliff = cliff_periods > 0 && curr_ts < cliff_end_ts;
+
+        if !curve.is_linear() {
+            let weight_bps = |secs: u64| curve.weight_bps(secs, lockup_saturation_secs);
+            let weight_bps_sum = if self.lockup.kind.duration.is_calendar_mode() {
+                self.calendar_vesting_sum(curr_ts, periods_left, lockup_saturation_secs, weight_bps)
+            } else if in_cliff {
+                let secs_to_cliff_end = (cliff_end_ts - curr_ts) as u64;
+                (cliff_periods as u128)
+                    .checked_mul(weight_bps(secs_to_cliff_end) as u128)
+                    .unwrap()
+                    + curve.sum_weight_bps(
+                        secs_to_cliff_end.checked_add(period_secs).unwrap(),
+                        periods_total.checked_sub(cliff_periods).unwrap(),
+                        period_secs,
+                        lockup_saturation_secs,
+                    )
+            } else {
+                curve.sum_weight_bps(
+                    self.secs_to_closest_cliff(curr_ts, periods_left),
+                    periods_left,
+                    period_secs,
+                    lockup_saturation_secs,
+                )
+            };
+            let denominator = periods_total.checked_mul(BPS_BASE).unwrap();
+            return Ok(u64::try_from(
+                (max_locked_vote_weight as u128)
+                    .checked_mul(weight_bps_sum)
+                    .unwrap()
+                    .checked_div(denominator as u128)
                     .unwrap(),
             )
-            .unwrap();
-
-        if secs_to_closest_cliff >= lockup_saturation_secs {
-            return Ok(max_locked_vote_weight);
+            .unwrap());
         }
 
         // In the example above, periods_total was 5.
         let denominator = periods_total.checked_mul(lockup_saturation_secs).unwrap();
 
-        let lockup_saturation_periods = (lockup_saturation_secs
-            .saturating_sub(secs_to_closest_cliff)
-            .checked_add(period_secs)
-            .unwrap())
-        .checked_div(period_secs)
-        .unwrap();
-        let q = min(lockup_saturation_periods, periods_left);
-        let r = periods_left.saturating_sub(q);
+        let lockup_secs = if self.lockup.kind.duration.is_calendar_mode() {
+            self.calendar_vesting_sum(curr_ts, periods_left, lockup_saturation_secs, |secs| {
+                min(secs, lockup_saturation_secs)
+            })
+        } else if in_cliff {
+            // Before the cliff has passed, the first cliff_periods vesting cliffs
+            // are all at the end of the cliff, the remaining ones follow one
+            // period apart as usual.
+            let secs_to_cliff_end = (cliff_end_ts - curr_ts) as u64;
+            let lockup_secs_cliff = (cliff_periods as u128)
+                .checked_mul(min(secs_to_cliff_end, lockup_saturation_secs) as u128)
+                .unwrap();
+            lockup_secs_cliff
+                + linear_vesting_lockup_secs(
+                    secs_to_cliff_end.checked_add(period_secs).unwrap(),
+                    periods_total.checked_sub(cliff_periods).unwrap(),
+                    period_secs,
+                    lockup_saturation_secs,
+                )
+        } else {
+            let secs_to_closest_cliff = self.secs_to_closest_cliff(curr_ts, periods_left);
 
-        // Sum of the full periods left for all remaining vesting cliffs.
-        //
-        // Examples:
-        // - if there are 3 periods left, meaning three vesting cliffs in the future:
-        //   one has only a fractional period left and contributes 0
-        //   the next has one full period left
-        //   and the next has two full periods left
-        //   so sums to 3 = 3 * 2 / 2
-        // - if there's only one period left, the sum is 0
-        let sum_full_periods = q.checked_mul(q.saturating_sub(1)).unwrap() / 2;
-
-        // Total number of seconds left over all periods_left remaining vesting cliffs
-        let lockup_secs_fractional = q.checked_mul(secs_to_closest_cliff).unwrap();
-        let lockup_secs_full = sum_full_periods.checked_mul(period_secs).unwrap();
-        let lockup_secs_saturated = r.checked_mul(lockup_saturation_secs).unwrap();
-        let lockup_secs = lockup_secs_fractional as u128
-            + lockup_secs_full as u128
-            + lockup_secs_saturated as u128;
+            if secs_to_closest_cliff >= lockup_saturation_secs {
+                return Ok(max_locked_vote_weight);
+            }
+
+            linear_vesting_lockup_secs(
+                secs_to_closest_cliff,
+                periods_left,
+                period_secs,
+                lockup_saturation_secs,
+            )
+        };
 
         Ok(u64::try_from(
             (max_locked_vote_weight as u128)
@@ -345,6 +619,63 @@ impl DepositEntry {
         .unwrap())
     }
 
+    /// Seconds to the closest upcoming vesting cliff of a lockup with fixed
+    /// length periods, after its cliff has passed.
+    fn secs_to_closest_cliff(&self, curr_ts: i64, periods_left: u64) -> u64 {
+        self.lockup
+            .seconds_left(curr_ts)
+            .checked_sub(
+                self.lockup
+                    .kind
+                    .period_secs()
+                    .checked_mul(periods_left.saturating_sub(1))
+                    .unwrap(),
+            )
+            .unwrap()
+    }
+
+    /// Sum of 'weight' over the seconds left for all remaining vesting cliffs
+    /// of a calendar mode lockup.
+    ///
+    /// Calendar periods vary in length, so the vesting cliffs are summed up
+    /// one by one until they are saturated.
+    fn calendar_vesting_sum(
+        &self,
+        curr_ts: i64,
+        periods_left: u64,
+        lockup_saturation_secs: u64,
+        weight: impl Fn(u64) -> u64,
+    ) -> u128 {
+        let periods_total = self.lockup.periods_total();
+        let cliff_periods = self.lockup.kind.cliff_periods();
+        let mut period = periods_total - periods_left + 1;
+        let mut sum: u128 = 0;
+
+        // Before the cliff has passed, the first cliff_periods vesting cliffs
+        // are all at the end of the cliff.
+        if period <= cliff_periods {
+            let secs_to_cliff_end = (self.lockup.cliff_end_ts() - curr_ts) as u64;
+            sum = (cliff_periods as u128)
+                .checked_mul(weight(secs_to_cliff_end) as u128)
+                .unwrap();
+            period = cliff_periods + 1;
+        }
+
+        while period <= periods_total {
+            let secs_left = (self.lockup.period_end_ts(period) - curr_ts) as u64;
+            if secs_left >= lockup_saturation_secs {
+                let periods_saturated = periods_total - period + 1;
+                return sum
+                    + (periods_saturated as u128)
+                        .checked_mul(weight(lockup_saturation_secs) as u128)
+                        .unwrap();
+            }
+            sum += weight(secs_left) as u128;
+            period += 1;
+        }
+        sum
+    }
+
     /// Returns the amount of unlocked tokens for this deposit--in native units
     /// of the original token amount (not scaled by the exchange rate).
     pub fn vested(&self, curr_ts: i64) -> Result<u64> {
@@ -353,13 +684,19 @@ impl DepositEntry {
         if self.lockup.expired(curr_ts) {
             return Ok(self.amount_initially_locked_native);
         }
-        if self.lockup.kind.is_vesting() {
-            self.vested_linearly(curr_ts)
-        } else {
-            Ok(0)
+        match self.lockup.kind.kind {
+            LockupKindKind::Daily
+            | LockupKindKind::Monthly
+            | LockupKindKind::Weekly
+            | LockupKindKind::Yearly => self.vested_linearly(curr_ts),
+            // Cliff lockups unlock everything at once when they expire,
+            // constant lockups never do.
+            LockupKindKind::Constant | LockupKindKind::Cliff => Ok(0),
         }
     }
 
+    /// Nothing vests before the cliff has passed: period_current() stays 0 until
+    /// then and jumps to cliff_periods at the end of the cliff.
     fn vested_linearly(&self, curr_ts: i64) -> Result<u64> {
         let period_current = self.lockup.period_current(curr_ts)?;
         let periods_total = self.lockup.periods_total();
@@ -400,6 +737,100 @@ impl DepositEntry {
     }
 }
 
+/// Total number of seconds left over periods_left vesting cliffs that are one
+/// period apart, the closest one being secs_to_closest_cliff away, each capped
+/// at lockup_saturation_secs. See voting_power_linear_vesting().
+fn linear_vesting_lockup_secs(
+    secs_to_closest_cliff: u64,
+    periods_left: u64,
+    period_secs: u64,
+    lockup_saturation_secs: u64,
+) -> u128 {
+    if secs_to_closest_cliff >= lockup_saturation_secs {
+        return (periods_left as u128)
+            .checked_mul(lockup_saturation_secs as u128)
+            .unwrap();
+    }
+
+    let lockup_saturation_periods = (lockup_saturation_secs
+        .saturating_sub(secs_to_closest_cliff)
+        .checked_add(period_secs)
+        .unwrap())
+    .checked_div(period_secs)
+    .unwrap();
+    let q = min(lockup_saturation_periods, periods_left);
+    let r = periods_left.saturating_sub(q);
+
+    // Sum of the full periods left for all remaining vesting cliffs.
+    //
+    // Examples:
+    // - if there are 3 periods left, meaning three vesting cliffs in the future:
+    //   one has only a fractional period left and contributes 0
+    //   the next has one full period left
+    //   and the next has two full periods left
+    //   so sums to 3 = 3 * 2 / 2
+    // - if there's only one period left, the sum is 0
+    let sum_full_periods = q.checked_mul(q.saturating_sub(1)).unwrap() / 2;
+
+    // Total number of seconds left over all periods_left remaining vesting cliffs
+    let lockup_secs_fractional = q.checked_mul(secs_to_closest_cliff).unwrap();
+    let lockup_secs_full = sum_full_periods.checked_mul(period_secs).unwrap();
+    let lockup_secs_saturated = r.checked_mul(lockup_saturation_secs).unwrap();
+    lockup_secs_fractional as u128 + lockup_secs_full as u128 + lockup_secs_saturated as u128
+}
+
+/// Sums over `count` vesting cliffs that are `step` seconds apart, the
+/// closest one being `secs_to_closest_cliff` away, of
+///   - the seconds left until they vest, and
+///   - twice the integral of min(secs_left, lockup_saturation_secs) over the
+///     seconds left until they vest.
+///
+/// Both shrink by the time integral of the locked tokens and of the lockup
+/// seconds of voting_power_linear_vesting() while time passes, and they are
+/// zero for vested cliffs.
+fn vesting_cliff_secs(
+    secs_to_closest_cliff: i64,
+    count: u64,
+    step: u64,
+    lockup_saturation_secs: u64,
+) -> (u128, u128) {
+    // Skip the vested cliffs
+    let vested = if secs_to_closest_cliff > 0 {
+        0
+    } else {
+        secs_to_closest_cliff
+            .unsigned_abs()
+            .checked_div(step)
+            .map_or(count, |periods| min(periods + 1, count))
+    };
+    let count = (count - vested) as u128;
+    if count == 0 {
+        return (0, 0);
+    }
+    let first = (secs_to_closest_cliff + (vested * step) as i64) as u128;
+    let (step, saturation) = (step as u128, lockup_saturation_secs as u128);
+
+    // The q cliffs up to the saturation contribute secs_left^2, the others
+    // saturation * (2 * secs_left - saturation)
+    let q = if first > saturation {
+        0
+    } else {
+        (saturation - first)
+            .checked_div(step)
+            .map_or(count, |periods| min(periods + 1, count))
+    };
+    let r = count - q;
+    let secs_left_sum = |first: u128, n: u128| n * first + step * (n * n.saturating_sub(1) / 2);
+
+    let unsaturated_secs = secs_left_sum(first, q);
+    let unsaturated = q * first * first
+        + first * step * q * q.saturating_sub(1)
+        + step * step * (q.saturating_sub(1) * q * (2 * q).saturating_sub(1) / 6);
+    let saturated_secs = secs_left_sum(first + q * step, r);
+    let saturated = 2 * saturation * saturated_secs - r * saturation * saturation;
+    (unsaturated_secs + saturated_secs, unsaturated + saturated)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -464,6 +895,7 @@ mod tests {
             baseline_vote_weight_scaled_factor: 1_000_000_000, // 1x
             max_extra_lockup_vote_weight_scaled_factor: 1_000_000_000, // 1x
             lockup_saturation_secs: saturation as u64,
+            vote_weight_curve: VoteWeightCurve::default(),
         };
 
         let baseline_vote_weight =
@@ -559,6 +991,34 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn daily_vested_with_cliff_test() -> Result<()> {
+        let day: i64 = i64::try_from(LockupTimeUnit::Day.seconds()).unwrap();
+        let lockup_start = 1; // arbitrary point
+        let mut entry = DepositEntry::new_from_lockup(Lockup::new_from_kind(
+            LockupKind::daily(4).with_cliff_periods(2),
+            0,
+            lockup_start,
+        )?)?;
+        entry.deposit(lockup_start, 10_000)?;
+
+        // nothing vests during the cliff
+        assert_eq!(entry.vested(lockup_start - 1)?, 0);
+        assert_eq!(entry.vested(lockup_start + day)?, 0);
+        assert_eq!(entry.vested(lockup_start + 2 * day - 1)?, 0);
+        assert_eq!(entry.amount_locked(lockup_start + 2 * day - 1)?, 10_000);
+
+        // the vesting of the cliff periods is deferred to the end of the cliff
+        assert_eq!(entry.vested(lockup_start + 2 * day)?, 5_000);
+        assert_eq!(entry.amount_unlocked(lockup_start + 2 * day)?, 5_000);
+        assert_eq!(entry.vested(lockup_start + 3 * day - 1)?, 5_000);
+        assert_eq!(entry.vested(lockup_start + 3 * day)?, 7_500);
+        assert_eq!(entry.vested(lockup_start + 4 * day)?, 10_000);
+        assert_eq!(entry.amount_locked(lockup_start + 4 * day)?, 0);
+
+        Ok(())
+    }
+
     #[test]
     pub fn monthly_vested_test() -> Result<()> {
         let month: i64 = i64::try_from(LockupTimeUnit::Month.seconds()).unwrap();
@@ -615,7 +1075,7 @@ mod tests {
     pub fn constant_vested_test() -> Result<()> {
         let month: i64 = i64::try_from(LockupTimeUnit::Month.seconds()).unwrap();
         let lockup_start = 1; // arbitrary point
-        let mut entry = DepositEntry::new_from_lockup(Lockup::new_from_kind(LockupKind::constant(LockupTimeDuration{periods: 1, unit: LockupTimeUnit::Month, filler: [0; 7]}), 0, lockup_start)?)?;
+        let mut entry = DepositEntry::new_from_lockup(Lockup::new_from_kind(LockupKind::constant(LockupTimeDuration{periods: 1, unit: LockupTimeUnit::Month, calendar_mode: 0, filler: [0; 6]}), 0, lockup_start)?)?;
         entry.deposit(lockup_start, 10_000)?;
 
         let mut vested = entry.vested(lockup_start - 1)?;
@@ -642,6 +1102,27 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn constant_vested_calendar_test() -> Result<()> {
+        let lockup_start = 1; // arbitrary point
+        for duration in [
+            LockupTimeDuration::calendar(1, LockupTimeUnit::Month),
+            LockupTimeDuration::new(2, LockupTimeUnit::Week),
+            LockupTimeDuration::calendar(1, LockupTimeUnit::Year),
+        ] {
+            let mut entry = DepositEntry::new_from_lockup(Lockup::new_from_kind(LockupKind::constant(duration), 0, lockup_start)?)?;
+            entry.deposit(lockup_start, 10_000)?;
+
+            for ts in [lockup_start - 1, lockup_start + 1, lockup_start + 2 * SECS_PER_YEAR as i64] {
+                assert_eq!(entry.vested(ts)?, 0);
+                assert_eq!(entry.amount_locked(ts)?, 10_000);
+                assert_eq!(entry.amount_unlocked(ts)?, 0);
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     pub fn withdraw_test() -> Result<()> {
         let day: i64 = i64::try_from(LockupTimeUnit::Day.seconds()).unwrap();
@@ -682,4 +1163,120 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    pub fn clawback_test() -> Result<()> {
+        let day: i64 = i64::try_from(LockupTimeUnit::Day.seconds()).unwrap();
+        let lockup_start = 0; // arbitrary point
+        let lockup_kind = LockupKind::daily(4);
+        let lockup = Lockup::new_from_kind(lockup_kind, lockup_start, lockup_start)?;
+
+        // Not allowed by default
+        let mut entry = DepositEntry::new_from_lockup(lockup)?;
+        entry.deposit(0, 10_000)?;
+        assert!(!entry.is_clawback_allowed());
+        assert_eq!(entry.clawback(lockup_start + day), Err(error!(VsrError::ClawbackNotAllowedOnDeposit)) as Result<u64>);
+
+        entry.allow_clawback()?;
+        assert!(entry.is_clawback_allowed());
+
+        // Withdraw some vested tokens first
+        let clawback_at = lockup_start + day;
+        entry.withdraw(clawback_at, 1_000)?;
+
+        // Only the locked tokens are clawed back
+        assert_eq!(entry.clawback(clawback_at)?, 7_500);
+        assert_eq!(entry.amount_deposited_native, 1_500);
+        assert_eq!(entry.amount_locked(clawback_at)?, 0);
+        assert_eq!(entry.amount_unlocked(clawback_at)?, 1_500);
+        assert_eq!(entry.amount_unlocked(clawback_at + 3 * day)?, 1_500);
+        assert!(!entry.is_clawback_allowed());
+
+        // At most once
+        assert_eq!(entry.clawback(clawback_at), Err(error!(VsrError::ClawbackNotAllowedOnDeposit)) as Result<u64>);
+
+        // Deactivating resets the flag
+        entry.allow_clawback()?;
+        entry.deactivate()?;
+        assert!(!entry.is_clawback_allowed());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn migrate_v0_to_v1_test() -> Result<()> {
+        let lockup = Lockup::new_from_kind(LockupKind::daily(2), 0, 1)?;
+        let mut entry = DepositEntry::new_from_lockup(lockup)?;
+        entry.deposit(0, 1_000)?;
+        entry.version = 0;
+
+        entry.migrate()?;
+        assert_eq!(DEPOSIT_ENTRY_VERSION, entry.get_version());
+        assert_eq!(1_000, entry.get_amount_deposited_native());
+        assert_eq!(1_000, entry.get_amount_initially_locked_native());
+
+        entry.version = DEPOSIT_ENTRY_VERSION + 1;
+        assert_eq!(entry.migrate(), Err(error!(VsrError::UnsupportedLayoutVersion)) as Result<()>);
+
+        Ok(())
+    }
+    #[test]
+    pub fn average_reward_weight_test() -> Result<()> {
+        let day = LockupTimeUnit::Day.seconds() as i64;
+        let config = RewardBoostConfig {
+            max_extra_lockup_reward_weight_scaled_factor: SCALED_FACTOR_BASE,
+            lockup_saturation_secs: 5 * day as u64 + 3_600,
+        };
+        // midnight, so that calendar months end on whole hours too
+        let start = 1_700_006_400;
+        let kinds = [
+            LockupKind::cliff(LockupTimeDuration::new(8, LockupTimeUnit::Day)),
+            LockupKind::daily(12),
+            LockupKind::daily(12).with_cliff_periods(4),
+            LockupKind::vesting(LockupTimeDuration::calendar(3, LockupTimeUnit::Month)),
+            LockupKind::vesting(LockupTimeDuration::calendar(3, LockupTimeUnit::Month))
+                .with_cliff_periods(1),
+        ];
+        let intervals = [
+            (start - 2 * day, start + day),
+            (start, start + 30 * day),
+            (start + 3 * day + 7 * 3_600, start + 9 * day),
+            (start + 40 * day, start + 100 * day),
+            (start + 200 * day, start + 300 * day),
+        ];
+        for kind in kinds {
+            let lockup = Lockup::new_from_kind(kind, start, start)?;
+            let mut entry = DepositEntry::new_from_lockup(lockup)?;
+            entry.deposit(start, 1_000_000_000)?;
+
+            for (from_ts, to_ts) in intervals {
+                // the midpoint rule is exact on the linear segments between
+                // breakpoints, which are all whole hours apart
+                let step = 3_600;
+                let mut sum: core::primitive::u128 = 0;
+                for ts in (from_ts..to_ts).step_by(step as usize) {
+                    sum += core::primitive::u128::from(entry.reward_weight(&config, ts + step / 2)?);
+                }
+                let expected = (sum * step as core::primitive::u128
+                    / (to_ts - from_ts) as core::primitive::u128) as u64;
+                let average = entry.average_reward_weight(&config, from_ts, to_ts)?;
+                assert!(
+                    average.abs_diff(expected) <= expected / 100_000 + 1,
+                    "{} != {}",
+                    average,
+                    expected
+                );
+                // not more than the reward weight at the start
+                assert!(average <= entry.reward_weight(&config, from_ts)?);
+            }
+
+            // the reward weight at a point in time
+            assert_eq!(
+                entry.average_reward_weight(&config, start + day, start + day)?,
+                entry.reward_weight(&config, start + day)?
+            );
+        }
+
+        Ok(())
+    }
 }
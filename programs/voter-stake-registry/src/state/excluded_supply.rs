@@ -0,0 +1,87 @@
+use crate::error::*;
+use anchor_lang::prelude::*;
+
+/// The maximum count of token accounts in an `ExcludedSupply`
+pub const MAX_EXCLUDED_TOKEN_ACCOUNTS: usize = 8;
+
+/// Token accounts of the governing token mint whose tokens can never vote,
+/// such as the reward vault or the treasury. Their balances are not counted
+/// by `MaxVoteWeightMode::CirculatingSupply`.
+///
+/// There can only be a single one per registrar, it is created by
+/// 'update_max_vote_weight_mode'.
+#[account(zero_copy)]
+pub struct ExcludedSupply {
+    pub registrar: Pubkey,
+
+    pub bump: u8,
+    /// Number of used entries in 'token_accounts'
+    pub token_account_count: u8,
+    pub reserved1: [u8; 6],
+
+    pub token_accounts: [Pubkey; MAX_EXCLUDED_TOKEN_ACCOUNTS],
+    pub reserved2: [u64; 4],
+}
+const_assert!(
+    std::mem::size_of::<ExcludedSupply>() == 32 + 1 + 1 + 6 + MAX_EXCLUDED_TOKEN_ACCOUNTS * 32 + 4 * 8
+);
+const_assert!(std::mem::size_of::<ExcludedSupply>() % 8 == 0);
+
+impl ExcludedSupply {
+    /// The excluded token accounts
+    pub fn token_accounts(&self) -> &[Pubkey] {
+        &self.token_accounts[..self.token_account_count as usize]
+    }
+
+    /// Replaces the excluded token accounts.
+    pub fn set_token_accounts(&mut self, token_accounts: &[Pubkey]) -> Result<()> {
+        require_gte!(
+            MAX_EXCLUDED_TOKEN_ACCOUNTS,
+            token_accounts.len(),
+            VsrError::TooManyExcludedTokenAccounts
+        );
+        for (i, token_account) in token_accounts.iter().enumerate() {
+            require!(
+                !token_accounts[..i].contains(token_account),
+                VsrError::DuplicateExcludedTokenAccount
+            );
+        }
+
+        self.token_accounts = [Pubkey::default(); MAX_EXCLUDED_TOKEN_ACCOUNTS];
+        self.token_accounts[..token_accounts.len()].copy_from_slice(token_accounts);
+        self.token_account_count = token_accounts.len() as u8;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    #[test]
+    fn set_token_accounts_test() {
+        let mut excluded_supply: ExcludedSupply = Zeroable::zeroed();
+        assert!(excluded_supply.token_accounts().is_empty());
+
+        let token_accounts: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        excluded_supply.set_token_accounts(&token_accounts).unwrap();
+        assert_eq!(excluded_supply.token_accounts(), &token_accounts[..]);
+
+        excluded_supply.set_token_accounts(&token_accounts[1..2]).unwrap();
+        assert_eq!(excluded_supply.token_accounts(), &token_accounts[1..2]);
+        assert_eq!(excluded_supply.token_accounts[1], Pubkey::default());
+
+        let duplicated = [token_accounts[0], token_accounts[1], token_accounts[0]];
+        assert!(excluded_supply.set_token_accounts(&duplicated).is_err());
+
+        let too_many: Vec<Pubkey> = (0..MAX_EXCLUDED_TOKEN_ACCOUNTS + 1)
+            .map(|_| Pubkey::new_unique())
+            .collect();
+        assert!(excluded_supply.set_token_accounts(&too_many).is_err());
+        excluded_supply
+            .set_token_accounts(&too_many[..MAX_EXCLUDED_TOKEN_ACCOUNTS])
+            .unwrap();
+        assert_eq!(excluded_supply.token_accounts().len(), MAX_EXCLUDED_TOKEN_ACCOUNTS);
+    }
+}
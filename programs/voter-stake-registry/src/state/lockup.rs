@@ -1,19 +1,26 @@
 use crate::error::*;
 use anchor_lang::prelude::*;
 use bytemuck::{Pod, Zeroable};
+use std::cmp::min;
 use std::convert::TryFrom;
 
 /// Seconds in one day.
 pub const SECS_PER_DAY: u64 = 86_400;
 
+/// Seconds in one week.
+pub const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+
 /// Seconds in one month.
 pub const SECS_PER_MONTH: u64 = 365 * SECS_PER_DAY / 12;
 
+/// Seconds in a year.
+pub const SECS_PER_YEAR: u64 = SECS_PER_DAY * 365;
+
 /// Maximum acceptable number of lockup periods.
 ///
 /// In the linear vesting voting power computation, a factor like
 /// `periods^2 * period_secs` is used. With the current setting
-/// that would be 36500^2 * SECS_PER_MONTH << 2^64.
+/// that would be 73000^2 * SECS_PER_YEAR << 2^64.
 ///
 /// This setting limits the maximum lockup duration for lockup methods
 /// with daily periods to 200 years.
@@ -53,30 +60,55 @@ impl Lockup {
             kind.periods(),
             VsrError::InvalidLockupPeriod
         );
+        require_gte!(
+            kind.periods(),
+            kind.cliff_periods(),
+            VsrError::InvalidLockupPeriod
+        );
+        kind.duration.validate()?;
         Ok(Self {
             kind,
             start_ts,
         })
     }
 
-    /// Create lockup for a given lockup duration
-    pub fn new_from_duration(duration: LockupTimeDuration, curr_ts: i64, start_ts: i64) -> Result<Self> {
-        match duration.unit {
-            LockupTimeUnit::Day => {
-                Lockup::new_from_kind(LockupKind::daily(duration.periods), curr_ts, start_ts)
-            }
-            LockupTimeUnit::Month => {
-                Lockup::new_from_kind(LockupKind::monthly(duration.periods), curr_ts, start_ts)
-            }
-        }
+    /// Create a vesting lockup for a given lockup duration
+    ///
+    /// Nothing vests during the first cliff_periods periods, capped at the
+    /// number of periods of the duration.
+    pub fn new_from_duration(
+        duration: LockupTimeDuration,
+        cliff_periods: u64,
+        curr_ts: i64,
+        start_ts: i64,
+    ) -> Result<Self> {
+        Lockup::new_from_kind(
+            LockupKind::vesting(duration).with_cliff_periods(cliff_periods),
+            curr_ts,
+            start_ts,
+        )
     }
 
     /// Return the end timestamp of this lockup
     #[inline(always)]
     pub fn end_ts(&self) -> i64 {
-        self.start_ts
-            .checked_add(i64::try_from(self.kind.duration.seconds()).unwrap())
-            .unwrap()
+        self.period_end_ts(self.periods_total())
+    }
+
+    /// Return the timestamp at which the vesting cliff ends.
+    /// Equals start_ts if the lockup has no cliff.
+    #[inline(always)]
+    pub fn cliff_end_ts(&self) -> i64 {
+        self.period_end_ts(self.kind.cliff_periods())
+    }
+
+    /// Return the end timestamp of the given period, capped at the last one.
+    /// The end of period 0 is start_ts.
+    #[inline(always)]
+    pub fn period_end_ts(&self, period: u64) -> i64 {
+        self.kind
+            .duration
+            .periods_end_ts(self.start_ts, min(period, self.periods_total()))
     }
 
     #[inline(always)]
@@ -84,6 +116,10 @@ impl Lockup {
         self.kind.is_vesting()
     }
 
+    #[inline(always)]
+    pub fn is_constant(&self) -> bool {
+        self.kind.is_constant()
+    }
 }
 
 impl Lockup {
@@ -98,7 +134,11 @@ impl Lockup {
     pub fn seconds_left(&self, mut curr_ts: i64) -> u64 {
         curr_ts = match self.kind.kind {
             LockupKindKind::Constant => self.start_ts,
-            _ => curr_ts,
+            LockupKindKind::Daily
+            | LockupKindKind::Monthly
+            | LockupKindKind::Cliff
+            | LockupKindKind::Weekly
+            | LockupKindKind::Yearly => curr_ts,
         };
 
         let end_ts = self.end_ts();
@@ -111,20 +151,25 @@ impl Lockup {
 
     /// Returns the number of periods left on the lockup.
     /// Returns 0 after lockup has expired and periods_total before start_ts.
+    ///
+    /// Nothing vests before the cliff has passed, so until then all periods
+    /// are left.
     pub fn periods_left(&self, curr_ts: i64) -> Result<u64> {
         let period_secs = self.kind.period_secs();
         if period_secs == 0 {
             return Ok(0);
         }
-        if curr_ts < self.start_ts {
+        if curr_ts < self.start_ts || self.is_constant() {
             return Ok(self.periods_total());
         }
-        Ok(self
-            .seconds_left(curr_ts)
-            .checked_add(period_secs.saturating_sub(1))
-            .unwrap()
-            .checked_div(period_secs)
-            .unwrap())
+        let periods_elapsed = min(
+            self.kind.duration.periods_elapsed(self.start_ts, curr_ts),
+            self.periods_total(),
+        );
+        if periods_elapsed < self.kind.cliff_periods() {
+            return Ok(self.periods_total());
+        }
+        Ok(self.periods_total() - periods_elapsed)
     }
 
     /// Returns the current period in the vesting schedule.
@@ -149,7 +194,8 @@ impl Default for Lockup {
             kind: LockupKind::constant(LockupTimeDuration {
                 periods: 0,
                 unit: LockupTimeUnit::Day,
-                filler: [0; 7]
+                calendar_mode: 0,
+                filler: [0; 6]
             }),
             start_ts: 0,
         }
@@ -161,25 +207,55 @@ impl Default for Lockup {
 pub struct LockupKind {
     pub duration: LockupTimeDuration,
     pub kind: LockupKindKind,
-    pub filler: [u8; 7]
+    pub filler: [u8; 3],
+    /// Number of periods at the start of a vesting lockup during which
+    /// nothing vests. The vesting of those periods is deferred to the end
+    /// of the cliff. Always 0 for non-vesting lockups.
+    pub cliff_periods: u32,
 }
-const_assert!(std::mem::size_of::<LockupKind>() == 16 + 1 + 7);
+const_assert!(std::mem::size_of::<LockupKind>() == 16 + 1 + 3 + 4);
 const_assert!(std::mem::size_of::<LockupKind>() % 8 == 0);
 
 impl LockupKind {
     pub fn daily(days: u64) -> LockupKind {
         LockupKind {
-            duration: LockupTimeDuration { periods: days, unit: LockupTimeUnit::Day, filler: [0; 7] },
+            duration: LockupTimeDuration::new(days, LockupTimeUnit::Day),
             kind: LockupKindKind::Daily,
-            filler: [0; 7]
+            filler: [0; 3],
+            cliff_periods: 0,
         }
     }
 
     pub fn monthly(months: u64) -> LockupKind {
         LockupKind {
-            duration: LockupTimeDuration { periods: months, unit: LockupTimeUnit::Month, filler: [0;7] },
+            duration: LockupTimeDuration::new(months, LockupTimeUnit::Month),
             kind: LockupKindKind::Monthly,
-            filler: [0; 7]
+            filler: [0; 3],
+            cliff_periods: 0,
+        }
+    }
+
+    pub fn weekly(weeks: u64) -> LockupKind {
+        LockupKind::vesting(LockupTimeDuration::new(weeks, LockupTimeUnit::Week))
+    }
+
+    pub fn yearly(years: u64) -> LockupKind {
+        LockupKind::vesting(LockupTimeDuration::new(years, LockupTimeUnit::Year))
+    }
+
+    /// Linear vesting over the periods of a duration, vesting once per unit.
+    pub fn vesting(duration: LockupTimeDuration) -> LockupKind {
+        let kind = match duration.unit {
+            LockupTimeUnit::Day => LockupKindKind::Daily,
+            LockupTimeUnit::Week => LockupKindKind::Weekly,
+            LockupTimeUnit::Month => LockupKindKind::Monthly,
+            LockupTimeUnit::Year => LockupKindKind::Yearly,
+        };
+        LockupKind {
+            duration,
+            kind,
+            filler: [0; 3],
+            cliff_periods: 0,
         }
     }
 
@@ -187,7 +263,26 @@ impl LockupKind {
         LockupKind {
             duration,
             kind: LockupKindKind::Constant,
-            filler: [0; 7]
+            filler: [0; 3],
+            cliff_periods: 0,
+        }
+    }
+
+    pub fn cliff(duration: LockupTimeDuration) -> LockupKind {
+        LockupKind {
+            duration,
+            kind: LockupKindKind::Cliff,
+            filler: [0; 3],
+            cliff_periods: 0,
+        }
+    }
+
+    /// Returns this vesting lockup kind with a cliff of cliff_periods periods,
+    /// capped at the number of periods.
+    pub fn with_cliff_periods(self, cliff_periods: u64) -> LockupKind {
+        LockupKind {
+            cliff_periods: u32::try_from(min(cliff_periods, self.periods())).unwrap_or(u32::MAX),
+            ..self
         }
     }
 
@@ -196,10 +291,16 @@ impl LockupKind {
         self.duration.periods
     }
 
+    #[inline(always)]
+    pub fn cliff_periods(&self) -> u64 {
+        self.cliff_periods as u64
+    }
+
     /// The lockup length is specified by passing the number of lockup periods
     /// to create_deposit_entry. This describes a period's length.
     ///
     /// For vesting lockups, the period length is also the vesting period.
+    /// Calendar mode periods vary in length, see `LockupTimeDuration::periods_end_ts`.
     #[inline(always)]
     pub fn period_secs(&self) -> u64 {
         self.duration.unit.seconds()
@@ -211,8 +312,17 @@ impl LockupKind {
             LockupKindKind::Daily => true,
             LockupKindKind::Monthly => true,
             LockupKindKind::Constant => false,
+            LockupKindKind::Cliff => false,
+            LockupKindKind::Weekly => true,
+            LockupKindKind::Yearly => true,
         }
     }
+
+    /// True for lockups that never decay, their tokens count as permanently locked.
+    #[inline(always)]
+    pub fn is_constant(&self) -> bool {
+        self.kind == LockupKindKind::Constant
+    }
 }
 
 #[repr(u8)]
@@ -226,6 +336,18 @@ pub enum LockupKindKind {
 
     /// Lock up permanently. 
     Constant,
+
+    /// Lock up for a duration starting at the deposit, everything unlocks
+    /// at the end. The locked tokens earn staking rewards with a reward
+    /// weight decaying like their vote weight, but never count as
+    /// permanently locked.
+    Cliff,
+
+    /// Lock up for a number of weeks.
+    Weekly,
+
+    /// Lock up for a number of years.
+    Yearly,
 }
 
 unsafe impl Pod for LockupKindKind { }
@@ -235,18 +357,85 @@ unsafe impl Pod for LockupKindKind { }
 pub struct LockupTimeDuration {
     pub periods: u64,
     pub unit: LockupTimeUnit,
-    pub filler: [u8; 7]
+    /// Opt-in calendar mode, 1 to end Month and Year periods on the same
+    /// day of the month as the start in UTC, instead of after a fixed
+    /// number of seconds. Days past the end of shorter months are clamped
+    /// to their last day. Must be 0 for Day and Week units.
+    pub calendar_mode: u8,
+    pub filler: [u8; 6]
 }
-const_assert!(std::mem::size_of::<LockupTimeDuration>() == 8 + 1 + 7);
+const_assert!(std::mem::size_of::<LockupTimeDuration>() == 8 + 1 + 1 + 6);
 const_assert!(std::mem::size_of::<LockupTimeDuration>() % 8 == 0);
 
 impl LockupTimeDuration {
-    pub fn seconds(&self) -> u64 {
-        self.unit
-            .seconds()
-            .checked_mul(self.periods.into())
+    pub fn new(periods: u64, unit: LockupTimeUnit) -> Self {
+        LockupTimeDuration {
+            periods,
+            unit,
+            calendar_mode: 0,
+            filler: [0; 6],
+        }
+    }
+
+    /// Same duration in calendar mode.
+    pub fn calendar(periods: u64, unit: LockupTimeUnit) -> Self {
+        LockupTimeDuration {
+            calendar_mode: 1,
+            ..LockupTimeDuration::new(periods, unit)
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_calendar_mode(&self) -> bool {
+        self.calendar_mode != 0
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require_gte!(
+            MAX_LOCKUP_PERIODS,
+            self.periods,
+            VsrError::InvalidLockupPeriod
+        );
+        require!(
+            self.calendar_mode <= 1
+                && (!self.is_calendar_mode() || self.unit.calendar_months() > 0),
+            VsrError::InvalidLockupDuration
+        );
+        Ok(())
+    }
+
+    /// Number of seconds of the duration when starting at start_ts.
+    ///
+    /// Only calendar mode durations depend on start_ts.
+    pub fn seconds(&self, start_ts: i64) -> u64 {
+        (self.periods_end_ts(start_ts, self.periods) - start_ts) as u64
+    }
+
+    /// Timestamp 'periods' periods after start_ts.
+    pub fn periods_end_ts(&self, start_ts: i64, periods: u64) -> i64 {
+        if self.is_calendar_mode() {
+            return add_calendar_months(
+                start_ts,
+                periods.checked_mul(self.unit.calendar_months()).unwrap(),
+            );
+        }
+        start_ts
+            .checked_add(
+                i64::try_from(self.unit.seconds().checked_mul(periods).unwrap()).unwrap(),
+            )
             .unwrap()
     }
+
+    /// Number of whole periods from start_ts to curr_ts, 0 before start_ts.
+    pub fn periods_elapsed(&self, start_ts: i64, curr_ts: i64) -> u64 {
+        if curr_ts < start_ts {
+            return 0;
+        }
+        if self.is_calendar_mode() {
+            return calendar_months_elapsed(start_ts, curr_ts) / self.unit.calendar_months();
+        }
+        (curr_ts - start_ts) as u64 / self.unit.seconds()
+    }
 }
 
 #[repr(u8)]
@@ -254,6 +443,8 @@ impl LockupTimeDuration {
 pub enum LockupTimeUnit {
     Day,
     Month,
+    Week,
+    Year,
 }
 
 unsafe impl Pod for LockupTimeUnit { }
@@ -262,15 +453,104 @@ impl LockupTimeUnit {
     pub fn seconds(&self) -> u64 {
         match *self {
             LockupTimeUnit::Day => SECS_PER_DAY,
-            LockupTimeUnit::Month => SECS_PER_MONTH
+            LockupTimeUnit::Month => SECS_PER_MONTH,
+            LockupTimeUnit::Week => SECS_PER_WEEK,
+            LockupTimeUnit::Year => SECS_PER_YEAR,
+        }
+    }
+
+    /// Calendar months per period in calendar mode, 0 for units that
+    /// don't support it.
+    pub fn calendar_months(&self) -> u64 {
+        match *self {
+            LockupTimeUnit::Day | LockupTimeUnit::Week => 0,
+            LockupTimeUnit::Month => 1,
+            LockupTimeUnit::Year => 12,
         }
     }
 }
 
+/// Days since 1970-01-01 of a proleptic Gregorian date, see
+/// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_from_march = (month as i64 + 9) % 12;
+    let day_of_year = (153 * month_from_march + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Proleptic Gregorian (year, month, day) of a number of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Adds calendar months to a UTC timestamp, keeping the time of day and
+/// clamping the day of the month to the length of the resulting month.
+fn add_calendar_months(ts: i64, months: u64) -> i64 {
+    let days = ts.div_euclid(SECS_PER_DAY as i64);
+    let secs_of_day = ts.rem_euclid(SECS_PER_DAY as i64);
+    let (year, month, day) = civil_from_days(days);
+
+    let total_months = (year * 12 + month as i64 - 1)
+        .checked_add(i64::try_from(months).unwrap())
+        .unwrap();
+    let new_year = total_months.div_euclid(12);
+    let new_month = (total_months.rem_euclid(12) + 1) as u32;
+    let new_day = min(day, days_in_month(new_year, new_month));
+
+    days_from_civil(new_year, new_month, new_day)
+        .checked_mul(SECS_PER_DAY as i64)
+        .unwrap()
+        .checked_add(secs_of_day)
+        .unwrap()
+}
+
+/// Number of whole calendar months from start_ts to curr_ts, where
+/// start_ts <= curr_ts.
+fn calendar_months_elapsed(start_ts: i64, curr_ts: i64) -> u64 {
+    let (start_year, start_month, _) = civil_from_days(start_ts.div_euclid(SECS_PER_DAY as i64));
+    let (curr_year, curr_month, _) = civil_from_days(curr_ts.div_euclid(SECS_PER_DAY as i64));
+    let months = ((curr_year * 12 + curr_month as i64) - (start_year * 12 + start_month as i64)) as u64;
+    if add_calendar_months(start_ts, months) > curr_ts {
+        months - 1
+    } else {
+        months
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::state::deposit_entry::DepositEntry;
+    use crate::state::registrar::{
+        VoteWeightCurve, VoteWeightCurveKind, VoteWeightCurvePoint, BPS_BASE,
+    };
 
     // intentionally not a multiple of a day
     const MAX_SECS_LOCKED: u64 = 365 * 24 * 60 * 60 + 7 * 60 * 60;
@@ -300,6 +580,275 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn week_and_year_period_computations() -> Result<()> {
+        let week = SECS_PER_WEEK as i64;
+        let lockup = Lockup::new_from_kind(LockupKind::weekly(3), 1000, 1000)?;
+        assert!(lockup.is_vesting());
+        assert_eq!(lockup.end_ts(), 1000 + 3 * week);
+        assert_eq!(lockup.periods_left(1000 + week - 1)?, 3);
+        assert_eq!(lockup.periods_left(1000 + week)?, 2);
+        assert_eq!(lockup.periods_left(1000 + 3 * week)?, 0);
+
+        let year = SECS_PER_YEAR as i64;
+        let lockup = Lockup::new_from_kind(LockupKind::yearly(2), 1000, 1000)?;
+        assert!(lockup.is_vesting());
+        assert_eq!(lockup.end_ts(), 1000 + 2 * year);
+        assert_eq!(lockup.periods_left(1000 + year - 1)?, 2);
+        assert_eq!(lockup.periods_left(1000 + year)?, 1);
+        assert_eq!(lockup.period_end_ts(1), 1000 + year);
+
+        let duration = LockupTimeDuration::new(2, LockupTimeUnit::Week);
+        let lockup = Lockup::new_from_duration(duration, 0, 1000, 1000)?;
+        assert!(lockup.kind.kind == LockupKindKind::Weekly);
+        assert_eq!(duration.seconds(1000), 2 * SECS_PER_WEEK);
+        Ok(())
+    }
+
+    #[test]
+    pub fn calendar_date_math() {
+        // 2024-01-01T00:00:00Z
+        let jan_1_2024 = 1704067200;
+        let day = SECS_PER_DAY as i64;
+        assert_eq!(days_from_civil(1970, 1, 1), 0);
+        assert_eq!(days_from_civil(2024, 1, 1) * day, jan_1_2024);
+        assert_eq!(civil_from_days(jan_1_2024 / day), (2024, 1, 1));
+        assert_eq!(civil_from_days(-1), (1969, 12, 31));
+        for days in -1_000..100_000 {
+            let (year, month, day_of_month) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day_of_month), days);
+        }
+
+        // the time of day is kept
+        let ts = jan_1_2024 + 14 * day + 45_296;
+        assert_eq!(add_calendar_months(ts, 0), ts);
+        assert_eq!(add_calendar_months(ts, 2), jan_1_2024 + (31 + 29 + 14) * day + 45_296);
+        // days past the end of the month are clamped, in leap years too
+        let jan_31_2024 = jan_1_2024 + 30 * day;
+        assert_eq!(add_calendar_months(jan_31_2024, 1), jan_1_2024 + (31 + 28) * day);
+        assert_eq!(add_calendar_months(jan_31_2024, 13), jan_1_2024 + (366 + 31 + 27) * day);
+        assert_eq!(add_calendar_months(jan_31_2024, 12), jan_1_2024 + 366 * day + 30 * day);
+
+        assert_eq!(calendar_months_elapsed(jan_31_2024, jan_31_2024), 0);
+        assert_eq!(calendar_months_elapsed(jan_31_2024, jan_1_2024 + (31 + 28) * day - 1), 0);
+        assert_eq!(calendar_months_elapsed(jan_31_2024, jan_1_2024 + (31 + 28) * day), 1);
+        assert_eq!(calendar_months_elapsed(jan_31_2024, jan_1_2024 + (31 + 29) * day), 1);
+        assert_eq!(calendar_months_elapsed(jan_31_2024, jan_1_2024 + 366 * day + 30 * day), 12);
+    }
+
+    #[test]
+    pub fn calendar_period_computations() -> Result<()> {
+        // 2024-01-31T00:00:00Z
+        let start_ts = 1706659200;
+        let day = SECS_PER_DAY as i64;
+        let lockup = Lockup::new_from_duration(
+            LockupTimeDuration::calendar(3, LockupTimeUnit::Month),
+            0,
+            start_ts,
+            start_ts,
+        )?;
+        assert!(lockup.kind.kind == LockupKindKind::Monthly);
+        // periods end on Feb 29, Mar 31 and Apr 30
+        assert_eq!(lockup.period_end_ts(1), start_ts + 29 * day);
+        assert_eq!(lockup.period_end_ts(2), start_ts + (29 + 31) * day);
+        assert_eq!(lockup.end_ts(), start_ts + (29 + 31 + 30) * day);
+        assert_eq!(lockup.kind.duration.seconds(start_ts), ((29 + 31 + 30) * day) as u64);
+        assert_eq!(lockup.periods_left(start_ts - 1)?, 3);
+        assert_eq!(lockup.periods_left(start_ts + 29 * day - 1)?, 3);
+        assert_eq!(lockup.periods_left(start_ts + 29 * day)?, 2);
+        assert_eq!(lockup.periods_left(start_ts + (29 + 31) * day)?, 1);
+        assert_eq!(lockup.periods_left(lockup.end_ts() - 1)?, 1);
+        assert_eq!(lockup.periods_left(lockup.end_ts())?, 0);
+        assert!(lockup.expired(lockup.end_ts()));
+
+        let lockup = Lockup::new_from_duration(
+            LockupTimeDuration::calendar(2, LockupTimeUnit::Year),
+            1,
+            start_ts,
+            start_ts,
+        )?;
+        assert!(lockup.kind.kind == LockupKindKind::Yearly);
+        // 2025-01-31 and 2026-01-31
+        assert_eq!(lockup.cliff_end_ts(), start_ts + 366 * day);
+        assert_eq!(lockup.end_ts(), start_ts + (366 + 365) * day);
+        assert_eq!(lockup.periods_left(start_ts + 366 * day - 1)?, 2);
+        assert_eq!(lockup.periods_left(start_ts + 366 * day)?, 1);
+
+        // calendar mode is only supported by months and years
+        assert!(LockupTimeDuration::calendar(3, LockupTimeUnit::Day).validate().is_err());
+        assert!(LockupTimeDuration::calendar(3, LockupTimeUnit::Week).validate().is_err());
+        let invalid = LockupTimeDuration {
+            calendar_mode: 2,
+            ..LockupTimeDuration::new(3, LockupTimeUnit::Month)
+        };
+        assert!(invalid.validate().is_err());
+        assert!(Lockup::new_from_kind(
+            LockupKind::constant(LockupTimeDuration::calendar(3, LockupTimeUnit::Day)),
+            start_ts,
+            start_ts
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    pub fn voting_power_calendar_monthly() -> Result<()> {
+        // 2024-01-31T00:00:00Z
+        let start_ts = 1706659200;
+        let amount_deposited = 12 * 1_000_000;
+        for cliff_periods in [0, 4] {
+            let kind = LockupKind::vesting(LockupTimeDuration::calendar(12, LockupTimeUnit::Month))
+                .with_cliff_periods(cliff_periods);
+            let mut d = DepositEntry::new_from_lockup(Lockup::new_from_kind(kind, start_ts, start_ts)?)?;
+            d.deposit(start_ts, amount_deposited)?;
+            let lockup = d.get_lockup();
+
+            for curr_day in [-1.5, 0.0, 10.5, 45.0, 130.0, 200.0, 365.0, 366.0] {
+                let curr_ts = start_ts + days_to_secs(curr_day);
+                let period_current = lockup.period_current(curr_ts)?;
+                let mut expected = 0u64;
+                for period in (period_current + 1)..=12 {
+                    let unlock_ts = lockup.period_end_ts(period.max(cliff_periods));
+                    let secs_left = min((unlock_ts - curr_ts) as u64, MAX_SECS_LOCKED);
+                    expected += secs_left;
+                }
+                let expected = (amount_deposited as u128 * expected as u128
+                    / (12 * MAX_SECS_LOCKED as u128)) as u64;
+                let power = d.voting_power_locked(
+                    curr_ts,
+                    amount_deposited,
+                    MAX_SECS_LOCKED,
+                    &VoteWeightCurve::default(),
+                )?;
+                assert_eq!(power, expected);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn voting_power_piecewise_curve() -> Result<()> {
+        // half of the extra vote weight for the first quarter of the saturation
+        let mut curve = VoteWeightCurve {
+            kind: VoteWeightCurveKind::PiecewiseLinear,
+            point_count: 1,
+            ..VoteWeightCurve::default()
+        };
+        curve.points[0] = VoteWeightCurvePoint {
+            lockup_bps: 2_500,
+            weight_bps: 5_000,
+        };
+        let weight = |secs: u64| -> f64 {
+            let x = secs.min(MAX_SECS_LOCKED) as f64 / MAX_SECS_LOCKED as f64;
+            if x < 0.25 {
+                2.0 * x
+            } else {
+                0.5 + (x - 0.25) * 2.0 / 3.0
+            }
+        };
+
+        let start_ts = 1_000_000;
+        let amount_deposited = 10_000_000_000;
+        let kinds = [
+            LockupKind::constant(LockupTimeDuration::new(60, LockupTimeUnit::Day)),
+            LockupKind::daily(400),
+            LockupKind::daily(90).with_cliff_periods(30),
+            LockupKind::monthly(24).with_cliff_periods(6),
+            LockupKind::vesting(LockupTimeDuration::calendar(12, LockupTimeUnit::Month)),
+        ];
+        for kind in kinds {
+            let mut d = DepositEntry::new_from_lockup(Lockup::new_from_kind(kind, start_ts, start_ts)?)?;
+            d.deposit(start_ts, amount_deposited)?;
+            let lockup = d.get_lockup();
+            let periods_total = lockup.periods_total();
+
+            for curr_day in [0.0, 0.5, 10.0, 45.5, 100.0, 200.0, 365.0, 500.0] {
+                let curr_ts = start_ts + days_to_secs(curr_day);
+                if lockup.expired(curr_ts) {
+                    continue;
+                }
+                let expected = if kind.is_vesting() {
+                    let cliff_periods = kind.cliff_periods();
+                    let period_current = lockup.period_current(curr_ts)?;
+                    let mut expected = 0.0;
+                    for period in (period_current + 1)..=periods_total {
+                        let unlock_ts = lockup.period_end_ts(period.max(cliff_periods));
+                        expected += weight((unlock_ts - curr_ts) as u64);
+                    }
+                    amount_deposited as f64 * expected / periods_total as f64
+                } else {
+                    amount_deposited as f64 * weight(lockup.seconds_left(curr_ts))
+                };
+                let power =
+                    d.voting_power_locked(curr_ts, amount_deposited, MAX_SECS_LOCKED, &curve)?;
+                // weight_bps rounds down to basis points
+                let tolerance = amount_deposited as f64 / BPS_BASE as f64 + 1.0;
+                assert!(
+                    (power as f64 - expected).abs() <= tolerance,
+                    "kind {} day {}: {} vs {}",
+                    kind.kind as u8,
+                    curr_day,
+                    power,
+                    expected
+                );
+                // the curve lies above the linear one
+                let linear = d.voting_power_locked(
+                    curr_ts,
+                    amount_deposited,
+                    MAX_SECS_LOCKED,
+                    &VoteWeightCurve::default(),
+                )?;
+                assert!(power >= linear);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    pub fn period_computations_with_cliff() -> Result<()> {
+        let lockup = Lockup::new_from_kind(LockupKind::daily(5).with_cliff_periods(2), 1000, 1000)?;
+        let day = SECS_PER_DAY as i64;
+        assert_eq!(lockup.periods_total(), 5);
+        assert_eq!(lockup.cliff_end_ts(), 1000 + 2 * day);
+        assert_eq!(lockup.periods_left(999)?, 5);
+        assert_eq!(lockup.period_current(1000)?, 0);
+        assert_eq!(lockup.periods_left(1000)?, 5);
+        assert_eq!(lockup.period_current(1000 + day)?, 0);
+        assert_eq!(lockup.periods_left(1000 + day)?, 5);
+        assert_eq!(lockup.period_current(1000 + 2 * day - 1)?, 0);
+        assert_eq!(lockup.periods_left(1000 + 2 * day - 1)?, 5);
+        assert_eq!(lockup.period_current(1000 + 2 * day)?, 2);
+        assert_eq!(lockup.periods_left(1000 + 2 * day)?, 3);
+        assert_eq!(lockup.period_current(1000 + 3 * day)?, 3);
+        assert_eq!(lockup.periods_left(1000 + 3 * day)?, 2);
+        assert_eq!(lockup.period_current(1000 + 5 * day)?, 5);
+        assert_eq!(lockup.periods_left(1000 + 5 * day)?, 0);
+
+        // the cliff is capped at the lockup duration
+        let lockup = Lockup::new_from_kind(LockupKind::daily(3).with_cliff_periods(10), 1000, 1000)?;
+        assert_eq!(lockup.kind.cliff_periods(), 3);
+        assert_eq!(lockup.periods_left(1000 + 3 * day - 1)?, 3);
+        assert_eq!(lockup.periods_left(1000 + 3 * day)?, 0);
+
+        let kind = LockupKind {
+            cliff_periods: 4,
+            ..LockupKind::daily(3)
+        };
+        assert!(Lockup::new_from_kind(kind, 1000, 1000).is_err());
+
+        let duration = LockupTimeDuration {
+            periods: 6,
+            unit: LockupTimeUnit::Month,
+            calendar_mode: 0,
+            filler: [0; 6],
+        };
+        let lockup = Lockup::new_from_duration(duration, 2, 1000, 1000)?;
+        assert!(lockup.kind.kind == LockupKindKind::Monthly);
+        assert_eq!(lockup.kind.cliff_periods(), 2);
+        assert_eq!(lockup.cliff_end_ts(), 1000 + 2 * SECS_PER_MONTH as i64);
+        Ok(())
+    }
+
     #[test]
     pub fn days_left_start() -> Result<()> {
         run_test_days_left(TestDaysLeft {
@@ -426,6 +975,272 @@ mod tests {
         })
     }
 
+    #[test]
+    pub fn cliff_days_left_start() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 10,
+            days_total: 10,
+            curr_day: 0.0,
+        })
+    }
+
+    #[test]
+    pub fn cliff_days_left_one_half() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 10,
+            days_total: 10,
+            curr_day: 0.5,
+        })
+    }
+
+    #[test]
+    pub fn cliff_days_left_one() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 9,
+            days_total: 10,
+            curr_day: 1.0,
+        })
+    }
+
+    #[test]
+    pub fn cliff_days_left_9_dot_nine() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 1,
+            days_total: 10,
+            curr_day: 9.9,
+        })
+    }
+
+    #[test]
+    pub fn cliff_days_left_ten() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 0,
+            days_total: 10,
+            curr_day: 10.0,
+        })
+    }
+
+    #[test]
+    pub fn cliff_days_left_eleven() -> Result<()> {
+        run_test_cliff_days_left(TestDaysLeft {
+            expected_days_left: 0,
+            days_total: 10,
+            curr_day: 11.0,
+        })
+    }
+
+    #[test]
+    pub fn cliff_vested() -> Result<()> {
+        let start_ts = 1634929833;
+        let kind = cliff_days(10);
+        let mut d = DepositEntry::new_from_lockup(Lockup::new_from_kind(kind, start_ts, start_ts)?)?;
+        d.deposit(start_ts, 10_000)?;
+        assert_eq!(d.vested(start_ts)?, 0);
+        assert_eq!(d.vested(start_ts + days_to_secs(9.9))?, 0);
+        assert_eq!(d.amount_unlocked(start_ts + days_to_secs(9.9))?, 0);
+        assert_eq!(d.vested(start_ts + days_to_secs(10.0))?, 10_000);
+        assert_eq!(d.amount_unlocked(start_ts + days_to_secs(10.0))?, 10_000);
+        assert!(!d.get_lockup().is_constant());
+        assert!(!d.get_lockup().is_vesting());
+        Ok(())
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_warmup() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, -1.5, 10, 3),
+            amount_deposited,
+            curr_day: -1.5,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_start() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 0.0, 10, 3),
+            amount_deposited,
+            curr_day: 0.0,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_one_half() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 0.5, 10, 3),
+            amount_deposited,
+            curr_day: 0.5,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_two_dot_nine() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 2.9, 10, 3),
+            amount_deposited,
+            curr_day: 2.9,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_three() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 3.0, 10, 3),
+            amount_deposited,
+            curr_day: 3.0,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_five_dot_five() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 5.5, 10, 3),
+            amount_deposited,
+            curr_day: 5.5,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_nine_dot_nine() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 9.9, 10, 3),
+            amount_deposited,
+            curr_day: 9.9,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_ten() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 10.0, 10, 3),
+            amount_deposited,
+            curr_day: 10.0,
+            kind: LockupKind::daily(10).with_cliff_periods(3),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_full_cliff() -> Result<()> {
+        // a cliff over the whole duration behaves like a cliff lockup
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, 4.5, 10),
+            amount_deposited,
+            curr_day: 4.5,
+            kind: LockupKind::daily(10).with_cliff_periods(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_daily_with_cliff_above_saturation() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_daily_with_cliff_power(amount_deposited, 0.5, 1000, 400),
+            amount_deposited,
+            curr_day: 0.5,
+            kind: LockupKind::daily(1000).with_cliff_periods(400),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_warmup() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, -1.5, 10),
+            amount_deposited,
+            curr_day: -1.5,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_start() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, 0.0, 10),
+            amount_deposited,
+            curr_day: 0.0,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_one_half() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, 0.5, 10),
+            amount_deposited,
+            curr_day: 0.5,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_nine_dot_nine() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, 9.9, 10),
+            amount_deposited,
+            curr_day: 9.9,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_ten() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: 0,
+            amount_deposited,
+            curr_day: 10.0,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_eleven() -> Result<()> {
+        let amount_deposited = 10 * 1_000_000;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: 0,
+            amount_deposited,
+            curr_day: 11.0,
+            kind: cliff_days(10),
+        })
+    }
+
+    #[test]
+    pub fn voting_power_cliff_above_saturation() -> Result<()> {
+        let days = (MAX_DAYS_LOCKED + 10.0).floor() as u64;
+        let amount_deposited = days * 1_000_000;
+        // saturated until less than MAX_DAYS_LOCKED remain
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: amount_deposited,
+            amount_deposited,
+            curr_day: 9.5,
+            kind: cliff_days(days),
+        })?;
+        run_test_voting_power(TestVotingPower {
+            expected_voting_power: locked_cliff_power(amount_deposited, 11.0, days),
+            amount_deposited,
+            curr_day: 11.0,
+            kind: cliff_days(days),
+        })
+    }
+
     #[test]
     pub fn voting_power_daily_warmup() -> Result<()> {
         let amount_deposited = 10 * 1_000_000;
@@ -637,6 +1452,27 @@ mod tests {
         Ok(())
     }
 
+    fn run_test_cliff_days_left(t: TestDaysLeft) -> Result<()> {
+        let start_ts = 1634929833;
+        let curr_ts = start_ts + days_to_secs(t.curr_day);
+        let l = Lockup {
+            kind: cliff_days(t.days_total),
+            start_ts,
+        };
+        let days_left = l.periods_left(curr_ts)?;
+        assert_eq!(days_left, t.expected_days_left);
+        Ok(())
+    }
+
+    fn cliff_days(days: u64) -> LockupKind {
+        LockupKind::cliff(LockupTimeDuration {
+            periods: days,
+            unit: LockupTimeUnit::Day,
+            calendar_mode: 0,
+            filler: [0; 6],
+        })
+    }
+
     fn run_test_months_left(t: TestMonthsLeft) -> Result<()> {
         let start_ts = 1634929833;
         let curr_ts = start_ts + months_to_secs(t.curr_month);
@@ -654,7 +1490,12 @@ mod tests {
         let mut d = DepositEntry::new_from_lockup(Lockup::new_from_kind(t.kind, start_ts, start_ts)?)?;
         d.deposit(start_ts, t.amount_deposited)?;
         let curr_ts = start_ts + days_to_secs(t.curr_day);
-        let power = d.voting_power_locked(curr_ts, t.amount_deposited, MAX_SECS_LOCKED)?;
+        let power = d.voting_power_locked(
+            curr_ts,
+            t.amount_deposited,
+            MAX_SECS_LOCKED,
+            &VoteWeightCurve::default(),
+        )?;
         assert_eq!(power, t.expected_voting_power);
         Ok(())
     }
@@ -692,6 +1533,28 @@ mod tests {
         (total + 0.0001).floor() as u64
     }
 
+    // Calculates locked voting power of a daily vesting lockup with a cliff,
+    // iteratively like locked_daily_power(): the first cliff_days
+    // cliff-locked deposits all unlock at the end of the cliff.
+    fn locked_daily_with_cliff_power(amount: u64, day: f64, total_days: u64, cliff_days: u64) -> u64 {
+        let mut total = 0f64;
+        for p in 1..=total_days {
+            let unlock_day = p.max(cliff_days) as f64;
+            if unlock_day > day {
+                total += locked_cliff_power_float(amount / total_days, unlock_day - day);
+            }
+        }
+        (total + 0.0001).floor() as u64
+    }
+
+    // Calculates locked voting power of a cliff lockup.
+    fn locked_cliff_power(amount: u64, day: f64, total_days: u64) -> u64 {
+        if day >= total_days as f64 {
+            return 0;
+        }
+        (locked_cliff_power_float(amount, total_days as f64 - day) + 0.0001).floor() as u64
+    }
+
     fn locked_cliff_power_float(amount: u64, remaining_days: f64) -> f64 {
         let relevant_days = if remaining_days < MAX_DAYS_LOCKED as f64 {
             remaining_days
@@ -1,12 +1,24 @@
+pub use delegation_record::*;
 pub use deposit_entry::*;
+pub use excluded_supply::*;
 pub use lockup::*;
+pub use node::*;
 pub use registrar::*;
+pub use registrar_config::*;
+pub use registrar_stats::*;
 pub use voter::*;
+pub use voter_weight_history::*;
 
+mod delegation_record;
 mod deposit_entry;
+mod excluded_supply;
 mod lockup;
+mod node;
 mod registrar;
+mod registrar_config;
+mod registrar_stats;
 mod voter;
+mod voter_weight_history;
 
 use crate::vote_weight_record;
 use crate::max_voter_weight_record;
@@ -0,0 +1,244 @@
+use crate::error::*;
+use crate::state::registrar::Registrar;
+use anchor_lang::prelude::*;
+use bytemuck::{Pod, Zeroable};
+
+/// The length of the endpoint of a node
+pub const NODE_ENDPOINT_LEN: usize = 64;
+
+/// On-chain registration of a node, a voter with an active node deposit.
+///
+/// There can only be a single node per voter. It is created by
+/// `node_deposit` and closed by `node_release_deposit`.
+#[account(zero_copy)]
+pub struct Node {
+    pub registrar: Pubkey,
+    /// Voter authority of the voter running the node
+    pub voter_authority: Pubkey,
+    /// Key operating the node on behalf of the voter authority
+    pub operator: Pubkey,
+    /// Endpoint or label of the node, utf-8 padded with zeros
+    pub endpoint: [u8; NODE_ENDPOINT_LEN],
+    /// The time the node was registered at
+    pub registration_ts: i64,
+
+    pub status: NodeStatus,
+    pub bump: u8,
+    /// True once the node deposit stayed below the node security deposit
+    /// beyond the grace period, see `Node::update_compliance`.
+    pub non_compliant: u8,
+    pub reserved1: [u8; 5],
+    /// The time the node deposit was first found below the node security
+    /// deposit, which starts the grace period. Zero while it satisfies it.
+    pub grace_period_start_ts: i64,
+    pub reserved2: [u64; 3],
+}
+const_assert!(std::mem::size_of::<Node>() == 3 * 32 + NODE_ENDPOINT_LEN + 8 + 1 + 1 + 1 + 5 + 8 + 3 * 8);
+const_assert!(std::mem::size_of::<Node>() % 8 == 0);
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Zeroable, Debug)]
+pub enum NodeStatus {
+    /// The node is running.
+    Active,
+
+    /// The node announced to leave.
+    Exiting,
+
+    /// The node was punished by the realm, see `slash_node_deposit`.
+    Jailed,
+}
+
+unsafe impl Pod for NodeStatus {}
+
+impl Node {
+    /// Registers an active node, counted by the registrar.
+    pub fn init(
+        &mut self,
+        registrar_key: Pubkey,
+        voter_authority: Pubkey,
+        bump: u8,
+        curr_ts: i64,
+        registrar: &mut Registrar,
+    ) {
+        self.registrar = registrar_key;
+        self.voter_authority = voter_authority;
+        self.operator = voter_authority;
+        self.endpoint = [0; NODE_ENDPOINT_LEN];
+        self.registration_ts = curr_ts;
+        self.status = NodeStatus::Active;
+        self.bump = bump;
+        self.non_compliant = 0;
+        self.grace_period_start_ts = 0;
+        registrar.active_node_count = registrar.active_node_count.checked_add(1).unwrap();
+    }
+
+    #[inline(always)]
+    pub fn is_active(&self) -> bool {
+        self.status == NodeStatus::Active
+    }
+
+    /// Changes the status of the node, keeping the registrar's count of
+    /// active nodes up to date. Only nodes with an active node deposit can
+    /// become active.
+    pub fn set_status(
+        &mut self,
+        status: NodeStatus,
+        node_deposit_active: bool,
+        registrar: &mut Registrar,
+    ) -> Result<()> {
+        require!(
+            status != NodeStatus::Active || node_deposit_active,
+            VsrError::InactiveDepositEntry
+        );
+
+        if self.is_active() && status != NodeStatus::Active {
+            registrar.active_node_count = registrar.active_node_count.checked_sub(1).unwrap();
+        } else if !self.is_active() && status == NodeStatus::Active {
+            registrar.active_node_count = registrar.active_node_count.checked_add(1).unwrap();
+        }
+        self.status = status;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn is_compliant(&self) -> bool {
+        self.non_compliant == 0
+    }
+
+    /// Flags the node as non-compliant if its node deposit of
+    /// 'amount_deposited_native' no longer satisfies the node security deposit.
+    ///
+    /// The grace period starts the first time the node deposit is found below
+    /// the node security deposit, and ends once it satisfies it again.
+    pub fn update_compliance(
+        &mut self,
+        amount_deposited_native: u64,
+        curr_ts: i64,
+        registrar: &Registrar,
+    ) {
+        if amount_deposited_native >= registrar.deposit_config.node_security_deposit {
+            self.grace_period_start_ts = 0;
+        } else if self.grace_period_start_ts == 0 {
+            self.grace_period_start_ts = curr_ts;
+        }
+        self.non_compliant =
+            u8::from(!self.is_deposit_compliant(amount_deposited_native, curr_ts, registrar));
+    }
+
+    /// Whether a node deposit of 'amount_deposited_native' satisfies the
+    /// node security deposit, or is still within the grace period of the node.
+    pub fn is_deposit_compliant(
+        &self,
+        amount_deposited_native: u64,
+        curr_ts: i64,
+        registrar: &Registrar,
+    ) -> bool {
+        if amount_deposited_native >= registrar.deposit_config.node_security_deposit {
+            return true;
+        }
+
+        // The grace period starts on the next 'update_compliance' if it did not yet
+        let grace_period_start_ts = if self.grace_period_start_ts == 0 {
+            curr_ts
+        } else {
+            self.grace_period_start_ts
+        };
+        let grace_period_secs =
+            i64::try_from(registrar.deposit_config.node_security_deposit_grace_period_secs)
+                .unwrap_or(i64::MAX);
+        curr_ts < grace_period_start_ts.saturating_add(grace_period_secs)
+    }
+
+    pub fn set_endpoint(&mut self, endpoint: &str) -> Result<()> {
+        let bytes = endpoint.as_bytes();
+        require_gte!(NODE_ENDPOINT_LEN, bytes.len(), VsrError::InvalidNodeEndpoint);
+
+        self.endpoint = [0; NODE_ENDPOINT_LEN];
+        self.endpoint[..bytes.len()].copy_from_slice(bytes);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_status_test() {
+        let mut registrar: Registrar = Zeroable::zeroed();
+        let mut node: Node = Zeroable::zeroed();
+        node.init(Pubkey::new_unique(), Pubkey::new_unique(), 0, 1, &mut registrar);
+        assert!(node.is_active());
+        assert_eq!(registrar.active_node_count, 1);
+
+        node.set_status(NodeStatus::Exiting, true, &mut registrar).unwrap();
+        assert_eq!(registrar.active_node_count, 0);
+        node.set_status(NodeStatus::Jailed, false, &mut registrar).unwrap();
+        assert_eq!(registrar.active_node_count, 0);
+
+        // nodes without a node deposit can not become active
+        assert_eq!(
+            node.set_status(NodeStatus::Active, false, &mut registrar),
+            Err(error!(VsrError::InactiveDepositEntry))
+        );
+        assert_eq!(node.status, NodeStatus::Jailed);
+        assert_eq!(registrar.active_node_count, 0);
+
+        node.set_status(NodeStatus::Active, true, &mut registrar).unwrap();
+        assert_eq!(registrar.active_node_count, 1);
+        node.set_status(NodeStatus::Active, true, &mut registrar).unwrap();
+        assert_eq!(registrar.active_node_count, 1);
+    }
+
+    #[test]
+    fn update_compliance_test() {
+        let mut registrar: Registrar = Zeroable::zeroed();
+        registrar.deposit_config.node_security_deposit = 100;
+        registrar.deposit_config.node_security_deposit_grace_period_secs = 10;
+
+        // the grace period starts when the node deposit is first found below
+        let mut node: Node = Zeroable::zeroed();
+        node.update_compliance(50, 1, &registrar);
+        assert_eq!(node.grace_period_start_ts, 1);
+        assert!(node.is_compliant());
+        node.update_compliance(50, 10, &registrar);
+        assert_eq!(node.grace_period_start_ts, 1);
+        assert!(node.is_compliant());
+        node.update_compliance(50, 11, &registrar);
+        assert!(!node.is_compliant());
+
+        // topping up ends the grace period
+        node.update_compliance(100, 11, &registrar);
+        assert_eq!(node.grace_period_start_ts, 0);
+        assert!(node.is_compliant());
+
+        // other nodes have their own grace period
+        let mut other_node: Node = Zeroable::zeroed();
+        assert!(other_node.is_deposit_compliant(50, 20, &registrar));
+        other_node.update_compliance(50, 20, &registrar);
+        assert!(other_node.is_deposit_compliant(50, 29, &registrar));
+        assert!(!other_node.is_deposit_compliant(50, 30, &registrar));
+    }
+
+    #[test]
+    fn set_endpoint_test() -> Result<()> {
+        let mut node: Node = Zeroable::zeroed();
+        node.set_endpoint("https://node.example.com")?;
+        assert_eq!(&node.endpoint[..24], b"https://node.example.com");
+        assert!(node.endpoint[24..].iter().all(|b| *b == 0));
+
+        // a shorter endpoint replaces the previous one
+        node.set_endpoint("node-1")?;
+        assert_eq!(&node.endpoint[..6], b"node-1");
+        assert!(node.endpoint[6..].iter().all(|b| *b == 0));
+
+        assert_eq!(
+            node.set_endpoint(&"x".repeat(NODE_ENDPOINT_LEN + 1)),
+            Err(error!(VsrError::InvalidNodeEndpoint))
+        );
+        node.set_endpoint(&"x".repeat(NODE_ENDPOINT_LEN))?;
+
+        Ok(())
+    }
+}
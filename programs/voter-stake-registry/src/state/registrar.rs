@@ -1,15 +1,37 @@
 use crate::error::*;
 use crate::state::lockup::*;
+use crate::state::registrar_stats::RegistrarStats;
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
+use bytemuck::{Pod, Zeroable};
+use std::cmp::min;
 
 pub const SCALED_FACTOR_BASE: u64 = 1_000_000_000;
-/// Total amount of staking rewards
+/// Basis points of a whole
+pub const BPS_BASE: u64 = 10_000;
+/// Default total amount of staking rewards
 pub const TOTAL_REWARD_AMOUNT: u64 = 770_000_000_000_000; // 770M
-/// Floor of permanently locked tokens to be met for full rewards
+/// Default floor of the total reward weight to be met for full rewards
 pub const FULL_REWARD_PERMANENTLY_LOCKED_FLOOR: u64 = 195_000_000_000_000; // 195M
-/// Seconds in a year
-pub const SECS_PER_YEAR: u64 = SECS_PER_DAY * 365;
+/// Default share of the remaining rewards emitted per rotation, in basis points
+pub const REWARD_EMISSION_RATE_BPS: u16 = 1_200; // 12%
+
+/// Layout version of registrars created by this program, see
+/// `Registrar::migrate`
+pub const REGISTRAR_VERSION: u8 = 1;
+
+/// Bit of 'Registrar::paused' that halts deposits and grants
+pub const PAUSE_DEPOSITS: u8 = 1 << 0;
+/// Bit of 'Registrar::paused' that halts releasing, splitting, merging and
+/// slashing deposit entries
+pub const PAUSE_RELEASES: u8 = 1 << 1;
+/// Bit of 'Registrar::paused' that halts withdraws, early unlocks and
+/// clawbacks
+pub const PAUSE_WITHDRAWS: u8 = 1 << 2;
+/// Bit of 'Registrar::paused' that halts claiming and compounding rewards
+pub const PAUSE_CLAIMS: u8 = 1 << 3;
+/// All bits of 'Registrar::paused'
+pub const PAUSE_ALL: u8 = PAUSE_DEPOSITS | PAUSE_RELEASES | PAUSE_WITHDRAWS | PAUSE_CLAIMS;
 
 /// Instance of a voting rights distributor.
 #[account(zero_copy)]
@@ -19,13 +41,15 @@ pub struct Registrar {
     pub realm_authority: Pubkey,
     pub governing_token_mint: Pubkey,
 
-    /// Storage for voting configuration: voting_config + reserved1.
     pub voting_config: VotingConfig,
-    pub reserved1: [u64; 5],
 
-    /// Storage for deposit configuration: deposit_config + reserved2.
+    /// Storage for deposit configuration: deposit_config + early_unlock_penalty_config.
     pub deposit_config: DepositConfig,
-    pub reserved2: [u64; 5],
+    /// Penalty of 'early_unlock', zeroed while early unlocks are disabled.
+    pub early_unlock_penalty_config: EarlyUnlockPenaltyConfig,
+
+    /// Boost of the reward weight of locked tokens by their remaining lockup time.
+    pub reward_boost_config: RewardBoostConfig,
 
     // The current value of reward amount per second.
     pub current_reward_amount_per_second: u128,
@@ -51,11 +75,72 @@ pub struct Registrar {
 
     pub bump: u8,
     pub max_voter_weight_record_bump: u8,
-    pub reserved3: [u8; 14],
-    pub reserved4: [u64; 9],
+    /// True once 'total_reward_weight' is tracked, see
+    /// `Registrar::initialize_total_reward_weight_if_needed`.
+    pub total_reward_weight_initialized: u8,
+    /// True if the registrar config has voter weight action requirements,
+    /// which 'update_voter_weight_record' must apply then.
+    pub has_voter_weight_action_requirements: u8,
+    /// Vesting cliffs of released deposits, zeroed for no cliff.
+    pub release_cliff_config: ReleaseCliffConfig,
+    /// True if instructions that reduce or restructure locked tokens need the
+    /// voter's token owner record and are forbidden while governance forbids
+    /// withdraws, see `assert_can_change_lockup`.
+    pub restrict_lockup_changes: u8,
+    /// How 'update_max_vote_weight' computes the max vote weight.
+    pub max_vote_weight_mode: MaxVoteWeightMode,
+    /// Bitmask of the paused instruction families, see `PAUSE_ALL`.
+    /// Set by the realm authority or the guardian of the registrar config
+    /// through 'set_paused', only the realm authority can unpause.
+    pub paused: u8,
+    /// Layout version, zero for registrars created before it was introduced.
+    /// 'migrate_registrar' brings it up to `REGISTRAR_VERSION`.
+    pub version: u8,
+    /// True once 'funded_reward_amount' is tracked, see
+    /// `Registrar::initialize_funded_reward_amount`.
+    pub funded_reward_amount_initialized: u8,
+    /// True once 'uncounted_voter_count' is known. Registrars created before
+    /// the deposit totals were introduced need 'update_uncounted_voter_count'.
+    pub uncounted_voter_count_initialized: u8,
+    pub reserved5: [u8; 2],
+
+    /// Emission schedule of staking rewards.
+    /// Registrars created before it was introduced have it zeroed,
+    /// see `Registrar::emission_config`.
+    pub emission_config: EmissionConfig,
+
+    /// Total amount of rewards funded through 'fund_rewards'.
+    /// Rewards are never issued beyond it. Registrars created before it was
+    /// introduced issue no rewards until 'migrate_registrar' derives it from
+    /// their reward vault.
+    pub funded_reward_amount: u64,
+
+    /// Sum of the reward weights of all voters, which drives the reward index.
+    /// Reward weights decay between balance-changing actions, the rewards
+    /// issued for the decayed part are returned by `LoadedVoter::accrue_rewards`.
+    pub total_reward_weight: u64,
+
+    /// Number of nodes with status 'Active', see `Node`.
+    /// Nodes registered before `Node` was introduced are only counted once
+    /// they register through 'update_node'.
+    pub active_node_count: u64,
+
+    /// Number of voters whose deposits are not counted in the deposit totals
+    /// yet, see `Registrar::assert_deposit_totals_complete`.
+    pub uncounted_voter_count: u64,
+
+    /// Sum of the tokens in the deposit entries of all voters.
+    /// Voters created before it was introduced are only counted after their
+    /// next balance-changing action or 'refresh_reward_weight'.
+    pub total_deposited_amount: u64,
+    /// Sum of the tokens locked when the lockups of all deposit entries
+    /// started, see `DepositEntry::amount_initially_locked_native`. Vesting
+    /// does not reduce it until the deposit entry changes again.
+    pub total_locked_amount: u64,
 }
 const_assert!(
-    std::mem::size_of::<Registrar>() == 4 * 32 + 80 + 64 + 16 + 8 + 8 + 16 + 8 * 3 + 1 + 1 + 86
+    std::mem::size_of::<Registrar>()
+        == 4 * 32 + 80 + 24 + 16 + 24 + 16 + 8 + 8 + 16 + 8 * 3 + 1 + 1 + 1 + 1 + 4 + 1 + 1 + 1 + 1 + 1 + 1 + 2 + 24 + 8 + 8 + 8 + 8 + 8 + 8
 );
 const_assert!(std::mem::size_of::<Registrar>() % 8 == 0);
 
@@ -73,40 +158,85 @@ impl Registrar {
             return Err(error!(VsrError::InvalidGoverningMint));
         }
 
-        let mut sum = self.voting_config.baseline_vote_weight(mint.supply)?;
+        self.max_vote_weight_for(mint.supply, mint.supply)
+    }
+
+    /// The max vote weight of `amount_native` tokens of which
+    /// `locked_amount_native` are locked up for the saturation period or
+    /// longer.
+    pub fn max_vote_weight_for(&self, amount_native: u64, locked_amount_native: u64) -> Result<u64> {
+        let mut sum = self.voting_config.baseline_vote_weight(amount_native)?;
         sum = sum
             .checked_add(
                 self.voting_config
-                    .max_extra_lockup_vote_weight(mint.supply)?,
+                    .max_extra_lockup_vote_weight(min(locked_amount_native, amount_native))?,
             )
             .ok_or_else(|| error!(VsrError::VoterWeightOverflow))?;
         Ok(sum)
     }
 
+    /// Fails with the paused error of the instruction family if `flag`,
+    /// one of the `PAUSE_*` bits, is set in 'paused'.
+    pub fn assert_not_paused(&self, flag: u8) -> Result<()> {
+        if self.paused & flag == 0 {
+            return Ok(());
+        }
+
+        Err(match flag {
+            PAUSE_DEPOSITS => error!(VsrError::DepositsPaused),
+            PAUSE_RELEASES => error!(VsrError::ReleasesPaused),
+            PAUSE_WITHDRAWS => error!(VsrError::WithdrawsPaused),
+            PAUSE_CLAIMS => error!(VsrError::ClaimsPaused),
+            _ => unreachable!(),
+        })
+    }
+
+    /// The emission config in effect, the zeroed config of registrars created
+    /// before it was introduced stands for the default one.
+    pub fn emission_config(&self) -> EmissionConfig {
+        if self.emission_config.is_initialized() {
+            self.emission_config
+        } else {
+            EmissionConfig::default()
+        }
+    }
+
     pub fn accrue_rewards(&mut self, curr_ts: i64) {
+        self.initialize_total_reward_weight_if_needed();
+
         let seconds_delta = curr_ts.checked_sub(self.reward_accrual_ts).unwrap() as u64;
         if seconds_delta == 0 {
             return;
         }
 
-        let reward_index_delta = if self.permanently_locked_amount != 0 {
+        let mut reward_index_delta = if self.total_reward_weight != 0 {
             self.current_reward_amount_per_second
                 .mul_scalar(seconds_delta as core::primitive::u128)
                 .div_scalar(u64::max(
-                    self.permanently_locked_amount,
-                    FULL_REWARD_PERMANENTLY_LOCKED_FLOOR,
+                    self.total_reward_weight,
+                    self.emission_config().full_reward_permanently_locked_floor,
                 ) as core::primitive::u128)
         } else {
             u128::new(0)
         };
 
-        let issued_reward_amount_delta = u64::try_from(
+        let mut issued_reward_amount_delta = u64::try_from(
             reward_index_delta
-                .mul_scalar(self.permanently_locked_amount as core::primitive::u128)
+                .mul_scalar(self.total_reward_weight as core::primitive::u128)
                 .truncate(),
         )
         .unwrap();
 
+        // Rewards are never issued beyond the funded amount
+        let unissued_funded_reward_amount = self.unissued_funded_reward_amount();
+        if issued_reward_amount_delta > unissued_funded_reward_amount {
+            issued_reward_amount_delta = unissued_funded_reward_amount;
+            reward_index_delta = u128::new_with_denom(
+                unissued_funded_reward_amount as core::primitive::u128,
+                self.total_reward_weight as core::primitive::u128,
+            );
+        }
+
         self.reward_accrual_ts = curr_ts;
         self.reward_index = self.reward_index.add(reward_index_delta);
         self.issued_reward_amount = self
@@ -117,20 +247,155 @@ impl Registrar {
         self.rotate_reward_amount_per_second_if_needed(curr_ts);
     }
 
+    /// Transforms the registrar from its layout version up to
+    /// `REGISTRAR_VERSION`, one version at a time.
+    ///
+    /// The account must have been reallocated to the current size before,
+    /// new fields start zeroed. `reward_vault_amount` is the balance of the
+    /// reward vault of the registrar.
+    pub fn migrate(
+        &mut self,
+        reward_vault_amount: u64,
+        registrar_stats: &RegistrarStats,
+    ) -> Result<()> {
+        require_gte!(
+            REGISTRAR_VERSION,
+            self.version,
+            VsrError::UnsupportedLayoutVersion
+        );
+
+        while self.version < REGISTRAR_VERSION {
+            match self.version {
+                0 => self.migrate_v0_to_v1(reward_vault_amount, registrar_stats),
+                _ => unreachable!(),
+            }
+            self.version += 1;
+        }
+        Ok(())
+    }
+
+    /// Version 1 stores the emission config, the total reward weight and the
+    /// funded reward amount that version 0 registrars may only have implied.
+    fn migrate_v0_to_v1(&mut self, reward_vault_amount: u64, registrar_stats: &RegistrarStats) {
+        self.emission_config = self.emission_config();
+        self.initialize_total_reward_weight_if_needed();
+        self.initialize_funded_reward_amount(reward_vault_amount, registrar_stats);
+    }
+
+    /// Registrars created before reward weights were introduced only rewarded
+    /// permanently locked tokens, hence their total reward weight starts from
+    /// 'permanently_locked_amount'. Voters catch up in `Voter::accrue_rewards`.
+    fn initialize_total_reward_weight_if_needed(&mut self) {
+        if self.total_reward_weight_initialized == 0 {
+            self.total_reward_weight = self.permanently_locked_amount;
+            self.total_reward_weight_initialized = 1;
+        }
+    }
+
+    /// Registrars created before 'fund_rewards' was introduced can only
+    /// issue the tokens of their reward vault that are not owed to voters
+    /// already. The unclaimed rewards are bounded from above by the registrar
+    /// stats, so the funded reward amount never exceeds the vault.
+    fn initialize_funded_reward_amount(
+        &mut self,
+        reward_vault_amount: u64,
+        registrar_stats: &RegistrarStats,
+    ) {
+        let max_unclaimed_reward_amount =
+            registrar_stats.max_unclaimed_reward_amount(self.issued_reward_amount);
+        self.funded_reward_amount = self
+            .issued_reward_amount
+            .checked_add(reward_vault_amount.saturating_sub(max_unclaimed_reward_amount))
+            .unwrap();
+        self.funded_reward_amount_initialized = 1;
+    }
+
+    /// Counts a voter created before the deposit totals were introduced,
+    /// once its deposits were added to them.
+    pub fn count_voter_deposits(&mut self) {
+        self.uncounted_voter_count = self.uncounted_voter_count.saturating_sub(1);
+    }
+
+    /// The deposit totals only cover all deposits once the voters created
+    /// before they were introduced are counted, which requires their number
+    /// to be known.
+    pub fn assert_deposit_totals_complete(&self) -> Result<()> {
+        require!(
+            self.uncounted_voter_count_initialized != 0 && self.uncounted_voter_count == 0,
+            VsrError::UncountedVoters
+        );
+        Ok(())
+    }
+
+    /// Adds early unlock penalties to the staking rewards. They are funded
+    /// and added to the emission budget, so that the following rotations
+    /// emit them on top of the emission schedule.
+    pub fn add_penalty_reward_amount(&mut self, amount: u64) {
+        self.emission_config = self.emission_config();
+        self.emission_config.total_reward_amount = self
+            .emission_config
+            .total_reward_amount
+            .checked_add(amount)
+            .unwrap();
+        self.funded_reward_amount = self.funded_reward_amount.checked_add(amount).unwrap();
+    }
+
+    /// Amount of funded rewards that were not issued yet.
+    pub fn unissued_funded_reward_amount(&self) -> u64 {
+        self.funded_reward_amount
+            .saturating_sub(self.issued_reward_amount)
+    }
+
+    /// Number of seconds until the funded rewards are used up at the current
+    /// emission rate, None if no rewards are being issued.
+    pub fn reward_runway_secs(&self) -> Option<u64> {
+        if self.total_reward_weight == 0 {
+            return None;
+        }
+
+        let issued_reward_amount_per_second = self
+            .current_reward_amount_per_second
+            .div_scalar(u64::max(
+                self.total_reward_weight,
+                self.emission_config().full_reward_permanently_locked_floor,
+            ) as core::primitive::u128)
+            .mul_scalar(self.total_reward_weight as core::primitive::u128);
+        if issued_reward_amount_per_second.as_u128() == 0 {
+            return None;
+        }
+
+        let runway_secs = u128::new(self.unissued_funded_reward_amount() as core::primitive::u128)
+            .as_u128()
+            .checked_div(issued_reward_amount_per_second.as_u128())
+            .unwrap();
+        Some(u64::try_from(runway_secs).unwrap_or(u64::MAX))
+    }
+
     fn rotate_reward_amount_per_second_if_needed(&mut self, curr_ts: i64) {
-        if self.last_reward_amount_per_second_rotated_ts + SECS_PER_YEAR as i64 <= curr_ts {
-            let current_annual_reward_amount = TOTAL_REWARD_AMOUNT
-                .checked_sub(self.issued_reward_amount)
-                .unwrap()
-                .checked_mul(12)
-                .unwrap()
-                .checked_div(100)
-                .unwrap() as core::primitive::u128;
-            self.current_reward_amount_per_second =
-                u128::new_with_denom(current_annual_reward_amount, SECS_PER_YEAR as core::primitive::u128);
-            self.last_reward_amount_per_second_rotated_ts = curr_ts;
+        let rotation_secs = self.emission_config().rotation_secs;
+        if self.last_reward_amount_per_second_rotated_ts + rotation_secs as i64 <= curr_ts {
+            self.rotate_reward_amount_per_second(curr_ts);
         }
     }
+
+    /// Recomputes 'current_reward_amount_per_second' from the emission config
+    /// and the rewards that were not issued yet.
+    pub fn rotate_reward_amount_per_second(&mut self, curr_ts: i64) {
+        let emission_config = self.emission_config();
+        let current_rotation_reward_amount = (emission_config
+            .total_reward_amount
+            .checked_sub(self.issued_reward_amount)
+            .unwrap() as core::primitive::u128)
+            .checked_mul(emission_config.emission_rate_bps as core::primitive::u128)
+            .unwrap()
+            .checked_div(BPS_BASE as core::primitive::u128)
+            .unwrap();
+        self.current_reward_amount_per_second = u128::new_with_denom(
+            current_rotation_reward_amount,
+            emission_config.rotation_secs as core::primitive::u128,
+        );
+        self.last_reward_amount_per_second_rotated_ts = curr_ts;
+    }
 }
 
 #[macro_export]
@@ -147,6 +412,26 @@ macro_rules! registrar_seeds {
 
 pub use registrar_seeds;
 
+/// How the max vote weight of a registrar is computed, see
+/// 'update_max_vote_weight'.
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Zeroable)]
+pub enum MaxVoteWeightMode {
+    /// From the supply of the governing token mint.
+    Supply,
+
+    /// From the tokens deposited into the registrar, see
+    /// 'Registrar::total_deposited_amount' and 'Registrar::total_locked_amount'.
+    Deposited,
+
+    /// From the supply of the governing token mint less the balances of the
+    /// token accounts in `ExcludedSupply`, such as the reward vault or the
+    /// treasury.
+    CirculatingSupply,
+}
+
+unsafe impl Pod for MaxVoteWeightMode {}
+
 /// Exchange rate for an asset that can be used to mint voting rights.
 ///
 /// See documentation of configure_voting_mint for details on how
@@ -171,11 +456,24 @@ pub struct VotingConfig {
 
     /// Number of seconds of lockup needed to reach the maximum lockup bonus.
     pub lockup_saturation_secs: u64,
+
+    /// Shape of the extra vote weight up to lockup_saturation_secs.
+    /// Registrars created before it was introduced have it zeroed,
+    /// which is the linear curve.
+    pub vote_weight_curve: VoteWeightCurve,
 }
-const_assert!(std::mem::size_of::<VotingConfig>() == 3 * 8);
+const_assert!(std::mem::size_of::<VotingConfig>() == 3 * 8 + 40);
 const_assert!(std::mem::size_of::<VotingConfig>() % 8 == 0);
 
 impl VotingConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.lockup_saturation_secs > 0,
+            VsrError::LockupSaturationMustBePositive
+        );
+        self.vote_weight_curve.validate()
+    }
+
     /// Apply a factor in SCALED_FACTOR_BASE units.
     fn apply_factor(base: u64, factor: u64) -> Result<u64> {
         let compute = || -> Option<u64> {
@@ -207,6 +505,230 @@ impl VotingConfig {
     }
 }
 
+/// Maximum number of points of a `VoteWeightCurve`.
+pub const MAX_VOTE_WEIGHT_CURVE_POINTS: usize = 8;
+
+#[repr(u8)]
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Zeroable)]
+pub enum VoteWeightCurveKind {
+    /// The extra vote weight grows linearly with the remaining lockup time.
+    Linear,
+
+    /// The extra vote weight follows straight lines between the curve points.
+    PiecewiseLinear,
+}
+
+unsafe impl Pod for VoteWeightCurveKind {}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+#[zero_copy]
+pub struct VoteWeightCurvePoint {
+    /// Remaining lockup time, in basis points of 'lockup_saturation_secs'.
+    pub lockup_bps: u16,
+    /// Share of the maximum extra lockup vote weight, in basis points.
+    pub weight_bps: u16,
+}
+
+/// Share of the maximum extra lockup vote weight a locked token gets for its
+/// remaining lockup time.
+///
+/// The piecewise-linear curve goes from (0, 0) through 'points' to
+/// ('lockup_saturation_secs', BPS_BASE), for example a steeper boost for the
+/// first year. It never decreases and never exceeds the maximum, so
+/// `Registrar::max_vote_weight` stays an upper bound.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+#[zero_copy]
+pub struct VoteWeightCurve {
+    pub kind: VoteWeightCurveKind,
+    /// Number of used 'points', the others must be zeroed.
+    pub point_count: u8,
+    pub reserved: [u8; 6],
+    /// Points between the end points of a piecewise-linear curve, with
+    /// increasing 'lockup_bps'.
+    pub points: [VoteWeightCurvePoint; MAX_VOTE_WEIGHT_CURVE_POINTS],
+}
+const_assert!(std::mem::size_of::<VoteWeightCurve>() == 1 + 1 + 6 + 4 * MAX_VOTE_WEIGHT_CURVE_POINTS);
+
+impl Default for VoteWeightCurve {
+    fn default() -> Self {
+        VoteWeightCurve {
+            kind: VoteWeightCurveKind::Linear,
+            point_count: 0,
+            reserved: [0; 6],
+            points: [VoteWeightCurvePoint::default(); MAX_VOTE_WEIGHT_CURVE_POINTS],
+        }
+    }
+}
+
+impl VoteWeightCurve {
+    #[inline(always)]
+    pub fn is_linear(&self) -> bool {
+        self.kind == VoteWeightCurveKind::Linear
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let point_count = self.point_count as usize;
+        require!(
+            point_count <= MAX_VOTE_WEIGHT_CURVE_POINTS
+                && (!self.is_linear() || point_count == 0),
+            VsrError::InvalidVoteWeightCurve
+        );
+
+        let mut prev = VoteWeightCurvePoint::default();
+        for (i, point) in self.points.iter().enumerate() {
+            if i >= point_count {
+                require!(
+                    point.lockup_bps == 0 && point.weight_bps == 0,
+                    VsrError::InvalidVoteWeightCurve
+                );
+                continue;
+            }
+            require!(
+                point.lockup_bps > prev.lockup_bps
+                    && (point.lockup_bps as u64) < BPS_BASE
+                    && point.weight_bps >= prev.weight_bps
+                    && (point.weight_bps as u64) <= BPS_BASE,
+                VsrError::InvalidVoteWeightCurve
+            );
+            prev = *point;
+        }
+        Ok(())
+    }
+
+    /// Corners of the piecewise-linear curve as (lockup secs, weight bps),
+    /// from (0, 0) to (lockup_saturation_secs, BPS_BASE).
+    fn corners(&self, lockup_saturation_secs: u64) -> impl Iterator<Item = (u64, u64)> + '_ {
+        let to_secs = move |lockup_bps: u16| {
+            (lockup_saturation_secs as core::primitive::u128 * lockup_bps as core::primitive::u128
+                / BPS_BASE as core::primitive::u128) as u64
+        };
+        std::iter::once((0, 0))
+            .chain(
+                self.points[..self.point_count as usize]
+                    .iter()
+                    .map(move |p| (to_secs(p.lockup_bps), p.weight_bps as u64)),
+            )
+            .chain(std::iter::once((lockup_saturation_secs, BPS_BASE)))
+    }
+
+    /// Segments of the piecewise-linear curve as ((x0, y0), (x1, y1)), skipping
+    /// the empty ones.
+    fn segments(
+        &self,
+        lockup_saturation_secs: u64,
+    ) -> impl Iterator<Item = ((u64, u64), (u64, u64))> + '_ {
+        self.corners(lockup_saturation_secs)
+            .zip(self.corners(lockup_saturation_secs).skip(1))
+            .filter(|(start, end)| end.0 > start.0)
+    }
+
+    /// The share of the maximum extra lockup vote weight for 'secs' of
+    /// remaining lockup time, in basis points.
+    pub fn weight_bps(&self, secs: u64, lockup_saturation_secs: u64) -> u64 {
+        if secs >= lockup_saturation_secs {
+            return BPS_BASE;
+        }
+        if self.is_linear() {
+            return (secs as core::primitive::u128 * BPS_BASE as core::primitive::u128
+                / lockup_saturation_secs as core::primitive::u128) as u64;
+        }
+        for ((x0, y0), (x1, y1)) in self.segments(lockup_saturation_secs) {
+            if secs < x1 {
+                return y0
+                    + ((secs - x0) as core::primitive::u128 * (y1 - y0) as core::primitive::u128
+                        / (x1 - x0) as core::primitive::u128) as u64;
+            }
+        }
+        BPS_BASE
+    }
+
+    /// Sum of weight_bps() over 'count' lockups of 'first_secs',
+    /// 'first_secs' + 'period_secs', 'first_secs' + 2 * 'period_secs', ...
+    ///
+    /// The lockups falling onto the same segment of the curve are summed up
+    /// at once, rounding down per segment.
+    pub fn sum_weight_bps(
+        &self,
+        first_secs: u64,
+        count: u64,
+        period_secs: u64,
+        lockup_saturation_secs: u64,
+    ) -> core::primitive::u128 {
+        type U128 = core::primitive::u128;
+        // index of the first lockup with at least 'secs' left
+        let index_of = |secs: u64| -> u64 {
+            if secs <= first_secs {
+                0
+            } else {
+                let ceil = (secs - first_secs)
+                    .checked_add(period_secs - 1)
+                    .unwrap()
+                    / period_secs;
+                min(count, ceil)
+            }
+        };
+
+        let mut sum: U128 = 0;
+        for ((x0, y0), (x1, y1)) in self.segments(lockup_saturation_secs) {
+            let (lo, hi) = (index_of(x0), index_of(x1));
+            if hi <= lo {
+                continue;
+            }
+            let n = (hi - lo) as U128;
+            // \sum_{k=lo}^{hi-1} (first_secs + k * period_secs - x0)
+            let sum_secs = n * first_secs as U128
+                + period_secs as U128 * ((lo + hi - 1) as U128 * n / 2)
+                - n * x0 as U128;
+            sum += n * y0 as U128 + sum_secs * (y1 - y0) as U128 / (x1 - x0) as U128;
+        }
+        let saturated = count - index_of(lockup_saturation_secs);
+        sum + saturated as U128 * BPS_BASE as U128
+    }
+}
+
+/// Boost of the reward weight of locked tokens by their remaining lockup time.
+///
+/// Every locked token has a reward weight of 1, plus an extra reward weight
+/// that scales linearly with the remaining lockup time like the extra lockup
+/// vote weight of `VotingConfig` with the linear curve. The zeroed config
+/// boosts nothing.
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+#[zero_copy]
+pub struct RewardBoostConfig {
+    /// Maximum extra reward weight factor for lockups.
+    ///
+    /// This is the extra reward weight gained for lockups lasting
+    /// lockup_saturation_secs or longer.
+    ///
+    /// In 1/SCALED_FACTOR_BASE units.
+    pub max_extra_lockup_reward_weight_scaled_factor: u64,
+
+    /// Number of seconds of lockup needed to reach the maximum reward boost.
+    pub lockup_saturation_secs: u64,
+}
+const_assert!(std::mem::size_of::<RewardBoostConfig>() == 2 * 8);
+const_assert!(std::mem::size_of::<RewardBoostConfig>() % 8 == 0);
+
+impl RewardBoostConfig {
+    /// The maximum extra reward weight a number of locked up native tokens can have.
+    /// Will be multiplied with a factor between 0 and 1 for the lockup duration.
+    pub fn max_extra_lockup_reward_weight(&self, amount_native: u64) -> Result<u64> {
+        VotingConfig::apply_factor(
+            amount_native,
+            self.max_extra_lockup_reward_weight_scaled_factor,
+        )
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.max_extra_lockup_reward_weight_scaled_factor == 0
+                || self.lockup_saturation_secs > 0,
+            VsrError::LockupSaturationMustBePositive
+        );
+        Ok(())
+    }
+}
+
 #[derive(AnchorSerialize, AnchorDeserialize)]
 #[zero_copy]
 pub struct DepositConfig {
@@ -216,10 +738,168 @@ pub struct DepositConfig {
     pub node_deposit_lockup_duration: LockupTimeDuration,
     /// Specific amount for node deposit.
     pub node_security_deposit: u64,
+    /// Seconds nodes have to top up their deposit after it was found below
+    /// the node security deposit, see `Node::update_compliance`.
+    pub node_security_deposit_grace_period_secs: u64,
 }
-const_assert!(std::mem::size_of::<DepositConfig>() == 16 + 16 + 8);
+const_assert!(std::mem::size_of::<DepositConfig>() == 16 + 16 + 8 + 8);
 const_assert!(std::mem::size_of::<DepositConfig>() % 8 == 0);
 
+impl DepositConfig {
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.node_security_deposit > 0,
+            VsrError::NodeSecurityDepositMustBePositive
+        );
+        self.ordinary_deposit_min_lockup_duration.validate()?;
+        self.node_deposit_lockup_duration.validate()?;
+        Ok(())
+    }
+}
+
+/// Penalty for unlocking constant lockups early, see 'early_unlock'.
+///
+/// The penalty rate grows from 'min_penalty_bps' for lockups ending right away
+/// to 'max_penalty_bps' for lockups with 'saturation_secs' or more left,
+/// following (secs_left / saturation_secs) ^ 'curve_exponent'.
+/// The zeroed config disables early unlocks.
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+#[zero_copy]
+pub struct EarlyUnlockPenaltyConfig {
+    /// Penalty rate for lockups ending right away, in basis points.
+    pub min_penalty_bps: u16,
+    /// Penalty rate for lockups with 'saturation_secs' or more left, in basis points.
+    pub max_penalty_bps: u16,
+    /// Shape of the curve: 1 for linear, 2 for quadratic, 3 for cubic.
+    pub curve_exponent: u8,
+    pub reserved: [u8; 3],
+    /// Number of seconds left at which the maximum penalty rate is reached.
+    pub saturation_secs: u64,
+}
+const_assert!(std::mem::size_of::<EarlyUnlockPenaltyConfig>() == 2 + 2 + 1 + 3 + 8);
+const_assert!(std::mem::size_of::<EarlyUnlockPenaltyConfig>() % 8 == 0);
+
+/// The highest supported 'curve_exponent' of `EarlyUnlockPenaltyConfig`.
+pub const MAX_EARLY_UNLOCK_PENALTY_CURVE_EXPONENT: u8 = 3;
+
+/// The highest supported 'saturation_secs' of `EarlyUnlockPenaltyConfig`,
+/// which keeps `EarlyUnlockPenaltyConfig::penalty_bps` from overflowing.
+pub const MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS: u64 = 100 * SECS_PER_YEAR;
+
+impl EarlyUnlockPenaltyConfig {
+    pub fn is_enabled(&self) -> bool {
+        self.max_penalty_bps > 0
+    }
+
+    /// The penalty rate for a lockup with 'secs_left' seconds left, in basis points.
+    pub fn penalty_bps(&self, secs_left: u64) -> u64 {
+        let min_bps = self.min_penalty_bps as core::primitive::u128;
+        let max_bps = self.max_penalty_bps as core::primitive::u128;
+        let saturation_secs = self.saturation_secs as core::primitive::u128;
+        let secs_left = u64::min(secs_left, self.saturation_secs) as core::primitive::u128;
+        let exponent = self.curve_exponent as u32;
+
+        let extra_bps = (max_bps - min_bps)
+            .checked_mul(secs_left.pow(exponent))
+            .unwrap()
+            .checked_div(saturation_secs.pow(exponent))
+            .unwrap();
+        (min_bps + extra_bps) as u64
+    }
+
+    /// The penalty for unlocking 'amount_native' tokens of a lockup with
+    /// 'secs_left' seconds left.
+    pub fn penalty(&self, amount_native: u64, secs_left: u64) -> u64 {
+        (amount_native as core::primitive::u128)
+            .checked_mul(self.penalty_bps(secs_left) as core::primitive::u128)
+            .unwrap()
+            .checked_div(BPS_BASE as core::primitive::u128)
+            .unwrap() as u64
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.is_enabled() {
+            return Ok(());
+        }
+        require!(
+            self.min_penalty_bps <= self.max_penalty_bps
+                && self.max_penalty_bps as u64 <= BPS_BASE
+                && self.curve_exponent >= 1
+                && self.curve_exponent <= MAX_EARLY_UNLOCK_PENALTY_CURVE_EXPONENT
+                && self.saturation_secs > 0
+                && self.saturation_secs <= MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS,
+            VsrError::InvalidEarlyUnlockPenaltyConfig
+        );
+        Ok(())
+    }
+}
+
+/// Vesting cliffs of the deposits released by 'ordinary_release_deposit'
+/// and 'node_release_deposit'.
+///
+/// Released deposits vest linearly over the periods of their original lockup
+/// duration. Nothing vests during the first cliff periods, the vesting of those
+/// periods is deferred to the end of the cliff. Cliffs longer than the lockup
+/// duration are capped at it.
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+#[zero_copy]
+pub struct ReleaseCliffConfig {
+    /// Cliff of deposits released by 'ordinary_release_deposit', in periods.
+    pub ordinary_release_cliff_periods: u16,
+    /// Cliff of deposits released by 'node_release_deposit' and of the
+    /// remainder of slashed node deposits, in periods.
+    pub node_release_cliff_periods: u16,
+}
+const_assert!(std::mem::size_of::<ReleaseCliffConfig>() == 2 + 2);
+
+/// Emission schedule of staking rewards.
+///
+/// Every 'rotation_secs', 'emission_rate_bps' of the rewards that were not
+/// issued yet are scheduled to be emitted linearly during the next rotation.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+#[zero_copy]
+pub struct EmissionConfig {
+    /// Total amount of staking rewards.
+    pub total_reward_amount: u64,
+    /// Floor of the total reward weight to be met for full rewards.
+    pub full_reward_permanently_locked_floor: u64,
+    /// Number of seconds between two rotations of the reward amount per second.
+    pub rotation_secs: u32,
+    /// Share of the remaining rewards emitted per rotation, in basis points.
+    pub emission_rate_bps: u16,
+    pub reserved: [u8; 2],
+}
+const_assert!(std::mem::size_of::<EmissionConfig>() == 8 + 8 + 4 + 2 + 2);
+const_assert!(std::mem::size_of::<EmissionConfig>() % 8 == 0);
+
+impl Default for EmissionConfig {
+    fn default() -> Self {
+        Self {
+            total_reward_amount: TOTAL_REWARD_AMOUNT,
+            full_reward_permanently_locked_floor: FULL_REWARD_PERMANENTLY_LOCKED_FLOOR,
+            rotation_secs: SECS_PER_YEAR as u32,
+            emission_rate_bps: REWARD_EMISSION_RATE_BPS,
+            reserved: [0; 2],
+        }
+    }
+}
+
+impl EmissionConfig {
+    pub fn is_initialized(&self) -> bool {
+        self.rotation_secs != 0
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        require!(
+            self.rotation_secs > 0
+                && self.emission_rate_bps > 0
+                && self.emission_rate_bps as u64 <= BPS_BASE,
+            VsrError::InvalidEmissionConfig
+        );
+        Ok(())
+    }
+}
+
 /// Wrapper of core::primitive::u128.
 /// In order to avoid 16 bits alignment problem.
 /// See: https://solana.stackexchange.com/questions/7720/using-u128-without-sacrificing-alignment-8
@@ -295,15 +975,23 @@ impl u128 {
 #[cfg(test)]
 mod tests {
     use crate::{
-        state::registrar::{FULL_REWARD_PERMANENTLY_LOCKED_FLOOR, TOTAL_REWARD_AMOUNT},
+        state::registrar::{
+            FULL_REWARD_PERMANENTLY_LOCKED_FLOOR, MAX_EARLY_UNLOCK_PENALTY_CURVE_EXPONENT,
+            MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS, REWARD_EMISSION_RATE_BPS, TOTAL_REWARD_AMOUNT,
+        },
         EXP_SCALE, SECS_PER_DAY,
     };
     use anchor_lang::prelude::*;
     use solana_sdk::{clock::SECONDS_PER_DAY, pubkey::Pubkey, timing::SECONDS_PER_YEAR};
 
     use super::{
-        DepositConfig, u128, LockupTimeUnit, Registrar, VotingConfig, SECS_PER_YEAR,
+        DepositConfig, EarlyUnlockPenaltyConfig, EmissionConfig, u128, LockupTimeUnit,
+        MaxVoteWeightMode, Registrar, ReleaseCliffConfig, RewardBoostConfig, VoteWeightCurve, VoteWeightCurveKind,
+        VoteWeightCurvePoint, VotingConfig, BPS_BASE, PAUSE_CLAIMS, PAUSE_DEPOSITS, PAUSE_RELEASES,
+        PAUSE_WITHDRAWS, REGISTRAR_VERSION, SECS_PER_YEAR,
     };
+    use crate::error::VsrError;
+    use crate::state::registrar_stats::RegistrarStats;
 
     fn new_registrar_data() -> Registrar {
         Registrar {
@@ -315,22 +1003,26 @@ mod tests {
                 baseline_vote_weight_scaled_factor: 1,
                 max_extra_lockup_vote_weight_scaled_factor: 1,
                 lockup_saturation_secs: 1,
+                vote_weight_curve: VoteWeightCurve::default(),
             },
-            reserved1: [0; 5],
             deposit_config: DepositConfig {
                 ordinary_deposit_min_lockup_duration: crate::LockupTimeDuration {
                     periods: 1,
                     unit: crate::LockupTimeUnit::Day,
-                    filler: [0; 7]
+                    calendar_mode: 0,
+                    filler: [0; 6]
                 },
                 node_deposit_lockup_duration: crate::LockupTimeDuration {
                     periods: 1,
                     unit: LockupTimeUnit::Month,
-                    filler: [0; 7]
+                    calendar_mode: 0,
+                    filler: [0; 6]
                 },
                 node_security_deposit: 1,
+                node_security_deposit_grace_period_secs: 0,
             },
-            reserved2: [0; 5],
+            early_unlock_penalty_config: EarlyUnlockPenaltyConfig::default(),
+            reward_boost_config: RewardBoostConfig::default(),
             current_reward_amount_per_second: u128::new(0),
             last_reward_amount_per_second_rotated_ts: 0,
             reward_index: u128::new(0),
@@ -340,8 +1032,23 @@ mod tests {
             time_offset: 0,
             bump: 0,
             max_voter_weight_record_bump: 0,
-            reserved3: [0; 14],
-            reserved4: [0; 9],
+            total_reward_weight_initialized: 1,
+            has_voter_weight_action_requirements: 0,
+            release_cliff_config: ReleaseCliffConfig::default(),
+            restrict_lockup_changes: 0,
+            max_vote_weight_mode: MaxVoteWeightMode::Supply,
+            paused: 0,
+            version: REGISTRAR_VERSION,
+            funded_reward_amount_initialized: 1,
+            uncounted_voter_count_initialized: 1,
+            reserved5: [0; 2],
+            emission_config: EmissionConfig::default(),
+            funded_reward_amount: TOTAL_REWARD_AMOUNT,
+            total_reward_weight: 0,
+            active_node_count: 0,
+            uncounted_voter_count: 0,
+            total_deposited_amount: 0,
+            total_locked_amount: 0,
         }
     }
 
@@ -360,7 +1067,7 @@ mod tests {
         assert_eq!(0, registrar.reward_index.as_u128());
         assert_eq!(curr_ts, registrar.reward_accrual_ts);
         assert_eq!(0, registrar.issued_reward_amount);
-        assert_eq!(0, registrar.permanently_locked_amount);
+        assert_eq!(0, registrar.total_reward_weight);
 
         Ok(())
     }
@@ -378,20 +1085,20 @@ mod tests {
         assert_eq!(0, registrar.reward_index.as_u128());
         assert_eq!(curr_ts, registrar.reward_accrual_ts);
         assert_eq!(0, registrar.issued_reward_amount);
-        assert_eq!(0, registrar.permanently_locked_amount);
+        assert_eq!(0, registrar.total_reward_weight);
 
-        // case 2: permanently_locked_amount == 0
+        // case 2: total_reward_weight == 0
         let curr_ts = curr_ts + SECS_PER_DAY as i64;
         registrar.accrue_rewards(curr_ts);
 
         assert_eq!(0, registrar.reward_index.as_u128());
         assert_eq!(curr_ts, registrar.reward_accrual_ts);
         assert_eq!(0, registrar.issued_reward_amount);
-        assert_eq!(0, registrar.permanently_locked_amount);
+        assert_eq!(0, registrar.total_reward_weight);
 
-        // case 3:  0 < permanently_locked_amount < FULL_REWARD_PERMANENTLY_LOCKED_FLOOR
+        // case 3:  0 < total_reward_weight < FULL_REWARD_PERMANENTLY_LOCKED_FLOOR
         let curr_ts = curr_ts + SECS_PER_DAY as i64;
-        registrar.permanently_locked_amount = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR / 2;
+        registrar.total_reward_weight = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR / 2;
         registrar.accrue_rewards(curr_ts);
 
         let reward_index_delta = registrar
@@ -402,21 +1109,21 @@ mod tests {
         assert_eq!(curr_ts, registrar.reward_accrual_ts);
         assert_eq!(
             reward_index_delta
-                .mul_scalar(registrar.permanently_locked_amount as core::primitive::u128)
+                .mul_scalar(registrar.total_reward_weight as core::primitive::u128)
                 .truncate() as u64,
             registrar.issued_reward_amount
         );
 
-        // case 4:  permanently_locked_amount > FULL_REWARD_PERMANENTLY_LOCKED_FLOOR
+        // case 4:  total_reward_weight > FULL_REWARD_PERMANENTLY_LOCKED_FLOOR
         let curr_ts = curr_ts + SECS_PER_DAY as i64;
         let registrar_cloned = registrar.clone();
-        registrar.permanently_locked_amount = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR * 2;
+        registrar.total_reward_weight = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR * 2;
         registrar.accrue_rewards(curr_ts);
 
         let reward_index_delta = registrar
             .current_reward_amount_per_second
             .mul_scalar(SECS_PER_DAY as core::primitive::u128)
-            .div_scalar(registrar.permanently_locked_amount as core::primitive::u128);
+            .div_scalar(registrar.total_reward_weight as core::primitive::u128);
         assert_eq!(
             registrar_cloned.reward_index.add(reward_index_delta).as_u128(),
             registrar.reward_index.as_u128()
@@ -425,7 +1132,7 @@ mod tests {
         assert_eq!(
             registrar_cloned.issued_reward_amount
                 + reward_index_delta
-                    .mul_scalar(registrar.permanently_locked_amount as core::primitive::u128)
+                    .mul_scalar(registrar.total_reward_weight as core::primitive::u128)
                     .truncate() as u64,
             registrar.issued_reward_amount
         );
@@ -488,4 +1195,543 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn emission_config_fallback_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.emission_config = EmissionConfig {
+            total_reward_amount: 0,
+            full_reward_permanently_locked_floor: 0,
+            rotation_secs: 0,
+            emission_rate_bps: 0,
+            reserved: [0; 2],
+        };
+
+        // a zeroed config stands for the default one
+        let emission_config = registrar.emission_config();
+        assert_eq!(TOTAL_REWARD_AMOUNT, emission_config.total_reward_amount);
+        assert_eq!(
+            FULL_REWARD_PERMANENTLY_LOCKED_FLOOR,
+            emission_config.full_reward_permanently_locked_floor
+        );
+        assert_eq!(SECS_PER_YEAR, emission_config.rotation_secs as u64);
+        assert_eq!(1_200, emission_config.emission_rate_bps);
+
+        let curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(
+            (TOTAL_REWARD_AMOUNT as core::primitive::u128) * EXP_SCALE * 12 / 100 / (SECS_PER_YEAR as core::primitive::u128),
+            registrar.current_reward_amount_per_second.as_u128()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn emission_config_validate_test() -> Result<()> {
+        let mut emission_config = EmissionConfig::default();
+        assert!(emission_config.validate().is_ok());
+
+        emission_config.rotation_secs = 0;
+        assert!(emission_config.validate().is_err());
+
+        emission_config.rotation_secs = 1;
+        emission_config.emission_rate_bps = 0;
+        assert!(emission_config.validate().is_err());
+
+        emission_config.emission_rate_bps = 10_001;
+        assert!(emission_config.validate().is_err());
+
+        emission_config.emission_rate_bps = 10_000;
+        assert!(emission_config.validate().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn accrue_rewards_custom_emission_config_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        let total_reward_amount: u64 = 1_000_000_000_000;
+        let full_reward_permanently_locked_floor: u64 = 100_000_000_000;
+        let rotation_secs: u64 = 30 * SECONDS_PER_DAY;
+        registrar.emission_config = EmissionConfig {
+            total_reward_amount,
+            full_reward_permanently_locked_floor,
+            rotation_secs: rotation_secs as u32,
+            emission_rate_bps: 500,
+            reserved: [0; 2],
+        };
+
+        // initialize
+        let mut curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(
+            (total_reward_amount as core::primitive::u128) * EXP_SCALE * 5 / 100 / (rotation_secs as core::primitive::u128),
+            registrar.current_reward_amount_per_second.as_u128()
+        );
+
+        // the floor of the config is used
+        registrar.total_reward_weight = full_reward_permanently_locked_floor / 2;
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        let reward_index_delta = registrar
+            .current_reward_amount_per_second
+            .mul_scalar(SECS_PER_DAY as core::primitive::u128)
+            .div_scalar(full_reward_permanently_locked_floor as core::primitive::u128);
+        assert_eq!(reward_index_delta.as_u128(), registrar.reward_index.as_u128());
+
+        // rotated after rotation_secs
+        curr_ts += (rotation_secs - SECS_PER_DAY) as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(curr_ts, registrar.last_reward_amount_per_second_rotated_ts);
+        assert_eq!(
+            ((total_reward_amount - registrar.issued_reward_amount) * 5 / 100) as core::primitive::u128
+                * EXP_SCALE
+                / (rotation_secs as core::primitive::u128),
+            registrar.current_reward_amount_per_second.as_u128()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn accrue_rewards_funded_cap_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.funded_reward_amount = 0;
+
+        // initialize
+        let mut curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        registrar.total_reward_weight = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR;
+        assert_eq!(Some(0), registrar.reward_runway_secs());
+
+        // nothing is issued without funds
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(0, registrar.reward_index.as_u128());
+        assert_eq!(0, registrar.issued_reward_amount);
+        assert_eq!(curr_ts, registrar.reward_accrual_ts);
+
+        // fund rewards for about half a day
+        let issued_reward_amount_per_day = registrar
+            .current_reward_amount_per_second
+            .mul_scalar(SECS_PER_DAY as core::primitive::u128)
+            .truncate() as u64;
+        registrar.funded_reward_amount = issued_reward_amount_per_day / 2;
+        let runway_secs = registrar.reward_runway_secs().unwrap();
+        assert!((SECS_PER_DAY / 2 - 1..=SECS_PER_DAY / 2).contains(&runway_secs));
+
+        // less than funded is issued normally
+        curr_ts += (SECS_PER_DAY / 4) as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert!(registrar.issued_reward_amount < registrar.funded_reward_amount);
+        assert!(registrar.reward_index.as_u128() > 0);
+
+        // issuance stops at the funded amount
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(registrar.funded_reward_amount, registrar.issued_reward_amount);
+        assert!(
+            registrar
+                .reward_index
+                .mul_scalar(registrar.total_reward_weight as core::primitive::u128)
+                .truncate() as u64
+                <= registrar.funded_reward_amount
+        );
+        assert_eq!(0, registrar.unissued_funded_reward_amount());
+        assert_eq!(Some(0), registrar.reward_runway_secs());
+
+        let reward_index = registrar.reward_index;
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(reward_index.as_u128(), registrar.reward_index.as_u128());
+        assert_eq!(registrar.funded_reward_amount, registrar.issued_reward_amount);
+
+        Ok(())
+    }
+
+    #[test]
+    fn funded_reward_amount_legacy_initialize_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.version = 0;
+        registrar.funded_reward_amount = 0;
+        registrar.funded_reward_amount_initialized = 0;
+        registrar.issued_reward_amount = 1_000;
+
+        // no rewards are issued before the funded reward amount is known
+        let mut curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        registrar.total_reward_weight = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR;
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(0, registrar.funded_reward_amount_initialized);
+        assert_eq!(1_000, registrar.issued_reward_amount);
+
+        // 300 of the issued rewards were claimed since the stats exist, the
+        // other 700 may still be owed to voters
+        let mut stats: RegistrarStats = bytemuck::Zeroable::zeroed();
+        stats.add_claimed_reward(300);
+        registrar.migrate(10_000, &stats)?;
+        assert_eq!(1, registrar.funded_reward_amount_initialized);
+        assert_eq!(1_000 + 10_000 - 700, registrar.funded_reward_amount);
+        assert_eq!(10_000 - 700, registrar.unissued_funded_reward_amount());
+
+        // rewards are issued from the vault balance that is not owed
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(registrar.funded_reward_amount, registrar.issued_reward_amount);
+
+        // a vault holding less than the unclaimed rewards funds nothing
+        let mut registrar = new_registrar_data();
+        registrar.version = 0;
+        registrar.funded_reward_amount_initialized = 0;
+        registrar.issued_reward_amount = 1_000;
+        registrar.migrate(500, &stats)?;
+        assert_eq!(1_000, registrar.funded_reward_amount);
+        assert_eq!(0, registrar.unissued_funded_reward_amount());
+
+        Ok(())
+    }
+
+    #[test]
+    fn total_reward_weight_legacy_initialize_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.total_reward_weight_initialized = 0;
+        registrar.permanently_locked_amount = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR;
+
+        // legacy registrars start from their permanently locked amount
+        let mut curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(1, registrar.total_reward_weight_initialized);
+        assert_eq!(FULL_REWARD_PERMANENTLY_LOCKED_FLOOR, registrar.total_reward_weight);
+
+        // only once
+        registrar.total_reward_weight = FULL_REWARD_PERMANENTLY_LOCKED_FLOOR * 2;
+        curr_ts += SECS_PER_DAY as i64;
+        registrar.accrue_rewards(curr_ts);
+        assert_eq!(FULL_REWARD_PERMANENTLY_LOCKED_FLOOR * 2, registrar.total_reward_weight);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reward_boost_config_validate_test() -> Result<()> {
+        let mut reward_boost_config = RewardBoostConfig::default();
+        assert!(reward_boost_config.validate().is_ok());
+
+        reward_boost_config.max_extra_lockup_reward_weight_scaled_factor = 1;
+        assert!(reward_boost_config.validate().is_err());
+
+        reward_boost_config.lockup_saturation_secs = 1;
+        assert!(reward_boost_config.validate().is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_deposit_totals_complete_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.assert_deposit_totals_complete()?;
+
+        // legacy registrars need the number of uncounted voters first
+        registrar.uncounted_voter_count_initialized = 0;
+        assert_eq!(
+            registrar.assert_deposit_totals_complete(),
+            Err(error!(VsrError::UncountedVoters))
+        );
+
+        registrar.uncounted_voter_count_initialized = 1;
+        registrar.uncounted_voter_count = 2;
+        registrar.count_voter_deposits();
+        assert_eq!(
+            registrar.assert_deposit_totals_complete(),
+            Err(error!(VsrError::UncountedVoters))
+        );
+        registrar.count_voter_deposits();
+        registrar.assert_deposit_totals_complete()?;
+
+        // saturates for voters counted before the number was set
+        registrar.count_voter_deposits();
+        assert_eq!(registrar.uncounted_voter_count, 0);
+        Ok(())
+    }
+
+    #[test]
+    fn add_penalty_reward_amount_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        let curr_ts = SECONDS_PER_YEAR as i64;
+        registrar.accrue_rewards(curr_ts);
+        registrar.issued_reward_amount = TOTAL_REWARD_AMOUNT / 10;
+        let mut penalized_registrar = registrar;
+
+        let penalty = TOTAL_REWARD_AMOUNT / 100;
+        penalized_registrar.add_penalty_reward_amount(penalty);
+        assert_eq!(TOTAL_REWARD_AMOUNT + penalty, penalized_registrar.funded_reward_amount);
+        assert_eq!(
+            TOTAL_REWARD_AMOUNT + penalty,
+            penalized_registrar.emission_config.total_reward_amount
+        );
+
+        // the next rotation emits a share of the penalty as well
+        let rotation_ts = curr_ts + SECONDS_PER_YEAR as i64;
+        registrar.rotate_reward_amount_per_second(rotation_ts);
+        penalized_registrar.rotate_reward_amount_per_second(rotation_ts);
+        assert!(
+            penalized_registrar.current_reward_amount_per_second.as_u128()
+                > registrar.current_reward_amount_per_second.as_u128()
+        );
+        assert_eq!(
+            penalized_registrar.current_reward_amount_per_second.as_u128(),
+            u128::new_with_denom(
+                ((TOTAL_REWARD_AMOUNT - TOTAL_REWARD_AMOUNT / 10 + penalty) as core::primitive::u128)
+                    * REWARD_EMISSION_RATE_BPS as core::primitive::u128
+                    / BPS_BASE as core::primitive::u128,
+                SECS_PER_YEAR as core::primitive::u128
+            )
+            .as_u128()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn early_unlock_penalty_config_test() -> Result<()> {
+        let mut config = EarlyUnlockPenaltyConfig::default();
+        assert!(!config.is_enabled());
+        assert!(config.validate().is_ok());
+
+        config.min_penalty_bps = 1_000;
+        config.max_penalty_bps = 5_000;
+        assert!(config.validate().is_err());
+        config.curve_exponent = 1;
+        assert!(config.validate().is_err());
+        config.saturation_secs = SECS_PER_YEAR;
+        assert!(config.validate().is_ok());
+
+        // linear
+        assert_eq!(config.penalty_bps(0), 1_000);
+        assert_eq!(config.penalty_bps(SECS_PER_YEAR / 2), 3_000);
+        assert_eq!(config.penalty_bps(SECS_PER_YEAR), 5_000);
+        assert_eq!(config.penalty_bps(SECS_PER_YEAR * 2), 5_000);
+        assert_eq!(config.penalty(1_000_000, SECS_PER_YEAR / 2), 300_000);
+
+        // quadratic
+        config.curve_exponent = 2;
+        assert_eq!(config.penalty_bps(0), 1_000);
+        assert_eq!(config.penalty_bps(SECS_PER_YEAR / 2), 2_000);
+        assert_eq!(config.penalty_bps(SECS_PER_YEAR), 5_000);
+
+        config.curve_exponent = 4;
+        assert!(config.validate().is_err());
+        config.curve_exponent = 1;
+        config.min_penalty_bps = 6_000;
+        assert!(config.validate().is_err());
+        config.max_penalty_bps = 10_001;
+        assert!(config.validate().is_err());
+
+        // the longest saturation does not overflow with the steepest curve
+        config.max_penalty_bps = 10_000;
+        config.min_penalty_bps = 0;
+        config.curve_exponent = MAX_EARLY_UNLOCK_PENALTY_CURVE_EXPONENT;
+        config.saturation_secs = MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS;
+        assert!(config.validate().is_ok());
+        assert_eq!(config.penalty_bps(MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS), 10_000);
+        config.saturation_secs = MAX_EARLY_UNLOCK_PENALTY_SATURATION_SECS + 1;
+        assert!(config.validate().is_err());
+
+        Ok(())
+    }
+
+    fn new_vote_weight_curve(points: &[(u16, u16)]) -> VoteWeightCurve {
+        let mut curve = VoteWeightCurve {
+            kind: VoteWeightCurveKind::PiecewiseLinear,
+            point_count: points.len() as u8,
+            ..VoteWeightCurve::default()
+        };
+        for (i, (lockup_bps, weight_bps)) in points.iter().enumerate() {
+            curve.points[i] = VoteWeightCurvePoint {
+                lockup_bps: *lockup_bps,
+                weight_bps: *weight_bps,
+            };
+        }
+        curve
+    }
+
+    #[test]
+    fn vote_weight_curve_validate_test() -> Result<()> {
+        assert!(VoteWeightCurve::default().validate().is_ok());
+        assert!(new_vote_weight_curve(&[]).validate().is_ok());
+        assert!(new_vote_weight_curve(&[(2_500, 5_000), (5_000, 7_500)])
+            .validate()
+            .is_ok());
+        // flat segments are fine
+        assert!(new_vote_weight_curve(&[(2_500, 5_000), (5_000, 5_000)])
+            .validate()
+            .is_ok());
+
+        // the linear curve has no points
+        let mut curve = new_vote_weight_curve(&[(5_000, 5_000)]);
+        curve.kind = VoteWeightCurveKind::Linear;
+        assert!(curve.validate().is_err());
+        // lockup_bps must increase
+        assert!(new_vote_weight_curve(&[(5_000, 5_000), (5_000, 6_000)])
+            .validate()
+            .is_err());
+        assert!(new_vote_weight_curve(&[(0, 5_000)]).validate().is_err());
+        assert!(new_vote_weight_curve(&[(10_000, 5_000)]).validate().is_err());
+        // weight_bps must not decrease
+        assert!(new_vote_weight_curve(&[(2_500, 6_000), (5_000, 5_000)])
+            .validate()
+            .is_err());
+        assert!(new_vote_weight_curve(&[(5_000, 10_001)]).validate().is_err());
+        // unused points must be zeroed
+        let mut curve = new_vote_weight_curve(&[(5_000, 5_000)]);
+        curve.points[1].lockup_bps = 6_000;
+        assert!(curve.validate().is_err());
+        let mut curve = new_vote_weight_curve(&[]);
+        curve.point_count = 9;
+        assert!(curve.validate().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn vote_weight_curve_weight_bps_test() -> Result<()> {
+        let sat = SECS_PER_YEAR;
+        let linear = VoteWeightCurve::default();
+        assert_eq!(linear.weight_bps(0, sat), 0);
+        assert_eq!(linear.weight_bps(sat / 4, sat), 2_500);
+        assert_eq!(linear.weight_bps(sat, sat), BPS_BASE);
+        assert_eq!(linear.weight_bps(2 * sat, sat), BPS_BASE);
+
+        // without points, the piecewise-linear curve is linear too
+        let curve = new_vote_weight_curve(&[]);
+        assert_eq!(curve.weight_bps(sat / 4, sat), 2_500);
+
+        // steep boost in the first quarter
+        let curve = new_vote_weight_curve(&[(2_500, 5_000)]);
+        assert_eq!(curve.weight_bps(0, sat), 0);
+        assert_eq!(curve.weight_bps(sat / 8, sat), 2_500);
+        assert_eq!(curve.weight_bps(sat / 4, sat), 5_000);
+        assert_eq!(curve.weight_bps(sat / 4 + sat * 3 / 8, sat), 7_500);
+        assert_eq!(curve.weight_bps(sat - 1, sat), 9_999);
+        assert_eq!(curve.weight_bps(sat, sat), BPS_BASE);
+        assert_eq!(curve.weight_bps(2 * sat, sat), BPS_BASE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn max_vote_weight_for_test() -> Result<()> {
+        let registrar = new_registrar_data();
+        let config = registrar.voting_config;
+
+        assert_eq!(registrar.max_vote_weight_for(0, 0)?, 0);
+        assert_eq!(
+            registrar.max_vote_weight_for(1_000, 1_000)?,
+            config.baseline_vote_weight(1_000)? + config.max_extra_lockup_vote_weight(1_000)?
+        );
+        assert_eq!(
+            registrar.max_vote_weight_for(1_000, 400)?,
+            config.baseline_vote_weight(1_000)? + config.max_extra_lockup_vote_weight(400)?
+        );
+        // no more tokens are locked than deposited
+        assert_eq!(
+            registrar.max_vote_weight_for(1_000, 2_000)?,
+            registrar.max_vote_weight_for(1_000, 1_000)?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn vote_weight_curve_sum_weight_bps_test() -> Result<()> {
+        let sat = SECS_PER_YEAR;
+        let curves = [
+            VoteWeightCurve::default(),
+            new_vote_weight_curve(&[(2_500, 5_000)]),
+            new_vote_weight_curve(&[(1_000, 1_000), (2_000, 6_000), (8_000, 6_000)]),
+        ];
+        for curve in curves.iter() {
+            for (first_secs, count, period_secs) in [
+                (0, 1, SECS_PER_DAY),
+                (1_000, 400, SECS_PER_DAY),
+                (SECS_PER_DAY / 3, 13, 30 * SECS_PER_DAY),
+                (sat / 2, 10, 7 * SECS_PER_DAY),
+                (sat, 5, SECS_PER_DAY),
+                (0, 0, SECS_PER_DAY),
+            ] {
+                let expected: core::primitive::u128 = (0..count)
+                    .map(|k| curve.weight_bps(first_secs + k * period_secs, sat) as core::primitive::u128)
+                    .sum();
+                let sum = curve.sum_weight_bps(first_secs, count, period_secs, sat);
+                // rounding down per segment instead of per lockup
+                assert!(sum >= expected && sum <= expected + 4 + count as core::primitive::u128);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn assert_not_paused_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        for flag in [PAUSE_DEPOSITS, PAUSE_RELEASES, PAUSE_WITHDRAWS, PAUSE_CLAIMS] {
+            registrar.assert_not_paused(flag)?;
+        }
+
+        registrar.paused = PAUSE_RELEASES | PAUSE_CLAIMS;
+        registrar.assert_not_paused(PAUSE_DEPOSITS)?;
+        registrar.assert_not_paused(PAUSE_WITHDRAWS)?;
+        assert_eq!(
+            registrar.assert_not_paused(PAUSE_RELEASES),
+            Err(error!(VsrError::ReleasesPaused))
+        );
+        assert_eq!(
+            registrar.assert_not_paused(PAUSE_CLAIMS),
+            Err(error!(VsrError::ClaimsPaused))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v0_to_v1_test() -> Result<()> {
+        let mut registrar = new_registrar_data();
+        registrar.version = 0;
+        registrar.emission_config = bytemuck::Zeroable::zeroed();
+        registrar.total_reward_weight_initialized = 0;
+        registrar.permanently_locked_amount = 1_000;
+        registrar.funded_reward_amount_initialized = 0;
+        registrar.funded_reward_amount = 0;
+        let stats: RegistrarStats = bytemuck::Zeroable::zeroed();
+
+        registrar.migrate(5_000, &stats)?;
+        assert_eq!(REGISTRAR_VERSION, registrar.version);
+        assert!(registrar.emission_config.is_initialized());
+        assert_eq!(
+            EmissionConfig::default().total_reward_amount,
+            registrar.emission_config.total_reward_amount
+        );
+        assert_eq!(1, registrar.total_reward_weight_initialized);
+        assert_eq!(1_000, registrar.total_reward_weight);
+        assert_eq!(1, registrar.funded_reward_amount_initialized);
+        assert_eq!(5_000, registrar.funded_reward_amount);
+
+        // migrating again has no effect
+        registrar.total_reward_weight = 2_000;
+        registrar.funded_reward_amount += 100;
+        registrar.migrate(10_000, &stats)?;
+        assert_eq!(2_000, registrar.total_reward_weight);
+        assert_eq!(5_100, registrar.funded_reward_amount);
+
+        // versions of newer programs are rejected
+        registrar.version = REGISTRAR_VERSION + 1;
+        assert_eq!(
+            registrar.migrate(5_000, &stats),
+            Err(error!(VsrError::UnsupportedLayoutVersion))
+        );
+
+        Ok(())
+    }
 }
@@ -0,0 +1,146 @@
+use crate::error::*;
+use crate::state::deposit_entry::DepositEntry;
+use crate::state::registrar::Registrar;
+use crate::state::voter::{LoadedVoter, Voter};
+use crate::NODE_DEPOSIT_ENTRY_INDEX;
+use anchor_lang::prelude::*;
+use std::ops::Deref;
+
+/// The count of `VoterWeightAction`s
+pub const VOTER_WEIGHT_ACTION_COUNT: usize = 5;
+
+/// Registrar settings that do not fit into the registrar account.
+///
+/// There can only be a single registrar config per registrar, it is created
+/// by the realm authority on first use.
+#[account(zero_copy)]
+pub struct RegistrarConfig {
+    pub registrar: Pubkey,
+    /// Key that may slash node deposits besides the realm authority,
+    /// Pubkey::default() if there is none
+    pub slasher: Pubkey,
+
+    pub bump: u8,
+    pub reserved1: [u8; 7],
+
+    /// Requirements a voter must meet to get a voter weight record for an
+    /// action, indexed by `VoterWeightAction`. Zeroed for no requirements.
+    pub voter_weight_action_requirements: [VoterWeightActionRequirement; VOTER_WEIGHT_ACTION_COUNT],
+    /// Key that may pause the registrar besides the realm authority,
+    /// Pubkey::default() if there is none. See 'Registrar::paused'.
+    pub guardian: Pubkey,
+    /// Upper bound of the voting time plus the cool off time of the realm's
+    /// governances, zero if unknown. See 'request_undelegate'.
+    pub max_voting_time_secs: u32,
+    pub reserved3: [u8; 4],
+    pub reserved2: [u64; 1],
+}
+const_assert!(
+    std::mem::size_of::<RegistrarConfig>()
+        == 2 * 32 + 1 + 7 + VOTER_WEIGHT_ACTION_COUNT * 16 + 32 + 4 + 4 + 8
+);
+const_assert!(std::mem::size_of::<RegistrarConfig>() % 8 == 0);
+
+impl RegistrarConfig {
+    pub fn is_slasher(&self, key: &Pubkey) -> bool {
+        self.slasher != Pubkey::default() && self.slasher == *key
+    }
+
+    pub fn is_guardian(&self, key: &Pubkey) -> bool {
+        self.guardian != Pubkey::default() && self.guardian == *key
+    }
+
+    #[inline(always)]
+    pub fn voter_weight_action_requirement(
+        &self,
+        action: VoterWeightAction,
+    ) -> &VoterWeightActionRequirement {
+        &self.voter_weight_action_requirements[action as usize]
+    }
+
+    /// True if any action has requirements
+    pub fn has_voter_weight_action_requirements(&self) -> bool {
+        self.voter_weight_action_requirements
+            .iter()
+            .any(|r| !r.is_empty())
+    }
+}
+
+/// The action a voter weight record is valid for, mirroring the
+/// `VoterWeightAction` of spl-governance.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Debug)]
+pub enum VoterWeightAction {
+    CastVote,
+    CommentProposal,
+    CreateGovernance,
+    CreateProposal,
+    SignOffProposal,
+}
+
+impl From<VoterWeightAction> for spl_governance_addin_api::voter_weight::VoterWeightAction {
+    fn from(action: VoterWeightAction) -> Self {
+        use spl_governance_addin_api::voter_weight::VoterWeightAction as GovAction;
+        match action {
+            VoterWeightAction::CastVote => GovAction::CastVote,
+            VoterWeightAction::CommentProposal => GovAction::CommentProposal,
+            VoterWeightAction::CreateGovernance => GovAction::CreateGovernance,
+            VoterWeightAction::CreateProposal => GovAction::CreateProposal,
+            VoterWeightAction::SignOffProposal => GovAction::SignOffProposal,
+        }
+    }
+}
+
+/// Minimums a voter must meet to get a voter weight record for an action,
+/// for example an active node deposit to create proposals.
+///
+/// Only the voter's own deposits count, not the vote weight delegated to it.
+#[derive(AnchorSerialize, AnchorDeserialize, Default)]
+#[zero_copy]
+pub struct VoterWeightActionRequirement {
+    /// Minimum vote weight of the deposits locked for at least
+    /// 'min_lockup_secs'.
+    pub min_voter_weight: u64,
+    /// Minimum remaining lockup time of the deposits counting towards
+    /// 'min_voter_weight'. If set, at least one such deposit is needed.
+    pub min_lockup_secs: u32,
+    /// True if the voter must have an active node deposit.
+    pub require_node_deposit: u8,
+    pub reserved: [u8; 3],
+}
+const_assert!(std::mem::size_of::<VoterWeightActionRequirement>() == 8 + 4 + 1 + 3);
+
+impl VoterWeightActionRequirement {
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.min_voter_weight == 0 && self.min_lockup_secs == 0 && self.require_node_deposit == 0
+    }
+
+    /// Fails with VoterWeightActionRequirementNotMet unless the voter meets
+    /// the requirement.
+    pub fn check<V, D>(
+        &self,
+        voter: &LoadedVoter<V, D>,
+        curr_ts: i64,
+        registrar: &Registrar,
+    ) -> Result<()>
+    where
+        V: Deref<Target = Voter>,
+        D: Deref<Target = [DepositEntry]>,
+    {
+        if self.require_node_deposit != 0 {
+            require!(
+                voter.is_active(NODE_DEPOSIT_ENTRY_INDEX)?,
+                VsrError::VoterWeightActionRequirementNotMet
+            );
+        }
+
+        if self.min_voter_weight > 0 || self.min_lockup_secs > 0 {
+            let weight = voter.weight_locked_for(self.min_lockup_secs as u64, curr_ts, registrar)?;
+            require!(
+                weight >= self.min_voter_weight && (self.min_lockup_secs == 0 || weight > 0),
+                VsrError::VoterWeightActionRequirementNotMet
+            );
+        }
+        Ok(())
+    }
+}
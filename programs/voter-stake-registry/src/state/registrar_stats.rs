@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use std::cell::RefMut;
+
+/// Registrar-wide statistics that do not fit into the registrar account.
+///
+/// The registrar itself tracks the deposited, locked and permanently locked
+/// amounts, the issued rewards and the active nodes.
+///
+/// There can only be a single one per registrar, it is created by the first
+/// 'create_voter' or 'grant', or by 'create_registrar_stats'. Registrars
+/// created before the stats were introduced only count claimed rewards from
+/// then on, and voters once they are created or migrated, see
+/// `Voter::count_in_stats_if_needed`.
+#[account(zero_copy)]
+pub struct RegistrarStats {
+    pub registrar: Pubkey,
+
+    pub bump: u8,
+    pub reserved1: [u8; 7],
+
+    /// Number of voters that were created and not closed yet
+    pub voter_count: u64,
+
+    /// Total amount of rewards claimed or compounded by voters
+    pub claimed_reward_amount: u64,
+
+    pub reserved2: [u64; 8],
+}
+const_assert!(std::mem::size_of::<RegistrarStats>() == 32 + 1 + 7 + 8 + 8 + 8 * 8);
+const_assert!(std::mem::size_of::<RegistrarStats>() % 8 == 0);
+
+impl RegistrarStats {
+    /// Loads the stats, initializing them if they were just created by
+    /// `init_if_needed`.
+    pub fn load_init_if_needed<'a>(
+        loader: &'a AccountLoader<RegistrarStats>,
+        registrar: Pubkey,
+        bump: u8,
+    ) -> Result<RefMut<'a, RegistrarStats>> {
+        match loader.load_init() {
+            Ok(mut stats) => {
+                stats.registrar = registrar;
+                stats.bump = bump;
+                Ok(stats)
+            }
+            Err(_) => loader.load_mut(),
+        }
+    }
+
+    pub fn add_voter(&mut self) {
+        self.voter_count = self.voter_count.checked_add(1).unwrap();
+    }
+
+    pub fn remove_voter(&mut self) {
+        self.voter_count = self.voter_count.checked_sub(1).unwrap();
+    }
+
+    pub fn add_claimed_reward(&mut self, amount: u64) {
+        self.claimed_reward_amount = self.claimed_reward_amount.checked_add(amount).unwrap();
+    }
+
+    /// Upper bound of the issued rewards that were not claimed yet, given the
+    /// issued amount of the registrar. Rewards claimed before the stats were
+    /// created are not deducted.
+    pub fn max_unclaimed_reward_amount(&self, issued_reward_amount: u64) -> u64 {
+        issued_reward_amount.saturating_sub(self.claimed_reward_amount)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::voter::Voter;
+    use crate::u128;
+    use bytemuck::Zeroable;
+
+    #[test]
+    fn registrar_stats_test() {
+        let mut stats: RegistrarStats = Zeroable::zeroed();
+        stats.add_voter();
+        stats.add_voter();
+        stats.remove_voter();
+        assert_eq!(stats.voter_count, 1);
+        stats.remove_voter();
+        assert_eq!(stats.voter_count, 0);
+
+        stats.add_claimed_reward(100);
+        stats.add_claimed_reward(50);
+        assert_eq!(stats.claimed_reward_amount, 150);
+        assert_eq!(stats.max_unclaimed_reward_amount(1_000), 850);
+        // rewards claimed before the stats are not counted
+        assert_eq!(stats.max_unclaimed_reward_amount(100), 0);
+    }
+
+    #[test]
+    fn count_voter_in_stats_test() {
+        let mut stats: RegistrarStats = Zeroable::zeroed();
+        let mut voter = Voter::new(Pubkey::new_unique(), Pubkey::new_unique(), u128::new(0), 0, 0);
+        assert!(!voter.is_counted_in_stats());
+
+        // voters are only counted once, e.g. when migrated again
+        voter.count_in_stats_if_needed(&mut stats);
+        voter.count_in_stats_if_needed(&mut stats);
+        assert!(voter.is_counted_in_stats());
+        assert_eq!(stats.voter_count, 1);
+    }
+}
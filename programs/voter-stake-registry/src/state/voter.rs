@@ -1,11 +1,22 @@
 use crate::state::deposit_entry::DepositEntry;
 use crate::state::registrar::Registrar;
+use crate::state::registrar_stats::RegistrarStats;
 use crate::{error::*, u128, Lockup};
 use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
+use std::cmp::min;
+use std::ops::{Deref, DerefMut};
 
-/// The count of deposit entries per voter
+/// The count of deposit entries stored in the voter itself
 pub const VOTER_DEPOSIT_ENTRY_COUNT: usize = 16;
 
+/// The maximum count of deposit entries per voter, see `resize_voter`
+pub const MAX_VOTER_DEPOSIT_ENTRY_COUNT: usize = 64;
+
+/// Layout version of voters created by this program, see
+/// `LoadedVoter::migrate`
+pub const VOTER_VERSION: u8 = 1;
+
 /// User account for minting voting rights.
 #[account(zero_copy)]
 pub struct Voter {
@@ -20,10 +31,47 @@ pub struct Voter {
 
     voter_bump: u8,
     voter_weight_record_bump: u8,
-    reserved1: [u8; 6],
-    reserved2: [u64; 8],
+
+    /// True if the vote weight is delegated to another voter
+    delegated: u8,
+
+    /// True once 'reward_weight' is tracked, voters created before reward
+    /// weights were introduced have it unset.
+    reward_weight_initialized: u8,
+
+    /// True once the deposits are counted in the registrar's deposit totals,
+    /// voters created before they were introduced have it unset.
+    deposit_totals_initialized: u8,
+
+    /// Layout version, zero for voters created before it was introduced.
+    /// 'migrate_voter' brings it up to `VOTER_VERSION`.
+    version: u8,
+
+    /// True once 'create_voter_weight_history' created the voter's vote
+    /// weight history, which is required by balance-changing instructions
+    /// from then on.
+    has_weight_history: u8,
+
+    /// True once the voter is counted in the registrar stats' voter count,
+    /// voters created before the stats were introduced have it unset until
+    /// 'migrate_voter'.
+    counted_in_stats: u8,
+
+    /// Reward weight as of the most recent balance-changing action,
+    /// which is what the voter contributes to the registrar's total reward weight
+    reward_weight: u64,
+
+    /// Number of voters that delegated their vote weight to this voter
+    delegator_count: u64,
+
+    /// Time 'reward_index' was last brought up to date, rewards since then
+    /// are accrued with the average reward weight. Zero if it was not yet
+    /// tracked.
+    reward_accrual_ts: i64,
+
+    reserved2: [u64; 5],
 }
-const_assert!(std::mem::size_of::<Voter>() == 2 * 32 + VOTER_DEPOSIT_ENTRY_COUNT * 88 + 16 + 8 + 1 + 1 + 6 + 64);
+const_assert!(std::mem::size_of::<Voter>() == 2 * 32 + VOTER_DEPOSIT_ENTRY_COUNT * 88 + 16 + 8 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 1 + 8 + 8 + 8 + 40);
 const_assert!(std::mem::size_of::<Voter>() % 8 == 0);
 
 /// impl: factory function and getters
@@ -43,8 +91,16 @@ impl Voter {
             reward_claimable_amount: 0,
             voter_bump,
             voter_weight_record_bump,
-            reserved1: [0; 6],
-            reserved2: [0; 8],
+            delegated: 0,
+            reward_weight_initialized: 1,
+            deposit_totals_initialized: 1,
+            version: VOTER_VERSION,
+            has_weight_history: 0,
+            counted_in_stats: 0,
+            reward_weight: 0,
+            delegator_count: 0,
+            reward_accrual_ts: 0,
+            reserved2: [0; 5],
         }
     }
 
@@ -58,11 +114,6 @@ impl Voter {
         self.registrar
     }
 
-    #[inline(always)]
-    pub fn get_deposits(&self) -> &[DepositEntry] {
-        &self.deposits
-    }
-
     #[inline(always)]
     pub fn get_reward_index(&self) -> u128 {
         self.reward_index
@@ -82,17 +133,146 @@ impl Voter {
     pub fn get_voter_weight_record_bump(&self) -> u8 {
         self.voter_weight_record_bump
     }
+
+    #[inline(always)]
+    pub fn get_reward_weight(&self) -> u64 {
+        self.reward_weight
+    }
+
+    #[inline(always)]
+    pub fn get_version(&self) -> u8 {
+        self.version
+    }
+
+    #[inline(always)]
+    pub fn get_delegator_count(&self) -> u64 {
+        self.delegator_count
+    }
+
+    #[inline(always)]
+    pub fn is_delegated(&self) -> bool {
+        self.delegated != 0
+    }
+
+    #[inline(always)]
+    pub fn has_weight_history(&self) -> bool {
+        self.has_weight_history != 0
+    }
+
+    #[inline(always)]
+    pub fn is_counted_in_stats(&self) -> bool {
+        self.counted_in_stats != 0
+    }
 }
 
 impl Voter {
+    /// The size of a voter account holding `deposit_entry_count` deposit entries
+    pub fn account_size(deposit_entry_count: usize) -> usize {
+        8 + std::mem::size_of::<Voter>()
+            + deposit_entry_count.saturating_sub(VOTER_DEPOSIT_ENTRY_COUNT)
+                * std::mem::size_of::<DepositEntry>()
+    }
+
+    pub fn delegate(&mut self) -> Result<()> {
+        require!(!self.is_delegated(), VsrError::VoterDelegated);
+        self.delegated = 1;
+        Ok(())
+    }
+
+    pub fn undelegate(&mut self) -> Result<()> {
+        require!(self.is_delegated(), VsrError::VoterNotDelegated);
+        self.delegated = 0;
+        Ok(())
+    }
+
+    pub fn set_has_weight_history(&mut self) {
+        self.has_weight_history = 1;
+    }
+
+    /// Counts a voter delegating to this voter.
+    pub fn add_delegator(&mut self) {
+        self.delegator_count = self.delegator_count.checked_add(1).unwrap();
+    }
+
+    /// Uncounts a voter undelegating from this voter.
+    pub fn remove_delegator(&mut self) {
+        self.delegator_count = self.delegator_count.checked_sub(1).unwrap();
+    }
+
+    /// Counts the voter in the registrar stats, unless it already is.
+    pub fn count_in_stats_if_needed(&mut self, stats: &mut RegistrarStats) {
+        if self.counted_in_stats == 0 {
+            stats.add_voter();
+            self.counted_in_stats = 1;
+        }
+    }
+}
+
+/// A voter together with the extra deposit entries 'resize_voter' appended
+/// to its account.
+///
+/// Deposit entries are indexed across both, the VOTER_DEPOSIT_ENTRY_COUNT
+/// entries of the `Voter` come first. Voter accounts that were never resized
+/// simply have no extra deposit entries.
+pub struct LoadedVoter<V, D> {
+    voter: V,
+    extra_deposits: D,
+}
+
+pub type VoterRef<'a> = LoadedVoter<Ref<'a, Voter>, Ref<'a, [DepositEntry]>>;
+pub type VoterRefMut<'a> = LoadedVoter<RefMut<'a, Voter>, RefMut<'a, [DepositEntry]>>;
+
+impl<V, D> LoadedVoter<V, D> {
+    pub fn new(voter: V, extra_deposits: D) -> Self {
+        LoadedVoter {
+            voter,
+            extra_deposits,
+        }
+    }
+}
+
+impl<V: Deref<Target = Voter>, D> Deref for LoadedVoter<V, D> {
+    type Target = Voter;
+
+    fn deref(&self) -> &Voter {
+        &self.voter
+    }
+}
+
+impl<V: DerefMut<Target = Voter>, D> DerefMut for LoadedVoter<V, D> {
+    fn deref_mut(&mut self) -> &mut Voter {
+        &mut self.voter
+    }
+}
+
+impl<V, D> LoadedVoter<V, D>
+where
+    V: Deref<Target = Voter>,
+    D: Deref<Target = [DepositEntry]>,
+{
+    /// The number of deposit entries of the voter
+    #[inline]
+    pub fn deposit_entry_count(&self) -> usize {
+        VOTER_DEPOSIT_ENTRY_COUNT + self.extra_deposits.len()
+    }
+
+    /// All deposit entries of the voter, active or not
+    pub fn deposits(&self) -> impl Iterator<Item = &DepositEntry> {
+        self.voter.deposits.iter().chain(self.extra_deposits.iter())
+    }
+
     pub fn deposit_entry_at(&self, index: u8) -> Result<&DepositEntry> {
         let index = index as usize;
         require_gt!(
-            self.deposits.len(),
+            self.deposit_entry_count(),
             index,
             VsrError::OutOfBoundsDepositEntryIndex
         );
-        let d = &self.deposits[index];
+        let d = if index < VOTER_DEPOSIT_ENTRY_COUNT {
+            &self.voter.deposits[index]
+        } else {
+            &self.extra_deposits[index - VOTER_DEPOSIT_ENTRY_COUNT]
+        };
         Ok(d)
     }
 
@@ -102,18 +282,155 @@ impl Voter {
         Ok(d.is_active())
     }
 
+    /// The reward weight the voter contributes to the total reward weight of
+    /// the registrar since the most recent balance-changing action.
+    fn contributed_reward_weight(&self, curr_ts: i64) -> Result<u64> {
+        // Voters created before reward weights were introduced contributed
+        // their permanently locked tokens to the total reward weight
+        if self.reward_weight_initialized == 0 {
+            self.permanently_locked(curr_ts)
+        } else {
+            Ok(self.reward_weight)
+        }
+    }
+
+    /// The reward weight rewards are accrued with since the most recent
+    /// accrual, the reward weight averaged over that time.
+    pub fn accrual_reward_weight(&self, curr_ts: i64, registrar: &Registrar) -> Result<u64> {
+        // Voters that did not accrue since the accrual time is tracked only
+        // know their current, decayed reward weight
+        let from_ts = if self.reward_accrual_ts == 0 {
+            curr_ts
+        } else {
+            min(self.reward_accrual_ts, curr_ts)
+        };
+        let reward_weight = self
+            .deposits()
+            .filter(|d| d.is_active())
+            .try_fold(0u64, |sum, d| {
+                d.average_reward_weight(&registrar.reward_boost_config, from_ts, curr_ts)
+                    .map(|rw| sum.checked_add(rw).unwrap())
+            })?;
+
+        // The reward weight decays since the most recent balance-changing action,
+        // and is never paid beyond what was contributed to the total reward weight
+        Ok(min(reward_weight, self.contributed_reward_weight(curr_ts)?))
+    }
+
+    /// The vote weight the voter can vote with by itself.
+    ///
+    /// It is zero while the voter delegates its vote weight to another voter.
+    pub fn own_vote_weight(&self, curr_ts: i64, registrar: &Registrar) -> Result<u64> {
+        if self.is_delegated() {
+            return Ok(0);
+        }
+        self.weight(curr_ts, registrar)
+    }
+
+    /// The full vote weight available to the voter
+    pub fn weight(&self, curr_ts: i64, registrar: &Registrar) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .try_fold(0u64, |sum, d| {
+                d.voting_power(&registrar.voting_config, curr_ts)
+                    .map(|vp| sum.checked_add(vp).unwrap())
+            })
+    }
+
+    /// The vote weight of the deposits that stay locked for at least
+    /// `min_lockup_secs`, all of them if zero
+    pub fn weight_locked_for(
+        &self,
+        min_lockup_secs: u64,
+        curr_ts: i64,
+        registrar: &Registrar,
+    ) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .filter(|d| d.get_lockup().seconds_left(curr_ts) >= min_lockup_secs)
+            .try_fold(0u64, |sum, d| {
+                d.voting_power(&registrar.voting_config, curr_ts)
+                    .map(|vp| sum.checked_add(vp).unwrap())
+            })
+    }
+
+    /// The vote weight available to the voter when ignoring any lockup effects
+    pub fn weight_baseline(&self, registrar: &Registrar) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .try_fold(0u64, |sum, d| {
+                registrar
+                    .voting_config
+                    .baseline_vote_weight(d.get_amount_deposited_native())
+                    .map(|vp| sum.checked_add(vp).unwrap())
+            })
+    }
+
+    /// The reward weight of all deposits, see `DepositEntry::reward_weight`
+    pub fn reward_weight(&self, curr_ts: i64, registrar: &Registrar) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .try_fold(0u64, |sum, d| {
+                d.reward_weight(&registrar.reward_boost_config, curr_ts)
+                    .map(|rw| sum.checked_add(rw).unwrap())
+            })
+    }
+
+    pub fn amount_deposited_native(&self) -> u64 {
+        self.deposits().fold(0u64, |sum, d| {
+            sum.checked_add(d.get_amount_deposited_native()).unwrap()
+        })
+    }
+
+    pub fn permanently_locked(&self, curr_ts: i64) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .filter(|d| d.get_lockup().is_constant())
+            .try_fold(0u64, |sum, d| {
+                Ok(sum.checked_add(d.amount_locked(curr_ts)?).unwrap())
+            })
+    }
+
+    pub fn vesting_locked(&self, curr_ts: i64) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .filter(|d| d.get_lockup().is_vesting())
+            .try_fold(0u64, |sum, d| {
+                Ok(sum.checked_add(d.amount_locked(curr_ts)?).unwrap())
+            })
+    }
+
+    pub fn vesting_unlocked(&self, curr_ts: i64) -> Result<u64> {
+        self.deposits()
+            .filter(|d| d.is_active())
+            .filter(|d| d.get_lockup().is_vesting())
+            .try_fold(0u64, |sum, d| {
+                Ok(sum.checked_add(d.amount_unlocked(curr_ts)?).unwrap())
+            })
+    }
+}
+
+impl<V, D> LoadedVoter<V, D>
+where
+    V: DerefMut<Target = Voter>,
+    D: DerefMut<Target = [DepositEntry]>,
+{
     fn deposit_entry_at_mut(&mut self, index: u8) -> Result<&mut DepositEntry> {
         let index = index as usize;
         require_gt!(
-            self.deposits.len(),
+            self.deposit_entry_count(),
             index,
             VsrError::OutOfBoundsDepositEntryIndex
         );
-        let d = &mut self.deposits[index];
+        let d = if index < VOTER_DEPOSIT_ENTRY_COUNT {
+            &mut self.voter.deposits[index]
+        } else {
+            &mut self.extra_deposits[index - VOTER_DEPOSIT_ENTRY_COUNT]
+        };
         Ok(d)
     }
 
-    fn accrue_rewards(&mut self, curr_ts: i64, registrar: &Registrar) -> Result<()> {
+    fn accrue_rewards(&mut self, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
         require_eq!(
             curr_ts,
             registrar.reward_accrual_ts,
@@ -121,33 +438,133 @@ impl Voter {
         );
 
         if registrar.reward_index.as_u128() > self.reward_index.as_u128() {
-            let permanently_locked = self.permanently_locked(curr_ts)?;
+            let reward_index_delta = registrar.reward_index.sub(self.reward_index);
+            let reward_weight = self.accrual_reward_weight(curr_ts, registrar)?;
 
             self.reward_claimable_amount = self
                 .reward_claimable_amount
                 .checked_add(
                     u64::try_from(
-                        registrar
-                            .reward_index
-                            .sub(self.reward_index)
-                            .mul_scalar(permanently_locked as core::primitive::u128)
+                        reward_index_delta
+                            .mul_scalar(reward_weight as core::primitive::u128)
                             .truncate()
                     )
                     .unwrap(),
                 )
                 .unwrap();
             self.reward_index = registrar.reward_index;
+
+            // The registrar issued rewards for the contributed reward weight,
+            // the part of it that decayed is never paid. Return it to the
+            // funded rewards, so that it is issued again.
+            let decayed_reward_weight = self
+                .contributed_reward_weight(curr_ts)?
+                .saturating_sub(reward_weight);
+            let unpaid_reward_amount = u64::try_from(
+                reward_index_delta
+                    .mul_scalar(decayed_reward_weight as core::primitive::u128)
+                    .truncate(),
+            )
+            .unwrap();
+            registrar.issued_reward_amount = registrar
+                .issued_reward_amount
+                .saturating_sub(unpaid_reward_amount);
         }
 
+        self.reward_accrual_ts = curr_ts;
+
+        // Start tracking the contribution of voters created before reward weights
+        if self.reward_weight_initialized == 0 {
+            self.reward_weight = self.permanently_locked(curr_ts)?;
+            self.reward_weight_initialized = 1;
+        }
+
+        Ok(())
+    }
+
+    /// Brings the reward weight of the voter up to date, and the total reward
+    /// weight of the registrar along with it.
+    fn update_reward_weight(&mut self, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
+        let reward_weight = self.reward_weight(curr_ts, registrar)?;
+        registrar.total_reward_weight = registrar
+            .total_reward_weight
+            .checked_sub(self.reward_weight)
+            .unwrap()
+            .checked_add(reward_weight)
+            .unwrap();
+        self.reward_weight = reward_weight;
         Ok(())
     }
 
+    /// Adds the deposits of voters created before the registrar tracked its
+    /// deposit totals to them.
+    pub fn initialize_deposit_totals_if_needed(&mut self, registrar: &mut Registrar) {
+        if self.deposit_totals_initialized == 0 {
+            for d in self.deposits().filter(|d| d.is_active()) {
+                registrar.total_deposited_amount = registrar
+                    .total_deposited_amount
+                    .checked_add(d.get_amount_deposited_native())
+                    .unwrap();
+                registrar.total_locked_amount = registrar
+                    .total_locked_amount
+                    .checked_add(d.get_amount_initially_locked_native())
+                    .unwrap();
+            }
+            self.deposit_totals_initialized = 1;
+            registrar.count_voter_deposits();
+        }
+    }
+
+    /// Transforms the voter and its active deposit entries from their layout
+    /// versions up to `VOTER_VERSION` and `DEPOSIT_ENTRY_VERSION`, one version
+    /// at a time.
+    ///
+    /// The account must have been reallocated to the current size before,
+    /// new fields start zeroed. Transitions that grow `Voter` must move the
+    /// extra deposit entries behind it.
+    pub fn migrate(&mut self, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
+        require_gte!(
+            VOTER_VERSION,
+            self.version,
+            VsrError::UnsupportedLayoutVersion
+        );
+
+        while self.version < VOTER_VERSION {
+            match self.version {
+                0 => self.migrate_v0_to_v1(curr_ts, registrar)?,
+                _ => unreachable!(),
+            }
+            self.version += 1;
+        }
+
+        for index in 0..self.deposit_entry_count() {
+            let d = self.deposit_entry_at_mut(index as u8)?;
+            if d.is_active() {
+                d.migrate()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Version 1 tracks the reward weight and the deposit totals that version
+    /// 0 voters only start tracking on their next balance-changing action.
+    fn migrate_v0_to_v1(&mut self, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
+        self.refresh_reward_weight(curr_ts, registrar)
+    }
+
+    /// Accrues the rewards of the voter and brings its reward weight up to date.
+    pub fn refresh_reward_weight(&mut self, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
+        self.initialize_deposit_totals_if_needed(registrar);
+        self.accrue_rewards(curr_ts, registrar)?;
+        self.update_reward_weight(curr_ts, registrar)
+    }
+
     pub fn activate(
         &mut self,
         index: u8,
         curr_ts: i64,
         lockup: Lockup,
-        registrar: &Registrar,
+        registrar: &mut Registrar,
     ) -> Result<()> {
         self.accrue_rewards(curr_ts, registrar)?;
 
@@ -159,11 +576,21 @@ impl Voter {
     }
 
     pub fn deactivate(&mut self, index: u8, curr_ts: i64, registrar: &mut Registrar) -> Result<()> {
+        self.initialize_deposit_totals_if_needed(registrar);
         self.accrue_rewards(curr_ts, registrar)?;
 
         let d = self.deposit_entry_at_mut(index)?;
-        // Deduct the permanent lock amount if it's lockup is not vesting kind
-        if !d.get_lockup().is_vesting() {
+        registrar.total_deposited_amount = registrar
+            .total_deposited_amount
+            .checked_sub(d.get_amount_deposited_native())
+            .unwrap();
+        registrar.total_locked_amount = registrar
+            .total_locked_amount
+            .checked_sub(d.get_amount_initially_locked_native())
+            .unwrap();
+
+        // Deduct the permanent lock amount if it's lockup is constant kind
+        if d.get_lockup().is_constant() {
             registrar.permanently_locked_amount = registrar
                 .permanently_locked_amount
                 .checked_sub(d.get_amount_deposited_native())
@@ -171,7 +598,7 @@ impl Voter {
         }
 
         d.deactivate()?;
-        Ok(())
+        self.update_reward_weight(curr_ts, registrar)
     }
 
     pub fn deposit(
@@ -181,19 +608,33 @@ impl Voter {
         amount: u64,
         registrar: &mut Registrar,
     ) -> Result<()> {
+        self.initialize_deposit_totals_if_needed(registrar);
         self.accrue_rewards(curr_ts, registrar)?;
 
         let d = self.deposit_entry_at_mut(index)?;
+        let prev_amount_initially_locked_native = d.get_amount_initially_locked_native();
         d.deposit(curr_ts, amount)?;
 
-        // Accumulate the permanent lock amount if it's lockup is not vesting kind
-        if !d.get_lockup().is_vesting() {
+        registrar.total_deposited_amount = registrar
+            .total_deposited_amount
+            .checked_add(amount)
+            .unwrap();
+        registrar.total_locked_amount = registrar
+            .total_locked_amount
+            .checked_sub(prev_amount_initially_locked_native)
+            .unwrap()
+            .checked_add(d.get_amount_initially_locked_native())
+            .unwrap();
+
+        // Accumulate the permanent lock amount if it's lockup is constant kind
+        if d.get_lockup().is_constant() {
             registrar.permanently_locked_amount = registrar
                 .permanently_locked_amount
                 .checked_add(amount)
                 .unwrap();
         }
 
+        self.update_reward_weight(curr_ts, registrar)?;
         Ok(())
     }
 
@@ -202,17 +643,53 @@ impl Voter {
         index: u8,
         curr_ts: i64,
         amount: u64,
-        registrar: &Registrar,
+        registrar: &mut Registrar,
     ) -> Result<u64> {
+        self.initialize_deposit_totals_if_needed(registrar);
         self.accrue_rewards(curr_ts, registrar)?;
 
         let d = self.deposit_entry_at_mut(index)?;
         d.withdraw(curr_ts, amount)?;
+        let amount_deposited_native = d.get_amount_deposited_native();
+
+        registrar.total_deposited_amount = registrar
+            .total_deposited_amount
+            .checked_sub(amount)
+            .unwrap();
+
+        self.update_reward_weight(curr_ts, registrar)?;
+        Ok(amount_deposited_native)
+    }
+
+    /// Allows the realm authority to claw back the locked tokens of the deposit entry.
+    pub fn allow_clawback(&mut self, index: u8) -> Result<()> {
+        let d = self.deposit_entry_at_mut(index)?;
+        d.allow_clawback()
+    }
+
+    /// Takes the locked tokens out of the deposit entry, returns the amount taken.
+    pub fn clawback(&mut self, index: u8, curr_ts: i64, registrar: &mut Registrar) -> Result<u64> {
+        self.initialize_deposit_totals_if_needed(registrar);
+        self.accrue_rewards(curr_ts, registrar)?;
 
-        Ok(d.get_amount_deposited_native())
+        let d = self.deposit_entry_at_mut(index)?;
+        let prev_amount_initially_locked_native = d.get_amount_initially_locked_native();
+        let amount = d.clawback(curr_ts)?;
+
+        registrar.total_deposited_amount = registrar
+            .total_deposited_amount
+            .checked_sub(amount)
+            .unwrap();
+        registrar.total_locked_amount = registrar
+            .total_locked_amount
+            .checked_sub(prev_amount_initially_locked_native)
+            .unwrap();
+
+        self.update_reward_weight(curr_ts, registrar)?;
+        Ok(amount)
     }
 
-    pub fn claim_reward(&mut self, curr_ts: i64, amount: Option<u64>, registrar: &Registrar) -> Result<u64> {
+    pub fn claim_reward(&mut self, curr_ts: i64, amount: Option<u64>, registrar: &mut Registrar) -> Result<u64> {
         self.accrue_rewards(curr_ts, registrar)?;
 
         let claim_amount = amount.unwrap_or(self.reward_claimable_amount);
@@ -226,66 +703,62 @@ impl Voter {
 
         Ok(claim_amount)
     }
+}
 
-    /// The full vote weight available to the voter
-    pub fn weight(&self, curr_ts: i64, registrar: &Registrar) -> Result<u64> {
-        self.deposits
-            .iter()
-            .filter(|d| d.is_active())
-            .try_fold(0u64, |sum, d| {
-                d.voting_power(&registrar.voting_config, curr_ts)
-                    .map(|vp| sum.checked_add(vp).unwrap())
-            })
-    }
+/// Loads a voter account together with its extra deposit entries.
+pub trait LoadVoter {
+    fn load_voter(&self) -> Result<VoterRef<'_>>;
+    fn load_voter_mut(&self) -> Result<VoterRefMut<'_>>;
+    fn load_voter_init(&self) -> Result<VoterRefMut<'_>>;
+}
 
-    /// The vote weight available to the voter when ignoring any lockup effects
-    pub fn weight_baseline(&self, registrar: &Registrar) -> Result<u64> {
-        self.deposits
-            .iter()
-            .filter(|d| d.is_active())
-            .try_fold(0u64, |sum, d| {
-                registrar
-                    .voting_config
-                    .baseline_vote_weight(d.get_amount_deposited_native())
-                    .map(|vp| sum.checked_add(vp).unwrap())
-            })
+impl<'info> LoadVoter for AccountLoader<'info, Voter> {
+    fn load_voter(&self) -> Result<VoterRef<'_>> {
+        // Let anchor check the owner and discriminator of the account
+        drop(self.load()?);
+        let data = self.as_ref().try_borrow_data()?;
+        Ok(split_voter_data(Ref::map(data, |d| &**d)))
     }
 
-    pub fn amount_deposited_native(&self) -> u64 {
-        self.deposits.iter().fold(0u64, |sum, d| {
-            sum.checked_add(d.get_amount_deposited_native()).unwrap()
-        })
+    fn load_voter_mut(&self) -> Result<VoterRefMut<'_>> {
+        drop(self.load_mut()?);
+        let data = self.as_ref().try_borrow_mut_data()?;
+        Ok(split_voter_data_mut(RefMut::map(data, |d| &mut **d)))
     }
 
-    pub fn permanently_locked(&self, curr_ts: i64) -> Result<u64> {
-        self.deposits
-            .iter()
-            .filter(|d| d.is_active())
-            .filter(|d| !d.get_lockup().is_vesting())
-            .try_fold(0u64, |sum, d| {
-                Ok(sum.checked_add(d.amount_locked(curr_ts)?).unwrap())
-            })
+    fn load_voter_init(&self) -> Result<VoterRefMut<'_>> {
+        drop(self.load_init()?);
+        let data = self.as_ref().try_borrow_mut_data()?;
+        Ok(split_voter_data_mut(RefMut::map(data, |d| &mut **d)))
     }
+}
 
-    pub fn vesting_locked(&self, curr_ts: i64) -> Result<u64> {
-        self.deposits
-            .iter()
-            .filter(|d| d.is_active())
-            .filter(|d| d.get_lockup().is_vesting())
-            .try_fold(0u64, |sum, d| {
-                Ok(sum.checked_add(d.amount_locked(curr_ts)?).unwrap())
-            })
-    }
+/// Splits the account data after the discriminator into the `Voter` and the
+/// extra deposit entries following it.
+fn split_voter_data(data: Ref<[u8]>) -> VoterRef {
+    let (voter, extra_deposits) = Ref::map_split(data, |d| {
+        let (voter, extra_deposits) = d[8..].split_at(std::mem::size_of::<Voter>());
+        let extra_len = extra_deposits.len() / std::mem::size_of::<DepositEntry>()
+            * std::mem::size_of::<DepositEntry>();
+        (
+            bytemuck::from_bytes::<Voter>(voter),
+            bytemuck::cast_slice::<u8, DepositEntry>(&extra_deposits[..extra_len]),
+        )
+    });
+    LoadedVoter::new(voter, extra_deposits)
+}
 
-    pub fn vesting_unlocked(&self, curr_ts: i64) -> Result<u64> {
-        self.deposits
-            .iter()
-            .filter(|d| d.is_active())
-            .filter(|d| d.get_lockup().is_vesting())
-            .try_fold(0u64, |sum, d| {
-                Ok(sum.checked_add(d.amount_unlocked(curr_ts)?).unwrap())
-            })
-    }
+fn split_voter_data_mut(data: RefMut<[u8]>) -> VoterRefMut {
+    let (voter, extra_deposits) = RefMut::map_split(data, |d| {
+        let (voter, extra_deposits) = d[8..].split_at_mut(std::mem::size_of::<Voter>());
+        let extra_len = extra_deposits.len() / std::mem::size_of::<DepositEntry>()
+            * std::mem::size_of::<DepositEntry>();
+        (
+            bytemuck::from_bytes_mut::<Voter>(voter),
+            bytemuck::cast_slice_mut::<u8, DepositEntry>(&mut extra_deposits[..extra_len]),
+        )
+    });
+    LoadedVoter::new(voter, extra_deposits)
 }
 
 #[macro_export]
@@ -304,7 +777,11 @@ pub use voter_seeds;
 
 #[cfg(test)]
 mod tests {
-    use crate::{DepositConfig, LockupKind, LockupTimeUnit, VotingConfig};
+    use crate::{
+        DepositConfig, EarlyUnlockPenaltyConfig, EmissionConfig, LockupKind, LockupTimeUnit,
+        MaxVoteWeightMode, ReleaseCliffConfig, RewardBoostConfig, VoteWeightCurve, VoterWeightActionRequirement,
+        VotingConfig, DEPOSIT_ENTRY_VERSION, NODE_DEPOSIT_ENTRY_INDEX, SCALED_FACTOR_BASE, SECS_PER_DAY, TOTAL_REWARD_AMOUNT,
+    };
 
     use super::*;
 
@@ -318,22 +795,26 @@ mod tests {
                 baseline_vote_weight_scaled_factor: 1,
                 max_extra_lockup_vote_weight_scaled_factor: 1,
                 lockup_saturation_secs: 1,
+                vote_weight_curve: VoteWeightCurve::default(),
             },
-            reserved1: [0; 5],
             deposit_config: DepositConfig {
                 ordinary_deposit_min_lockup_duration: crate::LockupTimeDuration {
                     periods: 1,
                     unit: crate::LockupTimeUnit::Day,
-                    filler: [0; 7]
+                    calendar_mode: 0,
+                    filler: [0; 6]
                 },
                 node_deposit_lockup_duration: crate::LockupTimeDuration {
                     periods: 1,
                     unit: LockupTimeUnit::Month,
-                    filler: [0; 7]
+                    calendar_mode: 0,
+                    filler: [0; 6]
                 },
                 node_security_deposit: 1,
+                node_security_deposit_grace_period_secs: 0,
             },
-            reserved2: [0; 5],
+            early_unlock_penalty_config: EarlyUnlockPenaltyConfig::default(),
+            reward_boost_config: RewardBoostConfig::default(),
             current_reward_amount_per_second: u128::new(0),
             last_reward_amount_per_second_rotated_ts: 0,
             issued_reward_amount: 0,
@@ -343,8 +824,23 @@ mod tests {
             time_offset: 0,
             bump: 0,
             max_voter_weight_record_bump: 0,
-            reserved3: [0; 14],
-            reserved4: [0; 9],
+            total_reward_weight_initialized: 1,
+            has_voter_weight_action_requirements: 0,
+            release_cliff_config: ReleaseCliffConfig::default(),
+            restrict_lockup_changes: 0,
+            max_vote_weight_mode: MaxVoteWeightMode::Supply,
+            paused: 0,
+            version: crate::REGISTRAR_VERSION,
+            funded_reward_amount_initialized: 1,
+            uncounted_voter_count_initialized: 1,
+            reserved5: [0; 2],
+            emission_config: EmissionConfig::default(),
+            funded_reward_amount: TOTAL_REWARD_AMOUNT,
+            total_reward_weight: 0,
+            active_node_count: 0,
+            uncounted_voter_count: 0,
+            total_deposited_amount: 0,
+            total_locked_amount: 0,
         }
     }
 
@@ -353,12 +849,13 @@ mod tests {
         let voter_authority = Pubkey::new_unique();
         let registrar = Pubkey::new_unique();
         let mut registrar_data = new_registrar_data();
-        let mut voter = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
         let index: u8 = 0;
 
         assert_eq!(
             voter.activate(
-                voter.deposits.len() as u8,
+                voter.deposit_entry_count() as u8,
                 0,
                 Lockup::default(),
                 &mut registrar_data
@@ -366,7 +863,7 @@ mod tests {
             Err(error!(VsrError::OutOfBoundsDepositEntryIndex)) as Result<()>
         );
         assert_eq!(
-            voter.deactivate(voter.deposits.len() as u8, 0, &mut registrar_data),
+            voter.deactivate(voter.deposit_entry_count() as u8, 0, &mut registrar_data),
             Err(error!(VsrError::OutOfBoundsDepositEntryIndex)) as Result<()>
         );
 
@@ -394,9 +891,10 @@ mod tests {
         let voter_authority = Pubkey::new_unique();
         let registrar = Pubkey::new_unique();
         let mut registrar_data = new_registrar_data();
-        let mut voter = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
         assert_eq!(
-            voter.deposit(voter.deposits.len() as u8, 0, 100, &mut registrar_data),
+            voter.deposit(voter.deposit_entry_count() as u8, 0, 100, &mut registrar_data),
             Err(error!(VsrError::OutOfBoundsDepositEntryIndex)) as Result<()>
         );
 
@@ -405,7 +903,8 @@ mod tests {
             LockupKind::constant(crate::LockupTimeDuration {
                 periods: 1,
                 unit: LockupTimeUnit::Day,
-                filler: [0; 7]
+                calendar_mode: 0,
+                filler: [0; 6]
             }),
             0,
             0,
@@ -459,7 +958,8 @@ mod tests {
             LockupKind::constant(crate::LockupTimeDuration {
                 periods: 1,
                 unit: LockupTimeUnit::Day,
-                filler: [0; 7]
+                calendar_mode: 0,
+                filler: [0; 6]
             }),
             0,
             0,
@@ -476,6 +976,33 @@ mod tests {
         );
         assert_eq!(registrar_data.permanently_locked_amount, 200);
 
+        // index 4, cliff lockups are not permanently locked
+        let lockup_4 = Lockup::new_from_kind(
+            LockupKind::cliff(crate::LockupTimeDuration {
+                periods: 1,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6]
+            }),
+            0,
+            0,
+        )?;
+        voter.activate(4, 0, lockup_4, &mut registrar_data)?;
+        voter.deposit(4, 0, 100, &mut registrar_data)?;
+
+        assert_eq!(
+            voter
+                .deposit_entry_at(4)
+                .unwrap()
+                .get_amount_deposited_native(),
+            100
+        );
+        assert_eq!(registrar_data.permanently_locked_amount, 200);
+        assert_eq!(voter.permanently_locked(0)?, 200);
+
+        voter.deactivate(4, 0, &mut registrar_data)?;
+        assert_eq!(registrar_data.permanently_locked_amount, 200);
+
         Ok(())
     }
 
@@ -484,9 +1011,10 @@ mod tests {
         let voter_authority = Pubkey::new_unique();
         let registrar = Pubkey::new_unique();
         let mut registrar_data = new_registrar_data();
-        let mut voter = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
         assert_eq!(
-            voter.deposit(voter.deposits.len() as u8, 0, 100, &mut registrar_data),
+            voter.deposit(voter.deposit_entry_count() as u8, 0, 100, &mut registrar_data),
             Err(error!(VsrError::OutOfBoundsDepositEntryIndex)) as Result<()>
         );
 
@@ -495,7 +1023,8 @@ mod tests {
             LockupKind::constant(crate::LockupTimeDuration {
                 periods: 1,
                 unit: LockupTimeUnit::Day,
-                filler: [0; 7]
+                calendar_mode: 0,
+                filler: [0; 6]
             }),
             0,
             0,
@@ -536,6 +1065,61 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    pub fn deposit_totals_test() -> Result<()> {
+        let voter_authority = Pubkey::new_unique();
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
+
+        let lockup_0 = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration {
+                periods: 1,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6]
+            }),
+            0,
+            0,
+        )?;
+        voter.activate(0, 0, lockup_0, &mut registrar_data)?;
+        voter.deposit(0, 0, 100, &mut registrar_data)?;
+        let lockup_1 = Lockup::new_from_kind(LockupKind::daily(10), 0, 0)?;
+        voter.activate(1, 0, lockup_1, &mut registrar_data)?;
+        voter.deposit(1, 0, 100, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 200);
+        assert_eq!(registrar_data.total_locked_amount, 200);
+
+        // withdrawing vested tokens leaves the locked total alone
+        let curr_ts = 5 * SECS_PER_DAY as i64;
+        registrar_data.accrue_rewards(curr_ts);
+        voter.withdraw(1, curr_ts, 50, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 150);
+        assert_eq!(registrar_data.total_locked_amount, 200);
+
+        // depositing restarts the vesting of the remaining tokens
+        voter.deposit(1, curr_ts, 10, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 160);
+        assert_eq!(registrar_data.total_locked_amount, 160);
+
+        voter.deactivate(1, curr_ts, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 100);
+        assert_eq!(registrar_data.total_locked_amount, 100);
+
+        // voters created before the totals are counted on their next change
+        voter.deposit_totals_initialized = 0;
+        registrar_data.total_deposited_amount = 0;
+        registrar_data.total_locked_amount = 0;
+        voter.deposit(0, curr_ts, 10, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 110);
+        assert_eq!(registrar_data.total_locked_amount, 110);
+        voter.deposit(0, curr_ts, 10, &mut registrar_data)?;
+        assert_eq!(registrar_data.total_deposited_amount, 120);
+
+        Ok(())
+    }
+
     #[test]
     fn accrue_rewards_test() -> Result<()> {
         let voter_authority = Pubkey::new_unique();
@@ -544,14 +1128,16 @@ mod tests {
         registrar_data.reward_accrual_ts = 1;
         registrar_data.reward_index = u128::new_with_denom(1, 10);
 
-        let mut voter = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
 
         // index 0
         let lockup_0 = Lockup::new_from_kind(
             LockupKind::constant(crate::LockupTimeDuration {
                 periods: 1,
                 unit: LockupTimeUnit::Day,
-                filler: [0; 7]
+                calendar_mode: 0,
+                filler: [0; 6]
             }),
             0,
             0,
@@ -620,4 +1206,366 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reward_weight_test() -> Result<()> {
+        let voter_authority = Pubkey::new_unique();
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        registrar_data.reward_boost_config = RewardBoostConfig {
+            max_extra_lockup_reward_weight_scaled_factor: crate::SCALED_FACTOR_BASE,
+            lockup_saturation_secs: 10 * crate::SECS_PER_DAY,
+        };
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
+
+        // index 0: constant lockup of 5 days, boosted by half and never decaying
+        let lockup_0 = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration {
+                periods: 5,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6],
+            }),
+            0,
+            0,
+        )?;
+        voter.activate(0, 0, lockup_0, &mut registrar_data)?;
+        voter.deposit(0, 0, 100, &mut registrar_data)?;
+        assert_eq!(voter.reward_weight, 150);
+        assert_eq!(registrar_data.total_reward_weight, 150);
+
+        // index 1: daily vesting over 10 days, earning rewards as well
+        let lockup_1 = Lockup::new_from_kind(LockupKind::daily(10), 0, 0)?;
+        voter.activate(1, 0, lockup_1, &mut registrar_data)?;
+        voter.deposit(1, 0, 100, &mut registrar_data)?;
+        let reward_weight = voter.reward_weight(0, &registrar_data)?;
+        assert!(reward_weight > 250 && reward_weight < 350);
+        assert_eq!(voter.reward_weight, reward_weight);
+        assert_eq!(registrar_data.total_reward_weight, reward_weight);
+
+        // the vesting entry decays, rewards are accrued on the decayed reward weight
+        let curr_ts = (5 * crate::SECS_PER_DAY) as i64;
+        registrar_data.reward_accrual_ts = curr_ts;
+        registrar_data.reward_index = u128::new(1);
+        registrar_data.issued_reward_amount = reward_weight;
+        let decayed_reward_weight = voter.reward_weight(curr_ts, &registrar_data)?;
+        assert!(decayed_reward_weight < reward_weight);
+        assert!(decayed_reward_weight > 150);
+        voter.refresh_reward_weight(curr_ts, &mut registrar_data)?;
+        assert_eq!(voter.reward_claimable_amount, decayed_reward_weight);
+        // the rewards issued for the decayed reward weight are issued again
+        assert_eq!(registrar_data.issued_reward_amount, decayed_reward_weight);
+        assert_eq!(voter.reward_weight, decayed_reward_weight);
+        assert_eq!(registrar_data.total_reward_weight, decayed_reward_weight);
+
+        // fully vested tokens earn nothing
+        let curr_ts = (10 * crate::SECS_PER_DAY) as i64;
+        registrar_data.reward_accrual_ts = curr_ts;
+        voter.refresh_reward_weight(curr_ts, &mut registrar_data)?;
+        assert_eq!(voter.reward_weight, 150);
+        assert_eq!(registrar_data.total_reward_weight, 150);
+
+        // deactivating removes the reward weight
+        voter.withdraw(1, curr_ts, 100, &mut registrar_data)?;
+        voter.deactivate(1, curr_ts, &mut registrar_data)?;
+        voter.deactivate(0, curr_ts, &mut registrar_data)?;
+        assert_eq!(voter.reward_weight, 0);
+        assert_eq!(registrar_data.total_reward_weight, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn accrual_reward_weight_test() -> Result<()> {
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        registrar_data.reward_boost_config = RewardBoostConfig {
+            max_extra_lockup_reward_weight_scaled_factor: crate::SCALED_FACTOR_BASE,
+            lockup_saturation_secs: 10 * crate::SECS_PER_DAY,
+        };
+        let start_ts = 1_000_000;
+        registrar_data.reward_accrual_ts = start_ts;
+
+        // voter 0 accrues once at the end, voter 1 refreshes every hour
+        let mut voter_data_0 = Voter::new(Pubkey::new_unique(), registrar, u128::new(0), 0, 0);
+        let mut voter_data_1 = Voter::new(Pubkey::new_unique(), registrar, u128::new(0), 0, 0);
+        let mut voter_0 = LoadedVoter::new(&mut voter_data_0, Vec::new());
+        let mut voter_1 = LoadedVoter::new(&mut voter_data_1, Vec::new());
+        for voter in [&mut voter_0, &mut voter_1] {
+            let lockup = Lockup::new_from_kind(
+                LockupKind::daily(10).with_cliff_periods(3),
+                start_ts,
+                start_ts,
+            )?;
+            voter.activate(0, start_ts, lockup, &mut registrar_data)?;
+            voter.deposit(0, start_ts, 1_000_000_000, &mut registrar_data)?;
+        }
+        let contributed_reward_weight = voter_0.reward_weight;
+        assert_eq!(voter_1.reward_weight, contributed_reward_weight);
+
+        // the reward index grows by one per hour for twelve days
+        let hours = 12 * 24;
+        let mut curr_ts = start_ts;
+        for _ in 0..hours {
+            curr_ts += 3_600;
+            registrar_data.reward_accrual_ts = curr_ts;
+            registrar_data.reward_index = registrar_data.reward_index.add(u128::new(1));
+            voter_1.refresh_reward_weight(curr_ts, &mut registrar_data)?;
+        }
+        voter_0.refresh_reward_weight(curr_ts, &mut registrar_data)?;
+
+        // both accrue the same rewards, up to the rounding of each accrual
+        let amount_0 = voter_0.reward_claimable_amount;
+        let amount_1 = voter_1.reward_claimable_amount;
+        assert!(amount_0.abs_diff(amount_1) <= hours, "{} != {}", amount_0, amount_1);
+        // which is less than what the reward weight before the decay would earn
+        assert!(amount_0 < contributed_reward_weight * hours);
+        // and more than what the fully decayed reward weight would earn
+        assert!(amount_0 > voter_0.reward_weight * hours);
+
+        // accruing again at the same time adds nothing
+        voter_0.refresh_reward_weight(curr_ts, &mut registrar_data)?;
+        assert_eq!(voter_0.reward_claimable_amount, amount_0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn reward_weight_legacy_voter_test() -> Result<()> {
+        let voter_authority = Pubkey::new_unique();
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
+
+        let lockup_0 = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration {
+                periods: 1,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6],
+            }),
+            0,
+            0,
+        )?;
+        voter.activate(0, 0, lockup_0, &mut registrar_data)?;
+        voter.deposit(0, 0, 100, &mut registrar_data)?;
+        let lockup_1 = Lockup::new_from_kind(LockupKind::daily(10), 0, 0)?;
+        voter.activate(1, 0, lockup_1, &mut registrar_data)?;
+        voter.deposit(1, 0, 100, &mut registrar_data)?;
+
+        // a voter of a legacy registrar only contributed its permanently locked tokens
+        voter.reward_weight_initialized = 0;
+        voter.reward_weight = 0;
+        registrar_data.total_reward_weight_initialized = 0;
+        registrar_data.total_reward_weight = 0;
+        registrar_data.reward_accrual_ts = 1;
+        registrar_data.accrue_rewards(1);
+        assert_eq!(registrar_data.total_reward_weight, 100);
+
+        registrar_data.reward_index = u128::new(1);
+        assert_eq!(voter.accrual_reward_weight(1, &registrar_data)?, 100);
+        voter.refresh_reward_weight(1, &mut registrar_data)?;
+        assert_eq!(voter.reward_claimable_amount, 100);
+        assert_eq!(voter.reward_weight, 200);
+        assert_eq!(registrar_data.total_reward_weight, 200);
+
+        Ok(())
+    }
+
+    #[test]
+    fn extra_deposit_entries_test() -> Result<()> {
+        let voter_authority = Pubkey::new_unique();
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(
+            &mut voter_data,
+            vec![DepositEntry::default(); 4],
+        );
+        assert_eq!(voter.deposit_entry_count(), VOTER_DEPOSIT_ENTRY_COUNT + 4);
+        assert_eq!(
+            voter.deposit(voter.deposit_entry_count() as u8, 0, 100, &mut registrar_data),
+            Err(error!(VsrError::OutOfBoundsDepositEntryIndex)) as Result<()>
+        );
+
+        let lockup = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration {
+                periods: 1,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6],
+            }),
+            0,
+            0,
+        )?;
+        let header_index = (VOTER_DEPOSIT_ENTRY_COUNT - 1) as u8;
+        let extra_index = (VOTER_DEPOSIT_ENTRY_COUNT + 3) as u8;
+        voter.activate(header_index, 0, lockup, &mut registrar_data)?;
+        voter.deposit(header_index, 0, 100, &mut registrar_data)?;
+        voter.activate(extra_index, 0, lockup, &mut registrar_data)?;
+        voter.deposit(extra_index, 0, 50, &mut registrar_data)?;
+
+        assert!(voter.is_active(extra_index)?);
+        assert!(voter.extra_deposits[3].is_active());
+        assert_eq!(voter.amount_deposited_native(), 150);
+        assert_eq!(voter.permanently_locked(0)?, 150);
+        assert_eq!(registrar_data.permanently_locked_amount, 150);
+
+        voter.deactivate(extra_index, 0, &mut registrar_data)?;
+        assert_eq!(voter.amount_deposited_native(), 100);
+        assert_eq!(registrar_data.permanently_locked_amount, 100);
+
+        Ok(())
+    }
+
+    #[test]
+    fn voter_weight_action_requirement_test() -> Result<()> {
+        let mut registrar_data = new_registrar_data();
+        registrar_data.voting_config.baseline_vote_weight_scaled_factor = SCALED_FACTOR_BASE;
+        registrar_data.voting_config.max_extra_lockup_vote_weight_scaled_factor = 0;
+        let mut voter_data = Voter::new(Pubkey::new_unique(), Pubkey::new_unique(), u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
+
+        let lockup = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration::new(1, LockupTimeUnit::Day)),
+            0,
+            0,
+        )?;
+        voter.activate(1, 0, lockup, &mut registrar_data)?;
+        voter.deposit(1, 0, 100, &mut registrar_data)?;
+
+        let mut requirement = VoterWeightActionRequirement::default();
+        assert!(requirement.is_empty());
+        assert!(requirement.check(&voter, 0, &registrar_data).is_ok());
+
+        requirement.min_voter_weight = 100;
+        assert!(requirement.check(&voter, 0, &registrar_data).is_ok());
+        requirement.min_voter_weight = 101;
+        assert_eq!(
+            requirement.check(&voter, 0, &registrar_data),
+            Err(error!(VsrError::VoterWeightActionRequirementNotMet))
+        );
+
+        // the deposit is locked for a day only
+        requirement.min_voter_weight = 0;
+        requirement.min_lockup_secs = 2 * SECS_PER_DAY as u32;
+        assert!(requirement.check(&voter, 0, &registrar_data).is_err());
+        requirement.min_lockup_secs = SECS_PER_DAY as u32;
+        assert!(requirement.check(&voter, 0, &registrar_data).is_ok());
+
+        requirement.require_node_deposit = 1;
+        assert!(requirement.check(&voter, 0, &registrar_data).is_err());
+        voter.activate(NODE_DEPOSIT_ENTRY_INDEX, 0, lockup, &mut registrar_data)?;
+        voter.deposit(NODE_DEPOSIT_ENTRY_INDEX, 0, 100, &mut registrar_data)?;
+        assert!(requirement.check(&voter, 0, &registrar_data).is_ok());
+        assert_eq!(voter.weight_locked_for(SECS_PER_DAY, 0, &registrar_data)?, 200);
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_voter_data_test() -> Result<()> {
+        // u64 words keep the account data aligned like the runtime does
+        let len = Voter::account_size(VOTER_DEPOSIT_ENTRY_COUNT + 2);
+        let mut words = vec![0u64; len / 8];
+        let data: &mut [u8] = bytemuck::cast_slice_mut(&mut words);
+        let cell = std::cell::RefCell::new(data);
+
+        {
+            let mut voter = split_voter_data_mut(RefMut::map(cell.borrow_mut(), |d| &mut **d));
+            assert_eq!(voter.deposit_entry_count(), VOTER_DEPOSIT_ENTRY_COUNT + 2);
+            *voter.deposit_entry_at_mut((VOTER_DEPOSIT_ENTRY_COUNT + 1) as u8)? =
+                DepositEntry::new_from_lockup(Lockup::default())?;
+        }
+
+        let voter = split_voter_data(Ref::map(cell.borrow(), |d| &**d));
+        assert!(voter.is_active((VOTER_DEPOSIT_ENTRY_COUNT + 1) as u8)?);
+        assert_eq!(
+            voter.deposits().filter(|d| d.is_active()).count(),
+            1
+        );
+
+        // existing accounts have no extra deposit entries
+        assert_eq!(Voter::account_size(VOTER_DEPOSIT_ENTRY_COUNT), 8 + std::mem::size_of::<Voter>());
+        let voter = split_voter_data(Ref::map(cell.borrow(), |d| &d[..Voter::account_size(0)]));
+        assert_eq!(voter.deposit_entry_count(), VOTER_DEPOSIT_ENTRY_COUNT);
+
+        Ok(())
+    }
+
+    #[test]
+    fn delegator_count_test() -> Result<()> {
+        let mut voter = Voter::new(Pubkey::new_unique(), Pubkey::new_unique(), u128::new(0), 0, 0);
+        assert_eq!(0, voter.get_delegator_count());
+
+        voter.add_delegator();
+        voter.add_delegator();
+        assert_eq!(2, voter.get_delegator_count());
+
+        voter.remove_delegator();
+        voter.remove_delegator();
+        assert_eq!(0, voter.get_delegator_count());
+
+        Ok(())
+    }
+
+    #[test]
+    fn migrate_v0_to_v1_test() -> Result<()> {
+        let voter_authority = Pubkey::new_unique();
+        let registrar = Pubkey::new_unique();
+        let mut registrar_data = new_registrar_data();
+        let mut voter_data = Voter::new(voter_authority, registrar, u128::new(0), 0, 0);
+        let mut voter = LoadedVoter::new(&mut voter_data, Vec::new());
+
+        let lockup = Lockup::new_from_kind(
+            LockupKind::constant(crate::LockupTimeDuration {
+                periods: 1,
+                unit: LockupTimeUnit::Day,
+                calendar_mode: 0,
+                filler: [0; 6]
+            }),
+            0,
+            0,
+        )?;
+        voter.activate(0, 0, lockup, &mut registrar_data)?;
+        voter.deposit(0, 0, 100, &mut registrar_data)?;
+
+        // a version 0 voter that is not counted in the registrar yet
+        voter.version = 0;
+        voter.deposit_totals_initialized = 0;
+        voter.reward_weight_initialized = 0;
+        voter.reward_weight = 0;
+        registrar_data.total_deposited_amount = 0;
+        registrar_data.total_locked_amount = 0;
+        registrar_data.uncounted_voter_count = 1;
+        // legacy registrars count permanently locked tokens as reward weight
+        assert_eq!(100, registrar_data.total_reward_weight);
+
+        let curr_ts = SECS_PER_DAY as i64;
+        registrar_data.accrue_rewards(curr_ts);
+        voter.migrate(curr_ts, &mut registrar_data)?;
+        assert_eq!(VOTER_VERSION, voter.get_version());
+        assert_eq!(1, voter.deposit_totals_initialized);
+        assert_eq!(1, voter.reward_weight_initialized);
+        assert_eq!(100, voter.get_reward_weight());
+        assert_eq!(100, registrar_data.total_deposited_amount);
+        assert_eq!(100, registrar_data.total_locked_amount);
+        assert_eq!(0, registrar_data.uncounted_voter_count);
+        assert_eq!(100, registrar_data.total_reward_weight);
+        assert_eq!(DEPOSIT_ENTRY_VERSION, voter.deposit_entry_at(0)?.get_version());
+
+        // migrating again has no effect
+        voter.migrate(curr_ts, &mut registrar_data)?;
+        assert_eq!(100, registrar_data.total_deposited_amount);
+
+        voter.version = VOTER_VERSION + 1;
+        assert_eq!(
+            voter.migrate(curr_ts, &mut registrar_data),
+            Err(error!(VsrError::UnsupportedLayoutVersion))
+        );
+
+        Ok(())
+    }
 }
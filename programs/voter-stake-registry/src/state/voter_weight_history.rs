@@ -0,0 +1,307 @@
+use crate::error::*;
+use crate::state::deposit_entry::DepositEntry;
+use crate::state::registrar::Registrar;
+use crate::state::voter::{LoadedVoter, Voter};
+use anchor_lang::prelude::*;
+use std::cell::RefMut;
+use std::ops::Deref;
+
+/// The count of checkpoints kept by a voter weight history
+pub const VOTER_WEIGHT_CHECKPOINT_COUNT: usize = 64;
+
+/// The vote weight of a voter as of a balance-changing instruction.
+#[derive(AnchorSerialize, AnchorDeserialize, Default, Debug, PartialEq)]
+#[zero_copy]
+pub struct VoterWeightCheckpoint {
+    /// The time the checkpoint was recorded at
+    pub ts: i64,
+    /// The vote weight the voter could vote with by itself, see
+    /// `Voter::own_vote_weight`
+    pub weight: u64,
+    /// Amount of native tokens in constant lockups
+    pub permanently_locked: u64,
+    /// Vote weight delegated to the voter, the sum of the delegators' vote
+    /// weights when they delegated. Their lockups keep decaying, which this
+    /// does not account for. Delegations made before the history was
+    /// created are not included.
+    pub delegated_weight: u64,
+}
+const_assert!(std::mem::size_of::<VoterWeightCheckpoint>() == 4 * 8);
+
+/// History of a voter's vote weight, for looking up the vote weight the voter
+/// had at a past time, e.g. at the start of a proposal.
+///
+/// There can only be a single history per voter, it is created by
+/// `create_voter_weight_history`. From then on, balance-changing instructions
+/// require it and record a checkpoint, and `delegate` and `undelegate` record
+/// one for the delegate. Once all VOTER_WEIGHT_CHECKPOINT_COUNT checkpoints
+/// are used, the oldest ones are overwritten.
+#[account(zero_copy)]
+pub struct VoterWeightHistory {
+    pub registrar: Pubkey,
+    /// Voter authority of the voter the history belongs to
+    pub voter_authority: Pubkey,
+
+    pub bump: u8,
+    pub reserved1: [u8; 7],
+    /// Number of checkpoints recorded so far, including overwritten ones
+    pub checkpoint_count: u64,
+    pub reserved2: [u64; 4],
+
+    /// Ring buffer of checkpoints, the next one is recorded at
+    /// checkpoint_count % VOTER_WEIGHT_CHECKPOINT_COUNT
+    pub checkpoints: [VoterWeightCheckpoint; VOTER_WEIGHT_CHECKPOINT_COUNT],
+}
+const_assert!(
+    std::mem::size_of::<VoterWeightHistory>()
+        == 2 * 32 + 1 + 7 + 8 + 4 * 8 + VOTER_WEIGHT_CHECKPOINT_COUNT * 32
+);
+const_assert!(std::mem::size_of::<VoterWeightHistory>() % 8 == 0);
+
+impl VoterWeightHistory {
+    /// Records the current vote weight of the voter.
+    pub fn record<V, D>(
+        &mut self,
+        voter: &LoadedVoter<V, D>,
+        curr_ts: i64,
+        registrar: &Registrar,
+    ) -> Result<()>
+    where
+        V: Deref<Target = Voter>,
+        D: Deref<Target = [DepositEntry]>,
+    {
+        self.record_with_delegated_weight(voter, curr_ts, registrar, self.delegated_weight())
+    }
+
+    /// Records the current vote weight of the voter after a voter delegated
+    /// `amount` of vote weight to it.
+    pub fn add_delegated_weight<V, D>(
+        &mut self,
+        voter: &LoadedVoter<V, D>,
+        curr_ts: i64,
+        registrar: &Registrar,
+        amount: u64,
+    ) -> Result<()>
+    where
+        V: Deref<Target = Voter>,
+        D: Deref<Target = [DepositEntry]>,
+    {
+        let delegated_weight = self.delegated_weight().checked_add(amount).unwrap();
+        self.record_with_delegated_weight(voter, curr_ts, registrar, delegated_weight)
+    }
+
+    /// Records the current vote weight of the voter after a voter took back
+    /// the `amount` of vote weight it delegated. The delegation must have been
+    /// added to the history, see `DelegationRecord::counted_in_weight_history`.
+    pub fn remove_delegated_weight<V, D>(
+        &mut self,
+        voter: &LoadedVoter<V, D>,
+        curr_ts: i64,
+        registrar: &Registrar,
+        amount: u64,
+    ) -> Result<()>
+    where
+        V: Deref<Target = Voter>,
+        D: Deref<Target = [DepositEntry]>,
+    {
+        let delegated_weight = self.delegated_weight().checked_sub(amount).unwrap();
+        self.record_with_delegated_weight(voter, curr_ts, registrar, delegated_weight)
+    }
+
+    fn record_with_delegated_weight<V, D>(
+        &mut self,
+        voter: &LoadedVoter<V, D>,
+        curr_ts: i64,
+        registrar: &Registrar,
+        delegated_weight: u64,
+    ) -> Result<()>
+    where
+        V: Deref<Target = Voter>,
+        D: Deref<Target = [DepositEntry]>,
+    {
+        self.push(VoterWeightCheckpoint {
+            ts: curr_ts,
+            weight: voter.own_vote_weight(curr_ts, registrar)?,
+            permanently_locked: voter.permanently_locked(curr_ts)?,
+            delegated_weight,
+        });
+        Ok(())
+    }
+
+    /// Appends a checkpoint, replacing the latest one if it was recorded at
+    /// the same time.
+    pub fn push(&mut self, checkpoint: VoterWeightCheckpoint) {
+        if let Some(latest) = self.latest() {
+            if latest.ts == checkpoint.ts {
+                let index = self.index_of(self.checkpoint_count - 1);
+                self.checkpoints[index] = checkpoint;
+                return;
+            }
+        }
+        let index = self.index_of(self.checkpoint_count);
+        self.checkpoints[index] = checkpoint;
+        self.checkpoint_count = self.checkpoint_count.checked_add(1).unwrap();
+    }
+
+    /// The most recent checkpoint, if any
+    pub fn latest(&self) -> Option<&VoterWeightCheckpoint> {
+        self.iter_rev().next()
+    }
+
+    /// Vote weight delegated to the voter as of the most recent checkpoint
+    pub fn delegated_weight(&self) -> u64 {
+        self.latest().map_or(0, |c| c.delegated_weight)
+    }
+
+    /// The checkpoint that was current at `ts`, the latest one recorded at
+    /// or before it.
+    ///
+    /// None if `ts` is before the oldest checkpoint that is still kept.
+    pub fn checkpoint_at(&self, ts: i64) -> Option<&VoterWeightCheckpoint> {
+        self.iter_rev().find(|c| c.ts <= ts)
+    }
+
+    /// The vote weight of the voter, including the vote weight delegated to
+    /// it, as of the latest checkpoint at or before `ts`.
+    ///
+    /// Lockups keep decaying between checkpoints, which this does not account
+    /// for. None if `ts` is before the oldest checkpoint that is still kept.
+    pub fn get_weight_at(&self, ts: i64) -> Option<u64> {
+        self.checkpoint_at(ts)
+            .map(|c| c.weight.saturating_add(c.delegated_weight))
+    }
+
+    /// Checkpoints from the most recent to the oldest one still kept
+    fn iter_rev(&self) -> impl Iterator<Item = &VoterWeightCheckpoint> {
+        let kept = self
+            .checkpoint_count
+            .min(VOTER_WEIGHT_CHECKPOINT_COUNT as u64);
+        (0..kept).map(move |i| &self.checkpoints[self.index_of(self.checkpoint_count - 1 - i)])
+    }
+
+    #[inline(always)]
+    fn index_of(&self, n: u64) -> usize {
+        (n % VOTER_WEIGHT_CHECKPOINT_COUNT as u64) as usize
+    }
+}
+
+/// Loads the given vote weight history after checking that it belongs to
+/// the voter. It is required if the voter has one.
+pub fn load_voter_weight_history<'a>(
+    history: Option<&'a AccountLoader<VoterWeightHistory>>,
+    registrar_key: &Pubkey,
+    voter: &Voter,
+) -> Result<Option<RefMut<'a, VoterWeightHistory>>> {
+    let history = match history {
+        Some(history) => history.load_mut()?,
+        None => {
+            require!(
+                !voter.has_weight_history(),
+                VsrError::VoterWeightHistoryRequired
+            );
+            return Ok(None);
+        }
+    };
+    require_keys_eq!(
+        history.registrar,
+        *registrar_key,
+        VsrError::InvalidVoterWeightHistory
+    );
+    require_keys_eq!(
+        history.voter_authority,
+        voter.get_voter_authority(),
+        VsrError::InvalidVoterWeightHistory
+    );
+    Ok(Some(history))
+}
+
+/// Records the current vote weight of the voter into its vote weight history,
+/// see `load_voter_weight_history`.
+pub fn record_voter_weight<V, D>(
+    history: Option<&AccountLoader<VoterWeightHistory>>,
+    registrar_key: &Pubkey,
+    voter: &LoadedVoter<V, D>,
+    curr_ts: i64,
+    registrar: &Registrar,
+) -> Result<()>
+where
+    V: Deref<Target = Voter>,
+    D: Deref<Target = [DepositEntry]>,
+{
+    if let Some(history) = &mut load_voter_weight_history(history, registrar_key, voter)? {
+        history.record(voter, curr_ts, registrar)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+
+    fn checkpoint(ts: i64, weight: u64) -> VoterWeightCheckpoint {
+        VoterWeightCheckpoint {
+            ts,
+            weight,
+            permanently_locked: weight / 2,
+            delegated_weight: 0,
+        }
+    }
+
+    #[test]
+    fn get_weight_at_test() {
+        let mut history: VoterWeightHistory = Zeroable::zeroed();
+        assert_eq!(history.latest(), None);
+        assert_eq!(history.get_weight_at(100), None);
+
+        history.push(checkpoint(100, 10));
+        history.push(checkpoint(200, 20));
+        history.push(checkpoint(300, 30));
+        assert_eq!(history.checkpoint_count, 3);
+        assert_eq!(history.latest(), Some(&checkpoint(300, 30)));
+        assert_eq!(history.get_weight_at(99), None);
+        assert_eq!(history.get_weight_at(100), Some(10));
+        assert_eq!(history.get_weight_at(199), Some(10));
+        assert_eq!(history.get_weight_at(200), Some(20));
+        assert_eq!(history.get_weight_at(1000), Some(30));
+        assert_eq!(history.checkpoint_at(250), Some(&checkpoint(200, 20)));
+
+        // the latest checkpoint is replaced at the same time
+        history.push(checkpoint(300, 35));
+        assert_eq!(history.checkpoint_count, 3);
+        assert_eq!(history.get_weight_at(300), Some(35));
+    }
+
+    #[test]
+    fn delegated_weight_test() {
+        let mut history: VoterWeightHistory = Zeroable::zeroed();
+        assert_eq!(history.delegated_weight(), 0);
+
+        history.push(checkpoint(100, 10));
+        history.push(VoterWeightCheckpoint {
+            delegated_weight: 5,
+            ..checkpoint(200, 10)
+        });
+        assert_eq!(history.delegated_weight(), 5);
+        assert_eq!(history.get_weight_at(199), Some(10));
+        assert_eq!(history.get_weight_at(200), Some(15));
+    }
+
+    #[test]
+    fn ring_buffer_test() {
+        let mut history: VoterWeightHistory = Zeroable::zeroed();
+        let count = VOTER_WEIGHT_CHECKPOINT_COUNT as i64;
+        for i in 0..count + 10 {
+            history.push(checkpoint(i * 10, i as u64));
+        }
+        assert_eq!(history.checkpoint_count, count as u64 + 10);
+        assert_eq!(history.latest(), Some(&checkpoint((count + 9) * 10, count as u64 + 9)));
+
+        // the oldest 10 checkpoints were overwritten
+        assert_eq!(history.get_weight_at(95), None);
+        assert_eq!(history.get_weight_at(100), Some(10));
+        assert_eq!(history.get_weight_at(105), Some(10));
+        assert_eq!(history.get_weight_at((count + 5) * 10 + 1), Some(count as u64 + 5));
+        assert_eq!(history.get_weight_at(i64::MAX), Some(count as u64 + 9));
+    }
+}
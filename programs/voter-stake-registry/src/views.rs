@@ -0,0 +1,201 @@
+use anchor_lang::prelude::*;
+
+use crate::events::{DepositEntryInfo, RegistrarInfo, VoterInfo};
+
+/// Maximum number of deposit entries returned by one 'view_voter_info'.
+/// Return data is limited to 1024 bytes, voters with more deposit entries
+/// are read in pages.
+pub const VOTER_VIEW_DEPOSIT_ENTRY_COUNT: usize = 8;
+
+/// Return data of 'view_voter_info', the values 'log_voter_info' logs.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct VoterView {
+    /// Voter's total voting power
+    pub voting_power: u64,
+    /// Voter's total voting power, when ignoring any effects from lockup
+    pub voting_power_baseline: u64,
+    /// Voter's total reward weight
+    pub reward_weight: u64,
+    /// Accumulated reward amount
+    pub reward_amount: u64,
+    /// Amount of funded rewards that were not issued yet
+    pub reward_runway_amount: u64,
+    /// Seconds until the funded rewards are used up at the current emission rate,
+    /// None if no rewards are being issued
+    pub reward_runway_secs: Option<u64>,
+    /// Number of deposit entries of the voter, active or not
+    pub deposit_entry_count: u8,
+    /// DepositEntry info of the requested deposit entries, at most
+    /// `VOTER_VIEW_DEPOSIT_ENTRY_COUNT` of them starting at the requested
+    /// index, None if inactive
+    pub deposit_entries: Vec<Option<DepositEntryInfo>>,
+    /// True if the node deposit is below the node security deposit and
+    /// the grace period of the node to top it up has passed, false if the
+    /// node account was not given
+    pub node_non_compliant: bool,
+}
+
+impl From<VoterView> for VoterInfo {
+    fn from(view: VoterView) -> Self {
+        VoterInfo {
+            voting_power: view.voting_power,
+            voting_power_baseline: view.voting_power_baseline,
+            reward_weight: view.reward_weight,
+            reward_amount: view.reward_amount,
+            reward_runway_amount: view.reward_runway_amount,
+            reward_runway_secs: view.reward_runway_secs,
+            deposit_entries: view.deposit_entries,
+            node_non_compliant: view.node_non_compliant,
+        }
+    }
+}
+
+/// Return data of 'view_registrar_info', the values 'log_registrar_info' logs.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RegistrarView {
+    /// Tokens in the deposit entries of all voters
+    pub deposited_amount: u64,
+    /// Tokens in constant lockups
+    pub permanently_locked_amount: u64,
+    /// Upper bound of the tokens in vesting and cliff lockups. They count as
+    /// locked when their lockups started, vesting since then is only deducted
+    /// once the deposit entry changes again
+    pub max_vesting_locked_amount: u64,
+    /// Amount of rewards issued up to now
+    pub issued_reward_amount: u64,
+    /// Upper bound of the issued rewards that were not claimed yet. Rewards
+    /// claimed before the registrar stats were created are not deducted
+    pub max_unclaimed_reward_amount: u64,
+    /// Amount of funded rewards that were not issued yet
+    pub reward_runway_amount: u64,
+    /// Seconds until the funded rewards are used up at the current emission rate,
+    /// None if no rewards are being issued
+    pub reward_runway_secs: Option<u64>,
+    /// Sum of the reward weights of all voters
+    pub total_reward_weight: u64,
+    /// Number of voters
+    pub voter_count: u64,
+    /// Number of nodes with status 'Active'
+    pub active_node_count: u64,
+}
+
+impl From<RegistrarView> for RegistrarInfo {
+    fn from(view: RegistrarView) -> Self {
+        RegistrarInfo {
+            deposited_amount: view.deposited_amount,
+            permanently_locked_amount: view.permanently_locked_amount,
+            max_vesting_locked_amount: view.max_vesting_locked_amount,
+            issued_reward_amount: view.issued_reward_amount,
+            max_unclaimed_reward_amount: view.max_unclaimed_reward_amount,
+            reward_runway_amount: view.reward_runway_amount,
+            reward_runway_secs: view.reward_runway_secs,
+            total_reward_weight: view.total_reward_weight,
+            voter_count: view.voter_count,
+            active_node_count: view.active_node_count,
+        }
+    }
+}
+
+/// Typed helpers for programs that read the views through CPI.
+///
+/// The views are returned through `set_return_data`, these helpers invoke
+/// them and decode the return data.
+#[cfg(feature = "cpi")]
+pub mod cpi {
+    use super::*;
+
+    pub fn view_voter_info<'info>(
+        program: AccountInfo<'info>,
+        registrar: AccountInfo<'info>,
+        voter: AccountInfo<'info>,
+        node: Option<AccountInfo<'info>>,
+        first_deposit_entry_index: u8,
+    ) -> Result<VoterView> {
+        let view = crate::cpi::view_voter_info(
+            CpiContext::new(
+                program,
+                crate::cpi::accounts::ViewVoterInfo {
+                    registrar,
+                    voter,
+                    node,
+                },
+            ),
+            first_deposit_entry_index,
+        )?;
+        Ok(view.get())
+    }
+
+    pub fn view_voter_weight_at<'info>(
+        program: AccountInfo<'info>,
+        registrar: AccountInfo<'info>,
+        voter_weight_history: AccountInfo<'info>,
+        ts: i64,
+    ) -> Result<Option<u64>> {
+        let view = crate::cpi::view_voter_weight_at(
+            CpiContext::new(
+                program,
+                crate::cpi::accounts::ViewVoterWeightAt {
+                    registrar,
+                    voter_weight_history,
+                },
+            ),
+            ts,
+        )?;
+        Ok(view.get())
+    }
+
+    pub fn view_registrar_info<'info>(
+        program: AccountInfo<'info>,
+        registrar: AccountInfo<'info>,
+        registrar_stats: AccountInfo<'info>,
+    ) -> Result<RegistrarView> {
+        let view = crate::cpi::view_registrar_info(CpiContext::new(
+            program,
+            crate::cpi::accounts::ViewRegistrarInfo {
+                registrar,
+                registrar_stats,
+            },
+        ))?;
+        Ok(view.get())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::VestingInfo;
+    use crate::state::*;
+    use solana_program::program::MAX_RETURN_DATA;
+
+    #[test]
+    fn voter_view_fits_return_data_test() -> Result<()> {
+        let deposit_entry = || DepositEntryInfo {
+            lockup: Lockup::new_from_kind(LockupKind::daily(15), 0, 0).unwrap(),
+            amount_locked: u64::MAX,
+            amount_unlocked: u64::MAX,
+            voting_power: u64::MAX,
+            voting_power_baseline: u64::MAX,
+            reward_weight: u64::MAX,
+            vesting: Some(VestingInfo {
+                rate: u64::MAX,
+                next_timestamp: u64::MAX,
+            }),
+        };
+        let view = VoterView {
+            voting_power: u64::MAX,
+            voting_power_baseline: u64::MAX,
+            reward_weight: u64::MAX,
+            reward_amount: u64::MAX,
+            reward_runway_amount: u64::MAX,
+            reward_runway_secs: Some(u64::MAX),
+            deposit_entry_count: MAX_VOTER_DEPOSIT_ENTRY_COUNT as u8,
+            deposit_entries: (0..VOTER_VIEW_DEPOSIT_ENTRY_COUNT)
+                .map(|_| Some(deposit_entry()))
+                .collect(),
+            node_non_compliant: true,
+        };
+        assert!(view.try_to_vec()?.len() <= MAX_RETURN_DATA);
+
+        Ok(())
+    }
+}
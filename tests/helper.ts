@@ -7,7 +7,7 @@ import { BrushoNftManager } from "../target/types/brusho_nft_manager";
 import { RewardDistributor } from "../target/types/reward_distributor";
 import { assert } from "chai";
 import { createMint, mintTo, getAccount, getMint as __getMint, getOrCreateAssociatedTokenAccount, TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID, createAssociatedTokenAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
-import { MintMaxVoteWeightSource, MintMaxVoteWeightSourceType, withCreateRealm, withCreateTokenOwnerRecord } from "@solana/spl-governance";
+import { GovernanceConfig, MintMaxVoteWeightSource, MintMaxVoteWeightSourceType, Vote, VoteChoice, VoteKind, VoteThreshold, VoteThresholdType, VoteTipping, VoteType, withCastVote, withCreateGovernance, withCreateProposal, withCreateRealm, withCreateTokenOwnerRecord, withDepositGoverningTokens, withSignOffProposal } from "@solana/spl-governance";
 
 // Configure the client to use the local cluster.
 anchor.setProvider(anchor.AnchorProvider.env());
@@ -201,6 +201,100 @@ export async function createTokenOwnerRecord(realm: PublicKey, governingTokenMin
   return tokenOwnerRecord;
 }
 
+// Casts a vote on a new proposal with tokens deposited into the governance,
+// leaving the vote unrelinquished on the voter's token owner record.
+export async function castVote(realm: PublicKey, governingTokenMint: PublicKey, mintAuthority: Keypair, voterAuthority: Keypair, tokenOwnerRecord: PublicKey): Promise<PublicKey> {
+  const amount = new anchor.BN(1e10);
+  const governingTokenSource = await mintTokenToWallet(governingTokenMint, mintAuthority, voterAuthority.publicKey, amount);
+
+  let instructions: TransactionInstruction[] = [];
+  await withDepositGoverningTokens(
+    instructions,
+    GOV_PROGRAM_ID,
+    3,
+    realm,
+    governingTokenSource,
+    governingTokenMint,
+    voterAuthority.publicKey,
+    voterAuthority.publicKey,
+    voterAuthority.publicKey,
+    amount,
+  );
+  const config = new GovernanceConfig({
+    communityVoteThreshold: new VoteThreshold({ type: VoteThresholdType.YesVotePercentage, value: 60 }),
+    minCommunityTokensToCreateProposal: new anchor.BN(1),
+    minInstructionHoldUpTime: 0,
+    baseVotingTime: SECS_PER_DAY.toNumber(),
+    communityVoteTipping: VoteTipping.Disabled,
+    minCouncilTokensToCreateProposal: new anchor.BN(1),
+    councilVoteThreshold: new VoteThreshold({ type: VoteThresholdType.Disabled }),
+    councilVetoVoteThreshold: new VoteThreshold({ type: VoteThresholdType.Disabled }),
+    communityVetoVoteThreshold: new VoteThreshold({ type: VoteThresholdType.Disabled }),
+    councilVoteTipping: VoteTipping.Disabled,
+    votingCoolOffTime: 0,
+    depositExemptProposalCount: 10,
+  });
+  const governance = await withCreateGovernance(
+    instructions,
+    GOV_PROGRAM_ID,
+    3,
+    realm,
+    undefined,
+    config,
+    tokenOwnerRecord,
+    voterAuthority.publicKey,
+    voterAuthority.publicKey,
+  );
+  await sendAndConfirmTransaction(CONNECTION, new Transaction().add(...instructions), [voterAuthority]);
+
+  instructions = [];
+  const proposal = await withCreateProposal(
+    instructions,
+    GOV_PROGRAM_ID,
+    3,
+    realm,
+    governance,
+    tokenOwnerRecord,
+    `Proposal ${new Date().valueOf()}`,
+    "",
+    governingTokenMint,
+    voterAuthority.publicKey,
+    0,
+    VoteType.SINGLE_CHOICE,
+    ["Approve"],
+    true,
+    voterAuthority.publicKey,
+  );
+  withSignOffProposal(
+    instructions,
+    GOV_PROGRAM_ID,
+    3,
+    realm,
+    governance,
+    proposal,
+    voterAuthority.publicKey,
+    undefined,
+    tokenOwnerRecord,
+  );
+  await withCastVote(
+    instructions,
+    GOV_PROGRAM_ID,
+    3,
+    realm,
+    governance,
+    proposal,
+    tokenOwnerRecord,
+    tokenOwnerRecord,
+    voterAuthority.publicKey,
+    governingTokenMint,
+    new Vote({ voteType: VoteKind.Approve, approveChoices: [new VoteChoice({ rank: 0, weightPercentage: 100 })], deny: undefined, veto: undefined }),
+    voterAuthority.publicKey,
+  );
+  await sendAndConfirmTransaction(CONNECTION, new Transaction().add(...instructions), [voterAuthority]);
+
+  return proposal;
+}
+
 export async function createRegistrar(
   realm: PublicKey,
   realmAuthority: Keypair,
@@ -209,6 +303,7 @@ export async function createRegistrar(
   depositConfig: DepositConfig,
   circuit_breaker_threshold: anchor.BN,
   payer: Keypair,
+  emissionConfig: EmissionConfig = defaultEmissionConfig(),
 ): Promise<[PublicKey, number, PublicKey, PublicKey, PublicKey]> {
   const registrarSeeds = [realm.toBytes(), Buffer.from("registrar"), governingTokenMint.toBytes()];
   const [registrar, registrarBump] = anchor.web3.PublicKey.findProgramAddressSync(registrarSeeds, VSR_PROGRAM.programId);
@@ -231,6 +326,7 @@ export async function createRegistrar(
     maxVoterWeightRecordBump,
     votingConfig,
     depositConfig,
+    emissionConfig,
     circuit_breaker_config
   ).accounts({
     registrar,
@@ -275,6 +371,7 @@ export async function createVoter(
     voterAuthority: voterAuthority.publicKey,
     vault,
     voterWeightRecord,
+    registrarStats: registrarStatsAddress(registrar),
     payer: payer.publicKey,
     tokenProgram: TOKEN_PROGRAM_ID,
     associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
@@ -285,6 +382,42 @@ export async function createVoter(
   return [voterAuthority, voter, voterWeightRecord, vault, tokenOwnerRecord];
 }
 
+export function voterWeightHistoryAddress(registrar: PublicKey, voterAuthority: PublicKey): PublicKey {
+  const [history] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("voter-weight-history"), voterAuthority.toBytes()], VSR_PROGRAM.programId);
+  return history;
+}
+
+export async function createVoterWeightHistory(registrar: PublicKey, voter: PublicKey, voterAuthority: PublicKey, payer: Keypair, commitment: Commitment = "confirmed"): Promise<PublicKey> {
+  const voterWeightHistory = voterWeightHistoryAddress(registrar, voterAuthority);
+  await VSR_PROGRAM.methods.createVoterWeightHistory()
+    .accounts({ registrar, voter, voterWeightHistory, payer: payer.publicKey })
+    .signers([payer]).rpc({ commitment });
+  return voterWeightHistory;
+}
+
+// The vote weight as of the latest checkpoint at or before ts, null if ts is
+// before the oldest checkpoint that is still kept, see view_voter_weight_at
+export async function getWeightAt(registrar: PublicKey, voterWeightHistory: PublicKey, ts: anchor.BN): Promise<anchor.BN | null> {
+  return await VSR_PROGRAM.methods.viewVoterWeightAt(ts)
+    .accounts({ registrar, voterWeightHistory })
+    .view();
+}
+
+export function registrarStatsAddress(registrar: PublicKey): PublicKey {
+  const [registrarStats] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("registrar-stats")], VSR_PROGRAM.programId);
+  return registrarStats;
+}
+
+export function registrarConfigAddress(registrar: PublicKey): PublicKey {
+  const [registrarConfig] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("registrar-config")], VSR_PROGRAM.programId);
+  return registrarConfig;
+}
+
+export function nodeAddress(registrar: PublicKey, voterAuthority: PublicKey): PublicKey {
+  const [node] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("node"), voterAuthority.toBytes()], VSR_PROGRAM.programId);
+  return node;
+}
+
 export async function fastup(registrar: PublicKey, realmAuthority: Keypair, seconds: anchor.BN, commitment: Commitment = "processed") {
   const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
   const currTimeOffset = registrarData.timeOffset;
@@ -294,16 +427,90 @@ export async function fastup(registrar: PublicKey, realmAuthority: Keypair, seco
     .signers([realmAuthority]).rpc({ commitment });
 }
 
+export async function fundRewards(registrar: PublicKey, vault: PublicKey, mint: PublicKey, mintAuthority: Keypair, amount: anchor.BN, commitment: Commitment = "confirmed") {
+  const fundingToken = await mintTokenToWallet(mint, mintAuthority, mintAuthority.publicKey, amount);
+
+  await VSR_PROGRAM.methods.fundRewards(amount)
+    .accounts({ registrar, vault, fundingToken, fundingAuthority: mintAuthority.publicKey })
+    .signers([mintAuthority]).rpc({ commitment });
+}
+
+export async function grant(
+  registrar: PublicKey,
+  governingTokenMint: PublicKey,
+  realmAuthority: Keypair,
+  voterAuthority: PublicKey,
+  depositEntryIndex: number,
+  kind: { daily: {} } | { monthly: {} } | { constant: {} },
+  periods: anchor.BN,
+  startTs: anchor.BN | null,
+  amount: anchor.BN,
+  allowClawback: boolean,
+  commitment: Commitment = "confirmed"
+): Promise<[PublicKey, PublicKey, PublicKey]> {
+  const [voter] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("voter"), voterAuthority.toBytes()], VSR_PROGRAM.programId);
+  const [voterWeightRecord] = anchor.web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("voter-weight-record"), voterAuthority.toBytes()], VSR_PROGRAM.programId);
+  const [vault] = anchor.web3.PublicKey.findProgramAddressSync([voter.toBytes(), TOKEN_PROGRAM_ID.toBytes(), governingTokenMint.toBytes()], ASSOCIATED_TOKEN_PROGRAM_ID);
+  const depositToken = await mintTokenToWallet(governingTokenMint, realmAuthority, realmAuthority.publicKey, amount);
+
+  await VSR_PROGRAM.methods.grant(depositEntryIndex, kind, periods, startTs, amount, allowClawback)
+    .accounts({
+      registrar,
+      governingTokenMint,
+      voter,
+      voterAuthority,
+      voterWeightRecord,
+      registrarStats: registrarStatsAddress(registrar),
+      vault,
+      depositToken,
+      realmAuthority: realmAuthority.publicKey,
+      payer: realmAuthority.publicKey,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    }).signers([realmAuthority])
+    .rpc({ commitment });
+
+  return [voter, voterWeightRecord, vault];
+}
+
 export type DepositConfig = {
   ordinaryDepositMinLockupDuration: LockupTimeDuration,
   nodeDepositLockupDuration: LockupTimeDuration,
   nodeSecurityDeposit: anchor.BN,
+  nodeSecurityDepositGracePeriodSecs: anchor.BN,
+}
+
+export type VoteWeightCurvePoint = { lockupBps: number, weightBps: number };
+export type VoteWeightCurve = {
+  kind: { linear: {} } | { piecewiseLinear: {} },
+  pointCount: number,
+  reserved: number[],
+  points: VoteWeightCurvePoint[],
+}
+
+export function linearVoteWeightCurve(): VoteWeightCurve {
+  return piecewiseLinearVoteWeightCurve([], { linear: {} });
+}
+
+export function piecewiseLinearVoteWeightCurve(points: VoteWeightCurvePoint[], kind: VoteWeightCurve["kind"] = { piecewiseLinear: {} }): VoteWeightCurve {
+  const padded = points.slice();
+  while (padded.length < 8) {
+    padded.push({ lockupBps: 0, weightBps: 0 });
+  }
+
+  return {
+    kind,
+    pointCount: points.length,
+    reserved: [0, 0, 0, 0, 0, 0],
+    points: padded,
+  };
 }
 
 export type VotingConfig = {
   baselineVoteWeightScaledFactor: anchor.BN,
   maxExtraLockupVoteWeightScaledFactor: anchor.BN,
   lockupSaturationSecs: anchor.BN,
+  voteWeightCurve: VoteWeightCurve,
 }
 
 export function defaultVotingConfig() {
@@ -311,6 +518,7 @@ export function defaultVotingConfig() {
     baselineVoteWeightScaledFactor: new anchor.BN(1e9),
     maxExtraLockupVoteWeightScaledFactor: new anchor.BN(0),
     lockupSaturationSecs: new anchor.BN(86400),
+    voteWeightCurve: linearVoteWeightCurve(),
   };
 }
 
@@ -319,22 +527,63 @@ export function defaultDepositConfig() {
     ordinaryDepositMinLockupDuration: lockupDayily(15),
     nodeDepositLockupDuration: lockupMonthly(6),
     nodeSecurityDeposit: new anchor.BN(10000 * (1e6)),
+    nodeSecurityDepositGracePeriodSecs: SECS_PER_DAY.muln(7),
+  };
+}
+
+export type EmissionConfig = {
+  totalRewardAmount: anchor.BN,
+  fullRewardPermanentlyLockedFloor: anchor.BN,
+  rotationSecs: number,
+  emissionRateBps: number,
+  reserved: number[],
+}
+
+export function defaultEmissionConfig(): EmissionConfig {
+  return {
+    totalRewardAmount: TOTAL_REWARD_AMOUNT,
+    fullRewardPermanentlyLockedFloor: FULL_REWARD_PERMANENTLY_LOCKED_FLOOR,
+    rotationSecs: SECS_PER_YEAR.toNumber(),
+    emissionRateBps: 1200,
+    reserved: [0, 0],
   };
 }
 
-export type LockupTimeDuration = { periods: anchor.BN, unit: { day: {} } | { month: {} }, filler: number[] };
-export function newLockupTimeDuration(periods: anchor.BN, unit: 'day' | 'month'): LockupTimeDuration {
+export type RewardBoostConfig = {
+  maxExtraLockupRewardWeightScaledFactor: anchor.BN,
+  lockupSaturationSecs: anchor.BN,
+}
+
+export type VoterWeightAction = { castVote: {} } | { commentProposal: {} } | { createGovernance: {} } | { createProposal: {} } | { signOffProposal: {} };
+export type VoterWeightActionRequirement = {
+  minVoterWeight: anchor.BN,
+  minLockupSecs: number,
+  requireNodeDeposit: number,
+  reserved: number[],
+};
+export function newVoterWeightActionRequirement(minVoterWeight: anchor.BN, minLockupSecs: number = 0, requireNodeDeposit: boolean = false): VoterWeightActionRequirement {
+  return { minVoterWeight, minLockupSecs, requireNodeDeposit: requireNodeDeposit ? 1 : 0, reserved: [0, 0, 0] };
+}
+
+export type LockupTimeUnit = 'day' | 'week' | 'month' | 'year';
+export type LockupTimeDuration = { periods: anchor.BN, unit: { day: {} } | { week: {} } | { month: {} } | { year: {} }, calendarMode: number, filler: number[] };
+export function newLockupTimeDuration(periods: anchor.BN, unit: LockupTimeUnit, calendarMode: boolean = false): LockupTimeDuration {
   let _unit;
   if (unit === 'day') {
     _unit = { day: {} };
-  } else {
+  } else if (unit === 'week') {
+    _unit = { week: {} };
+  } else if (unit === 'month') {
     _unit = { month: {} };
+  } else {
+    _unit = { year: {} };
   }
 
   return {
     periods: periods,
     unit: _unit,
-    filler: [0, 0, 0, 0, 0, 0, 0]
+    calendarMode: calendarMode ? 1 : 0,
+    filler: [0, 0, 0, 0, 0, 0]
   };
 }
 
@@ -342,15 +591,29 @@ export function lockupDayily(periods: number): LockupTimeDuration {
   return newLockupTimeDuration(new anchor.BN(periods), 'day');
 }
 
+export function lockupWeekly(periods: number): LockupTimeDuration {
+  return newLockupTimeDuration(new anchor.BN(periods), 'week');
+}
+
 export function lockupMonthly(periods: number): LockupTimeDuration {
   return newLockupTimeDuration(new anchor.BN(periods), 'month');
 }
 
+export function lockupYearly(periods: number): LockupTimeDuration {
+  return newLockupTimeDuration(new anchor.BN(periods), 'year');
+}
+
+// Calendar mode durations have no fixed length, this returns their nominal length.
 export function lockupTimeDurationSeconds(lockupTimeDuration: LockupTimeDuration): anchor.BN {
-  if ((lockupTimeDuration.unit as any).day != undefined) {
+  const unit = lockupTimeDuration.unit as any;
+  if (unit.day != undefined) {
     return SECS_PER_DAY.mul(lockupTimeDuration.periods);
-  } else {
+  } else if (unit.week != undefined) {
+    return SECS_PER_DAY.muln(7).mul(lockupTimeDuration.periods);
+  } else if (unit.month != undefined) {
     return SECS_PER_MONTH.mul(lockupTimeDuration.periods);
+  } else {
+    return SECS_PER_YEAR.mul(lockupTimeDuration.periods);
   }
 }
 
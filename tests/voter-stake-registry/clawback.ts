@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, fastup, getTokenAccount, grant, mintTokenToWallet, newSigner, SECS_PER_DAY, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("clawback!", () => {
+  const grantAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let realmAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let grantee: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let destination: web3.PublicKey;
+
+  before(async () => {
+    realmAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(realmAuthority);
+    [registrar] = await createRegistrar(realm, realmAuthority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), realmAuthority);
+
+    grantee = await newSigner();
+    [voter, voterWeightRecord, vault] = await grant(registrar, mint, realmAuthority, grantee.publicKey, 1, { daily: {} }, new anchor.BN(10), null, grantAmount, true);
+    await grant(registrar, mint, realmAuthority, grantee.publicKey, 2, { daily: {} }, new anchor.BN(10), null, grantAmount, false);
+    destination = await mintTokenToWallet(mint, realmAuthority, realmAuthority.publicKey, new anchor.BN(0));
+  })
+
+  async function clawback(depositEntryIndex: number, authority: web3.Keypair = realmAuthority) {
+    await VSR_PROGRAM.methods.clawback(depositEntryIndex)
+      .accounts({
+        registrar,
+        realmAuthority: authority.publicKey,
+        voter,
+        voterWeightRecord,
+        vault,
+        destination,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function setPaused(paused: number) {
+    await VSR_PROGRAM.methods
+      .setPaused(paused)
+      .accounts({
+        registrar,
+        registrarConfig: null,
+        authority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await clawback(1, await newSigner());
+    });
+  });
+
+  it("without_allow_clawback_should_fail", async () => {
+    await assertThrowsAnchorError('ClawbackNotAllowedOnDeposit', async () => {
+      await clawback(2);
+    });
+  });
+
+  it("while_withdraws_paused_should_fail", async () => {
+    await setPaused(1 << 2);
+    await assertThrowsAnchorError('WithdrawsPaused', async () => {
+      await clawback(1);
+    });
+    await setPaused(0);
+  });
+
+  it("verify_clawback", async () => {
+    // 3 of 10 days vested
+    await fastup(registrar, realmAuthority, SECS_PER_DAY.muln(3), "confirmed");
+
+    const prevDestinationAmount = new anchor.BN((await getTokenAccount(destination)).amount.toString());
+    await clawback(1);
+
+    const expectClawbackAmount = grantAmount.muln(7).divn(10);
+    const destinationAmount = new anchor.BN((await getTokenAccount(destination)).amount.toString());
+    assert.equal(destinationAmount.sub(prevDestinationAmount).toString(), expectClawbackAmount.toString());
+
+    // the vested tokens remain with the grantee and are unlocked
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    const entry = voterData.deposits.at(1);
+    assert.isTrue(entry.isActive == 1);
+    assert.isTrue(entry.allowClawback == 0);
+    assert.equal(entry.amountDepositedNative.toString(), grantAmount.sub(expectClawbackAmount).toString());
+    assert.isTrue(entry.amountInitiallyLockedNative.eqn(0));
+
+    const vaultData = await getTokenAccount(vault);
+    assert.equal(vaultData.amount.toString(), grantAmount.muln(2).sub(expectClawbackAmount).toString());
+
+    // at most once
+    await assertThrowsAnchorError('ClawbackNotAllowedOnDeposit', async () => {
+      await clawback(1);
+    });
+  });
+});
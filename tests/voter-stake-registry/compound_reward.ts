@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, LockupTimeDuration, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, CIRCUIT_BREAKER_PROGRAM, fastup, SECS_PER_DAY, fundRewards, getTokenAccount, registrarStatsAddress } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("compound_reward!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let registrarVault: web3.PublicKey;
+  let circuitBreaker: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let tokenOwnerRecord: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    // create registrar
+    [registrar, , registrarVault, circuitBreaker] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    await fundRewards(registrar, registrarVault, mint, authority, new anchor.BN(1e10));
+    [voterAuthority, voter, voterWeightRecord, vault, tokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    // fastup 1 day
+    await fastup(registrar, authority, SECS_PER_DAY, "confirmed");
+  })
+
+  async function compoundReward(depositEntryIndex: number, amount: anchor.BN | null, duration: LockupTimeDuration, tokenOwnerRecord: web3.PublicKey | null = null): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .compoundReward(depositEntryIndex, amount, duration)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        tokenOwnerRecord,
+        registrarStats: registrarStatsAddress(registrar),
+        rewardVault: registrarVault,
+        circuitBreaker,
+        vault,
+        circuitBreakerProgram: CIRCUIT_BREAKER_PROGRAM.programId,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_node_deposit_entry_index_should_fail", async () => {
+    await assertThrowsAnchorError('NodeDepositReservedEntryIndex', async () => {
+      await compoundReward(0, null, lockupDayily(15));
+    });
+  });
+
+  it("with_too_short_lockup_duration_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidLockupDuration', async () => {
+      await compoundReward(2, null, lockupDayily(1));
+    });
+  });
+
+  it("with_insufficient_claimable_rewards_should_fail", async () => {
+    await assertThrowsAnchorError('InsufficientClaimableRewards', async () => {
+      await compoundReward(2, new anchor.BN(1e12), lockupDayily(15));
+    });
+  });
+
+  it("verify_compound_reward", async () => {
+    const prevVaultAmount = new anchor.BN((await getTokenAccount(vault)).amount.toString());
+
+    await compoundReward(2, null, lockupDayily(30));
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.rewardClaimableAmount.eqn(0));
+
+    const compoundedEntry = voterData.deposits.at(2);
+    assert.isTrue(compoundedEntry.isActive == 1);
+    assert.isTrue(compoundedEntry.amountDepositedNative.gtn(0));
+    assert.isTrue(compoundedEntry.amountDepositedNative.eq(compoundedEntry.amountInitiallyLockedNative));
+    assert.isTrue(compoundedEntry.lockup.kind.kind.constant != undefined);
+    assert.equal(compoundedEntry.lockup.kind.duration.periods.toNumber(), 30);
+
+    // the rewards are moved into the voter's vault
+    const vaultAmount = new anchor.BN((await getTokenAccount(vault)).amount.toString());
+    assert.equal(vaultAmount.sub(prevVaultAmount).toString(), compoundedEntry.amountDepositedNative.toString());
+
+    // the compounded rewards are permanently locked
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), depositAmount.add(compoundedEntry.amountDepositedNative).toString());
+  });
+
+  it("with_restricted_lockup_changes", async () => {
+    await VSR_PROGRAM.methods
+      .updateRestrictLockupChanges(true)
+      .accounts({
+        registrar,
+        realmAuthority: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+    await fastup(registrar, authority, SECS_PER_DAY, "confirmed");
+
+    await assertThrowsAnchorError('TokenOwnerRecordRequired', async () => {
+      await compoundReward(1, null, lockupDayily(30));
+    });
+    await compoundReward(1, null, lockupDayily(30), tokenOwnerRecord);
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.equal(voterData.deposits.at(1).lockup.kind.duration.periods.toNumber(), 30);
+  });
+});
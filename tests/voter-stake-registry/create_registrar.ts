@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { assertThrowsAnchorError, CIRCUIT_BREAKER_PROGRAM, CONNECTION, createRealm, defaultDepositConfig, defaultVotingConfig, DepositConfig, EXP_SCALE, getTokenAccount, GOV_PROGRAM_ID, lockupDayily, lockupMonthly, newMint, newSigner, SECS_PER_DAY, SECS_PER_YEAR, TOTAL_REWARD_AMOUNT, VotingConfig, VSR_PROGRAM } from "../helper";
+import { assertThrowsAnchorError, CIRCUIT_BREAKER_PROGRAM, CONNECTION, createRealm, defaultDepositConfig, defaultEmissionConfig, defaultVotingConfig, DepositConfig, EmissionConfig, EXP_SCALE, getTokenAccount, GOV_PROGRAM_ID, linearVoteWeightCurve, lockupDayily, lockupMonthly, newMint, newSigner, SECS_PER_DAY, TOTAL_REWARD_AMOUNT, VotingConfig, VSR_PROGRAM } from "../helper";
 import { assert } from "chai";
 import { getAccount, getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { getMaxVoterWeightRecord, getVoterWeightRecord } from "@solana/spl-governance";
@@ -20,6 +20,7 @@ async function createRegistrar(
   votingConfig?: VotingConfig,
   depositConfig?: DepositConfig,
   circuit_breaker_threshold?: anchor.BN,
+  emissionConfig?: EmissionConfig,
 ) {
   if (votingConfig == undefined) {
     votingConfig = defaultVotingConfig();
@@ -33,6 +34,10 @@ async function createRegistrar(
     circuit_breaker_threshold = new anchor.BN(1e10);
   }
 
+  if (emissionConfig == undefined) {
+    emissionConfig = defaultEmissionConfig();
+  }
+
   const circuit_breaker_config = {
     windowSizeSeconds: SECS_PER_DAY,
     thresholdType: { absolute: {} },
@@ -44,6 +49,7 @@ async function createRegistrar(
     maxVoterWeightRecordBump,
     votingConfig,
     depositConfig,
+    emissionConfig,
     circuit_breaker_config
   ).accounts({
     registrar,
@@ -121,6 +127,7 @@ describe("create_registrar!", () => {
         baselineVoteWeightScaledFactor: new anchor.BN(1e9),
         maxExtraLockupVoteWeightScaledFactor: new anchor.BN(0),
         lockupSaturationSecs: new anchor.BN(0), // zero value
+        voteWeightCurve: linearVoteWeightCurve(),
       };
 
       await assertThrowsAnchorError('LockupSaturationMustBePositive', async () => {
@@ -146,12 +153,35 @@ describe("create_registrar!", () => {
         ordinaryDepositMinLockupDuration: lockupDayily(15),
         nodeDepositLockupDuration: lockupMonthly(6),
         nodeSecurityDeposit: new anchor.BN(0), // zero value
+        nodeSecurityDepositGracePeriodSecs: new anchor.BN(0),
       };
 
       await assertThrowsAnchorError('NodeSecurityDepositMustBePositive', async () => {
         await createRegistrar(bump, maxVoterWeightRecordBump, registrar, vault, circuitBreaker, maxVoterWeightRecord, realm, mint, realmAuthority, realmAuthority, undefined, depositConfig);
       });
     });
+
+    it("with_zero_emission_rotation_secs_should_fail", async () => {
+      const realmAuthority = await newSigner();
+      let [mint, councilMint, realm] = await createRealm(realmAuthority);
+
+      const seeds = [realm.toBytes(), Buffer.from("registrar"), mint.toBytes()];
+      const [registrar, bump] = anchor.web3.PublicKey.findProgramAddressSync(seeds, VSR_PROGRAM.programId);
+
+      const vault = getAssociatedTokenAddressSync(mint, registrar, true);
+      const circuitBreakerSeeds = [Buffer.from("account_windowed_breaker"), vault.toBytes()];
+      const [circuitBreaker, circuitBreakerBump] = anchor.web3.PublicKey.findProgramAddressSync(circuitBreakerSeeds, CIRCUIT_BREAKER_PROGRAM.programId);
+
+      const maxVoterWeightRecordSeeds = [realm.toBytes(), Buffer.from("max-voter-weight-record"), mint.toBytes()];
+      const [maxVoterWeightRecord, maxVoterWeightRecordBump] = anchor.web3.PublicKey.findProgramAddressSync(maxVoterWeightRecordSeeds, VSR_PROGRAM.programId);
+
+      const emissionConfig = defaultEmissionConfig();
+      emissionConfig.rotationSecs = 0; // zero value
+
+      await assertThrowsAnchorError('InvalidEmissionConfig', async () => {
+        await createRegistrar(bump, maxVoterWeightRecordBump, registrar, vault, circuitBreaker, maxVoterWeightRecord, realm, mint, realmAuthority, realmAuthority, undefined, undefined, undefined, emissionConfig);
+      });
+    });
   });
 
   describe("Realm verification", () => {
@@ -196,10 +226,19 @@ describe("create_registrar!", () => {
       ordinaryDepositMinLockupDuration: lockupDayily(15),
       nodeDepositLockupDuration: lockupMonthly(6),
       nodeSecurityDeposit: new anchor.BN(10000 * (1e6)),
+      nodeSecurityDepositGracePeriodSecs: SECS_PER_DAY.muln(7),
+    };
+
+    const emissionConfig = {
+      totalRewardAmount: TOTAL_REWARD_AMOUNT.divn(2),
+      fullRewardPermanentlyLockedFloor: new anchor.BN("100000000000000"),
+      rotationSecs: SECS_PER_DAY.muln(30).toNumber(),
+      emissionRateBps: 500,
+      reserved: [0, 0],
     };
 
     const circuitBreakerThreshold = new anchor.BN(1e9);
-    const txId = await createRegistrar(bump, maxVoterWeightRecordBump, registrar, vault, circuitBreaker, maxVoterWeightRecord, realm, mint, realmAuthority, realmAuthority, votingConfig, depositConfig, circuitBreakerThreshold);
+    const txId = await createRegistrar(bump, maxVoterWeightRecordBump, registrar, vault, circuitBreaker, maxVoterWeightRecord, realm, mint, realmAuthority, realmAuthority, votingConfig, depositConfig, circuitBreakerThreshold, emissionConfig);
     const tx = await CONNECTION.getTransaction(txId, { commitment: 'confirmed' })
 
     // assert vault has been initialized
@@ -226,7 +265,12 @@ describe("create_registrar!", () => {
     assert.isTrue(registrarData.depositConfig.nodeDepositLockupDuration.unit.month != undefined)
     assert.equal(registrarData.depositConfig.nodeSecurityDeposit.toNumber(), depositConfig.nodeSecurityDeposit.toNumber())
 
-    const expectCurrentRewardAmountPerSecond = TOTAL_REWARD_AMOUNT.muln(12).divn(100).mul(EXP_SCALE).div(SECS_PER_YEAR);
+    assert.equal(registrarData.emissionConfig.totalRewardAmount.toString(), emissionConfig.totalRewardAmount.toString())
+    assert.equal(registrarData.emissionConfig.fullRewardPermanentlyLockedFloor.toString(), emissionConfig.fullRewardPermanentlyLockedFloor.toString())
+    assert.equal(registrarData.emissionConfig.rotationSecs, emissionConfig.rotationSecs)
+    assert.equal(registrarData.emissionConfig.emissionRateBps, emissionConfig.emissionRateBps)
+
+    const expectCurrentRewardAmountPerSecond = emissionConfig.totalRewardAmount.muln(5).divn(100).mul(EXP_SCALE).divn(emissionConfig.rotationSecs);
     assert.equal(registrarData.currentRewardAmountPerSecond.toString(), expectCurrentRewardAmountPerSecond.toString());
     assert.equal(registrarData.lastRewardAmountPerSecondRotatedTs.toString(), tx.blockTime.toString());
     assert.equal(registrarData.rewardAccrualTs.toString(), tx.blockTime.toString());
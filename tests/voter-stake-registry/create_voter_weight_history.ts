@@ -0,0 +1,231 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, createVoterWeightHistory, defaultDepositConfig, defaultVotingConfig, fastup, getWeightAt, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, SECS_PER_DAY, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("create_voter_weight_history!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+  })
+
+  async function deposit(depositEntryIndex: number, voterWeightHistory: web3.PublicKey | null) {
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        voterWeightHistory,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("verify_voter_weight_history", async () => {
+    await deposit(1, null);
+
+    const voterWeightHistory = await createVoterWeightHistory(registrar, voter, voterAuthority.publicKey, authority);
+    let history = await VSR_PROGRAM.account.voterWeightHistory.fetch(voterWeightHistory);
+    assert.equal(history.registrar.toBase58(), registrar.toBase58());
+    assert.equal(history.voterAuthority.toBase58(), voterAuthority.publicKey.toBase58());
+    assert.equal(history.checkpointCount.toNumber(), 1);
+    const first = history.checkpoints[0];
+    assert.equal(first.weight.toString(), depositAmount.toString());
+    assert.equal(first.permanentlyLocked.toString(), depositAmount.toString());
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter);
+    assert.equal(voterData.hasWeightHistory, 1);
+
+    await fastup(registrar, authority, SECS_PER_DAY);
+    await deposit(2, voterWeightHistory);
+
+    history = await VSR_PROGRAM.account.voterWeightHistory.fetch(voterWeightHistory);
+    assert.equal(history.checkpointCount.toNumber(), 2);
+    const second = history.checkpoints[1];
+    assert.isTrue(second.ts.gt(first.ts));
+    assert.equal(second.weight.toString(), depositAmount.muln(2).toString());
+
+    assert.isNull(await getWeightAt(registrar, voterWeightHistory, first.ts.subn(1)));
+    assert.equal((await getWeightAt(registrar, voterWeightHistory, first.ts)).toString(), depositAmount.toString());
+    assert.equal((await getWeightAt(registrar, voterWeightHistory, second.ts.subn(1))).toString(), depositAmount.toString());
+    assert.equal((await getWeightAt(registrar, voterWeightHistory, second.ts)).toString(), depositAmount.muln(2).toString());
+  });
+
+  it("without_history_once_created_should_fail", async () => {
+    await assertThrowsAnchorError('VoterWeightHistoryRequired', async () => {
+      await deposit(3, null);
+    }, undefined, false);
+  });
+
+  it("with_history_of_another_voter_should_fail", async () => {
+    const [otherVoterAuthority, otherVoter] = await createVoter(realm, mint, registrar, authority);
+    const otherVoterWeightHistory = await createVoterWeightHistory(registrar, otherVoter, otherVoterAuthority.publicKey, authority);
+
+    await assertThrowsAnchorError('InvalidVoterWeightHistory', async () => {
+      await deposit(3, otherVoterWeightHistory);
+    }, undefined, false);
+  });
+
+  it("delegate_records_delegated_weight", async () => {
+    const [delegatorAuthority, delegator, delegatorVoterWeightRecord, delegatorVault, delegatorTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    const [delegateAuthority, delegate, delegateVoterWeightRecord, , delegateTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    const delegateVoterWeightHistory = await createVoterWeightHistory(registrar, delegate, delegateAuthority.publicKey, authority);
+    const [delegationRecord] = web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("delegation-record"), delegatorAuthority.publicKey.toBytes()], VSR_PROGRAM.programId);
+
+    const delegatorDepositToken = await newTokenAccount(mint, delegatorAuthority);
+    await mintTokenToAccount(mint, authority, delegatorDepositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter: delegator,
+        vault: delegatorVault,
+        depositToken: delegatorDepositToken,
+        depositAuthority: delegatorAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    const delegateAccounts = {
+      registrar,
+      voter: delegator,
+      voterAuthority: delegatorAuthority.publicKey,
+      tokenOwnerRecord: delegatorTokenOwnerRecord,
+      voterWeightRecord: delegatorVoterWeightRecord,
+      delegateVoter: delegate,
+      delegationRecord,
+      payer: delegatorAuthority.publicKey,
+    };
+    await assertThrowsAnchorError('VoterWeightHistoryRequired', async () => {
+      await VSR_PROGRAM.methods.delegate().accounts(delegateAccounts).signers([delegatorAuthority]).rpc();
+    }, undefined, false);
+
+    await fastup(registrar, authority, SECS_PER_DAY);
+    await VSR_PROGRAM.methods
+      .delegate()
+      .accounts({ ...delegateAccounts, delegateVoterWeightHistory })
+      .signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    let history = await VSR_PROGRAM.account.voterWeightHistory.fetch(delegateVoterWeightHistory, "confirmed");
+    assert.equal(history.checkpointCount.toNumber(), 2);
+    const delegated = history.checkpoints[1];
+    assert.equal(delegated.weight.toNumber(), 0);
+    assert.equal(delegated.delegatedWeight.toString(), depositAmount.toString());
+    assert.equal((await getWeightAt(registrar, delegateVoterWeightHistory, delegated.ts)).toString(), depositAmount.toString());
+    const delegationRecordData = await VSR_PROGRAM.account.delegationRecord.fetch(delegationRecord, "confirmed");
+    assert.equal(delegationRecordData.delegatedWeight.toString(), depositAmount.toString());
+    assert.equal(delegationRecordData.countedInWeightHistory, 1);
+
+    await fastup(registrar, authority, SECS_PER_DAY);
+    await VSR_PROGRAM.methods
+      .undelegate()
+      .accounts({
+        registrar,
+        voter: delegator,
+        voterAuthority: delegatorAuthority.publicKey,
+        delegationRecord,
+        delegateVoter: delegate,
+        delegateTokenOwnerRecord,
+        delegateVoterWeightRecord,
+        delegateVoterWeightHistory,
+        solDestination: delegatorAuthority.publicKey,
+      })
+      .signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    history = await VSR_PROGRAM.account.voterWeightHistory.fetch(delegateVoterWeightHistory, "confirmed");
+    assert.equal(history.checkpointCount.toNumber(), 3);
+    const undelegated = history.checkpoints[2];
+    assert.equal(undelegated.delegatedWeight.toNumber(), 0);
+    assert.equal((await getWeightAt(registrar, delegateVoterWeightHistory, undelegated.ts)).toNumber(), 0);
+    assert.equal((await getWeightAt(registrar, delegateVoterWeightHistory, undelegated.ts.subn(1))).toString(), depositAmount.toString());
+  });
+
+  it("undelegate_from_before_the_history_keeps_delegated_weight", async () => {
+    const [delegateAuthority, delegate, delegateVoterWeightRecord, , delegateTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+
+    async function delegateFrom(delegateVoterWeightHistory: web3.PublicKey | null): Promise<[web3.Keypair, web3.PublicKey, web3.PublicKey]> {
+      const [delegatorAuthority, delegator, delegatorVoterWeightRecord, delegatorVault, delegatorTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+      const [delegationRecord] = web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("delegation-record"), delegatorAuthority.publicKey.toBytes()], VSR_PROGRAM.programId);
+      const delegatorDepositToken = await newTokenAccount(mint, delegatorAuthority);
+      await mintTokenToAccount(mint, authority, delegatorDepositToken, depositAmount)
+      await VSR_PROGRAM.methods
+        .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+        .accounts({
+          registrar,
+          voter: delegator,
+          vault: delegatorVault,
+          depositToken: delegatorDepositToken,
+          depositAuthority: delegatorAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID
+        }).signers([delegatorAuthority])
+        .rpc({ commitment: "confirmed" });
+      await VSR_PROGRAM.methods
+        .delegate()
+        .accounts({
+          registrar,
+          voter: delegator,
+          voterAuthority: delegatorAuthority.publicKey,
+          tokenOwnerRecord: delegatorTokenOwnerRecord,
+          voterWeightRecord: delegatorVoterWeightRecord,
+          delegateVoter: delegate,
+          delegateVoterWeightHistory,
+          delegationRecord,
+          payer: delegatorAuthority.publicKey,
+        })
+        .signers([delegatorAuthority])
+        .rpc({ commitment: "confirmed" });
+      return [delegatorAuthority, delegator, delegationRecord];
+    }
+
+    // delegated before the delegate had a history
+    const [delegatorAuthority, delegator, delegationRecord] = await delegateFrom(null);
+    const delegateVoterWeightHistory = await createVoterWeightHistory(registrar, delegate, delegateAuthority.publicKey, authority);
+    await delegateFrom(delegateVoterWeightHistory);
+    const delegationRecordData = await VSR_PROGRAM.account.delegationRecord.fetch(delegationRecord, "confirmed");
+    assert.equal(delegationRecordData.countedInWeightHistory, 0);
+
+    await fastup(registrar, authority, SECS_PER_DAY);
+    await VSR_PROGRAM.methods
+      .undelegate()
+      .accounts({
+        registrar,
+        voter: delegator,
+        voterAuthority: delegatorAuthority.publicKey,
+        delegationRecord,
+        delegateVoter: delegate,
+        delegateTokenOwnerRecord,
+        delegateVoterWeightRecord,
+        delegateVoterWeightHistory,
+        solDestination: delegatorAuthority.publicKey,
+      })
+      .signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    // the history keeps the weight of the delegation it counted
+    const history = await VSR_PROGRAM.account.voterWeightHistory.fetch(delegateVoterWeightHistory, "confirmed");
+    const undelegated = history.checkpoints[history.checkpointCount.toNumber() - 1];
+    assert.equal(undelegated.delegatedWeight.toString(), depositAmount.toString());
+  });
+});
@@ -0,0 +1,339 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, assertThrowsSendTransactionError, castVote, CONNECTION, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, fastup, lockupDayily, LockupTimeDuration, mintTokenToAccount, newTokenAccount, newSigner, registrarConfigAddress, registrarStatsAddress, SECS_PER_DAY, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { getVoterWeightRecord } from "@solana/spl-governance";
+import { assert } from "chai";
+
+describe("delegate!", () => {
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let tokenOwnerRecord: web3.PublicKey;
+
+  let delegateVoterAuthority: web3.Keypair;
+  let delegateVoter: web3.PublicKey;
+  let delegateVoterWeightRecord: web3.PublicKey;
+  let delegateVault: web3.PublicKey;
+  let delegateTokenOwnerRecord: web3.PublicKey;
+
+  let delegationRecord: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    // create registrar
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, voterWeightRecord, vault, tokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    [delegateVoterAuthority, delegateVoter, delegateVoterWeightRecord, delegateVault, delegateTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+
+    await deposit(voterAuthority, voter, vault, 1, lockupDayily(15), new anchor.BN(1e9));
+    await deposit(delegateVoterAuthority, delegateVoter, delegateVault, 1, lockupDayily(15), new anchor.BN(2e9));
+
+    const delegationRecordSeeds = [registrar.toBytes(), Buffer.from("delegation-record"), voterAuthority.publicKey.toBytes()];
+    [delegationRecord] = web3.PublicKey.findProgramAddressSync(delegationRecordSeeds, VSR_PROGRAM.programId);
+  })
+
+  async function deposit(depositAuthority: web3.Keypair, voter: web3.PublicKey, vault: web3.PublicKey, depositEntryIndex: number, duration: LockupTimeDuration, amount: anchor.BN) {
+    const depositToken = await newTokenAccount(mint, depositAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, amount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, amount, duration)
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: depositAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([depositAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function updateVoterWeightRecord(voter: web3.PublicKey, voterWeightRecord: web3.PublicKey, remainingAccounts: web3.AccountMeta[] = []) {
+    await VSR_PROGRAM.methods
+      .updateVoterWeightRecord(null, null)
+      .accounts({
+        registrar,
+        voter,
+        voterWeightRecord,
+      })
+      .remainingAccounts(remainingAccounts)
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("delegate_to_self_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidDelegate', async () => {
+      await VSR_PROGRAM.methods
+        .delegate()
+        .accounts({
+          registrar,
+          voter,
+          voterAuthority: voterAuthority.publicKey,
+          tokenOwnerRecord,
+          voterWeightRecord,
+          delegateVoter: voter,
+          delegationRecord,
+          payer: voterAuthority.publicKey,
+        })
+        .signers([voterAuthority])
+        .rpc()
+    })
+  });
+
+  it("delegate", async () => {
+    await VSR_PROGRAM.methods
+      .delegate()
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        tokenOwnerRecord,
+        voterWeightRecord,
+        delegateVoter,
+        delegationRecord,
+        payer: voterAuthority.publicKey,
+      })
+      .signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    const delegationRecordData = await VSR_PROGRAM.account.delegationRecord.fetch(delegationRecord);
+    assert.equal(delegationRecordData.registrar.toBase58(), registrar.toBase58());
+    assert.equal(delegationRecordData.delegator.toBase58(), voterAuthority.publicKey.toBase58());
+    assert.equal(delegationRecordData.delegate.toBase58(), delegateVoterAuthority.publicKey.toBase58());
+    let delegateVoterData = await VSR_PROGRAM.account.voter.fetch(delegateVoter);
+    assert.equal(delegateVoterData.delegatorCount.toNumber(), 1);
+
+    // the delegator can not vote with the delegated weight
+    await updateVoterWeightRecord(voter, voterWeightRecord);
+    let voterWeightRecordData = await getVoterWeightRecord(CONNECTION, voterWeightRecord);
+    assert.isTrue(voterWeightRecordData.account.voterWeight.eqn(0));
+
+    // the delegate votes with its own weight plus the delegated weight
+    await updateVoterWeightRecord(delegateVoter, delegateVoterWeightRecord, [
+      { pubkey: delegationRecord, isSigner: false, isWritable: false },
+      { pubkey: voter, isSigner: false, isWritable: false },
+    ]);
+    voterWeightRecordData = await getVoterWeightRecord(CONNECTION, delegateVoterWeightRecord);
+    assert.equal(voterWeightRecordData.account.voterWeight.toString(), new anchor.BN(3e9).toString());
+
+    // the same delegator can not be counted twice
+    await assertThrowsAnchorError('UnsortedDelegators', async () => {
+      await updateVoterWeightRecord(delegateVoter, delegateVoterWeightRecord, [
+        { pubkey: delegationRecord, isSigner: false, isWritable: false },
+        { pubkey: voter, isSigner: false, isWritable: false },
+        { pubkey: delegationRecord, isSigner: false, isWritable: false },
+        { pubkey: voter, isSigner: false, isWritable: false },
+      ]);
+    })
+  });
+
+  it("delegate_with_delegators_should_fail", async () => {
+    const [delegatorDelegationRecord] = web3.PublicKey.findProgramAddressSync(
+      [registrar.toBytes(), Buffer.from("delegation-record"), delegateVoterAuthority.publicKey.toBytes()],
+      VSR_PROGRAM.programId
+    );
+    await assertThrowsAnchorError('VoterHasDelegators', async () => {
+      await VSR_PROGRAM.methods
+        .delegate()
+        .accounts({
+          registrar,
+          voter: delegateVoter,
+          voterAuthority: delegateVoterAuthority.publicKey,
+          tokenOwnerRecord: delegateTokenOwnerRecord,
+          voterWeightRecord: delegateVoterWeightRecord,
+          delegateVoter: voter,
+          delegationRecord: delegatorDelegationRecord,
+          payer: delegateVoterAuthority.publicKey,
+        })
+        .signers([delegateVoterAuthority])
+        .rpc()
+    })
+  });
+
+  it("withdraw_while_delegated_should_fail", async () => {
+    await assertThrowsAnchorError('VoterDelegated', async () => {
+      await VSR_PROGRAM.methods
+        .withdraw(1, new anchor.BN(1))
+        .accounts({
+          registrar,
+          voter,
+          voterAuthority: voterAuthority.publicKey,
+          tokenOwnerRecord,
+          voterWeightRecord,
+          vault,
+          destination: await newTokenAccount(mint, voterAuthority),
+          tokenProgram: TOKEN_PROGRAM_ID
+        })
+        .signers([voterAuthority])
+        .rpc()
+    })
+  });
+
+  it("undelegate", async () => {
+    await VSR_PROGRAM.methods
+      .undelegate()
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        delegationRecord,
+        delegateVoter,
+        delegateTokenOwnerRecord,
+        delegateVoterWeightRecord,
+        solDestination: voterAuthority.publicKey,
+      })
+      .signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    assert.isTrue(await CONNECTION.getAccountInfo(delegationRecord, "confirmed") == null);
+    const delegateVoterData = await VSR_PROGRAM.account.voter.fetch(delegateVoter, "confirmed");
+    assert.equal(delegateVoterData.delegatorCount.toNumber(), 0);
+
+    // the delegate's voter weight record only contains its own weight
+    let voterWeightRecordData = await getVoterWeightRecord(CONNECTION, delegateVoterWeightRecord);
+    assert.equal(voterWeightRecordData.account.voterWeight.toString(), new anchor.BN(2e9).toString());
+
+    // the voter votes with its own weight again
+    await updateVoterWeightRecord(voter, voterWeightRecord);
+    voterWeightRecordData = await getVoterWeightRecord(CONNECTION, voterWeightRecord);
+    assert.equal(voterWeightRecordData.account.voterWeight.toString(), new anchor.BN(1e9).toString());
+  });
+
+  it("close_voter_with_delegators_should_fail", async () => {
+    const [emptyVoterAuthority, emptyVoter] = await createVoter(realm, mint, registrar, authority);
+    await VSR_PROGRAM.methods
+      .delegate()
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        tokenOwnerRecord,
+        voterWeightRecord,
+        delegateVoter: emptyVoter,
+        delegationRecord,
+        payer: voterAuthority.publicKey,
+      })
+      .signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    await assertThrowsAnchorError('VoterHasDelegators', async () => {
+      await VSR_PROGRAM.methods
+        .closeVoter()
+        .accounts({
+          registrar,
+          voter: emptyVoter,
+          voterAuthority: emptyVoterAuthority.publicKey,
+          registrarStats: registrarStatsAddress(registrar),
+          solDestination: emptyVoterAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([emptyVoterAuthority])
+        .rpc()
+    })
+  });
+
+  it("request_undelegate_while_delegate_votes", async () => {
+    const [delegatorAuthority, delegator, delegatorVoterWeightRecord, delegatorVault, delegatorTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    const [votingDelegateAuthority, votingDelegate, votingDelegateVoterWeightRecord, , votingDelegateTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    await deposit(delegatorAuthority, delegator, delegatorVault, 1, lockupDayily(15), new anchor.BN(1e9));
+    const [delegatorDelegationRecord] = web3.PublicKey.findProgramAddressSync(
+      [registrar.toBytes(), Buffer.from("delegation-record"), delegatorAuthority.publicKey.toBytes()],
+      VSR_PROGRAM.programId
+    );
+    await VSR_PROGRAM.methods
+      .delegate()
+      .accounts({
+        registrar,
+        voter: delegator,
+        voterAuthority: delegatorAuthority.publicKey,
+        tokenOwnerRecord: delegatorTokenOwnerRecord,
+        voterWeightRecord: delegatorVoterWeightRecord,
+        delegateVoter: votingDelegate,
+        delegationRecord: delegatorDelegationRecord,
+        payer: delegatorAuthority.publicKey,
+      })
+      .signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    // the delegate never relinquishes its vote
+    await castVote(realm, mint, authority, votingDelegateAuthority, votingDelegateTokenOwnerRecord);
+
+    const maxVotingTimeSecs = SECS_PER_DAY.muln(3);
+    const registrarConfig = registrarConfigAddress(registrar);
+    await VSR_PROGRAM.methods
+      .updateMaxVotingTime(maxVotingTimeSecs.toNumber())
+      .accounts({
+        registrar,
+        registrarConfig,
+        realmAuthority: authority.publicKey,
+        payer: authority.publicKey,
+      })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    const undelegate = async () => {
+      await VSR_PROGRAM.methods
+        .undelegate()
+        .accounts({
+          registrar,
+          voter: delegator,
+          voterAuthority: delegatorAuthority.publicKey,
+          voterWeightHistory: null,
+          delegationRecord: delegatorDelegationRecord,
+          delegateVoter: votingDelegate,
+          delegateTokenOwnerRecord: votingDelegateTokenOwnerRecord,
+          delegateVoterWeightRecord: votingDelegateVoterWeightRecord,
+          delegateVoterWeightHistory: null,
+          registrarConfig,
+          solDestination: delegatorAuthority.publicKey,
+        })
+        .signers([delegatorAuthority])
+        .rpc({ commitment: "confirmed" });
+    };
+
+    // governance forbids undelegating while the delegate's vote is unrelinquished
+    await assertThrowsSendTransactionError('custom program error: 0x1fa', undelegate);
+
+    await VSR_PROGRAM.methods
+      .requestUndelegate()
+      .accounts({
+        registrar,
+        voter: delegator,
+        voterAuthority: delegatorAuthority.publicKey,
+        delegationRecord: delegatorDelegationRecord,
+        delegateVoter: votingDelegate,
+        delegateVoterWeightHistory: null,
+      })
+      .signers([delegatorAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    // the delegate can no longer vote with the delegated weight
+    await assertThrowsAnchorError('UndelegationRequested', async () => {
+      await updateVoterWeightRecord(votingDelegate, votingDelegateVoterWeightRecord, [
+        { pubkey: delegatorDelegationRecord, isSigner: false, isWritable: false },
+        { pubkey: delegator, isSigner: false, isWritable: false },
+      ]);
+    });
+
+    // a vote cast with the delegated weight may still be ongoing
+    await fastup(registrar, authority, maxVotingTimeSecs.subn(60), "confirmed");
+    await assertThrowsSendTransactionError('custom program error: 0x1fa', undelegate);
+
+    // the delegate can not block undelegating forever
+    await fastup(registrar, authority, new anchor.BN(60), "confirmed");
+    await undelegate();
+
+    assert.isTrue(await CONNECTION.getAccountInfo(delegatorDelegationRecord, "confirmed") == null);
+    const votingDelegateData = await VSR_PROGRAM.account.voter.fetch(votingDelegate, "confirmed");
+    assert.equal(votingDelegateData.delegatorCount.toNumber(), 0);
+  });
+});
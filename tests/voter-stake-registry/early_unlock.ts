@@ -0,0 +1,142 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, getTokenAccount, lockupDayily, LockupTimeDuration, lockupTimeDurationSeconds, mintTokenToAccount, newSigner, newTokenAccount, SECS_PER_YEAR, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("early_unlock!", () => {
+  const earlyUnlockPenaltyConfig = {
+    minPenaltyBps: 1_000,
+    maxPenaltyBps: 5_000,
+    curveExponent: 1,
+    reserved: [0, 0, 0],
+    saturationSecs: SECS_PER_YEAR,
+  };
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let rewardVault: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let tokenOwnerRecord: web3.PublicKey;
+  let voterTokenAccount: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar, , rewardVault] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, voterWeightRecord, vault, tokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    voterTokenAccount = await newTokenAccount(mint, voterAuthority);
+  })
+
+  async function deposit(depositEntryIndex: number, duration: LockupTimeDuration, amount: anchor.BN) {
+    await mintTokenToAccount(mint, authority, voterTokenAccount, amount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, amount, duration)
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken: voterTokenAccount,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function earlyUnlock(depositEntryIndex: number, amount: anchor.BN): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .earlyUnlock(depositEntryIndex, amount)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        tokenOwnerRecord,
+        voterWeightRecord,
+        vault,
+        rewardVault,
+        destination: voterTokenAccount,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_early_unlock_disabled_should_fail", async () => {
+    await deposit(1, lockupDayily(180), new anchor.BN(1e9));
+
+    await assertThrowsAnchorError('EarlyUnlockDisabled', async () => {
+      await earlyUnlock(1, new anchor.BN(1e8));
+    });
+  });
+
+  it("with_invalid_penalty_config_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidEarlyUnlockPenaltyConfig', async () => {
+      await VSR_PROGRAM.methods
+        .updateEarlyUnlockPenaltyConfig({ ...earlyUnlockPenaltyConfig, maxPenaltyBps: 10_001 })
+        .accounts({ registrar, realmAuthority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+    });
+
+    await VSR_PROGRAM.methods
+      .updateEarlyUnlockPenaltyConfig(earlyUnlockPenaltyConfig)
+      .accounts({ registrar, realmAuthority: authority.publicKey })
+      .signers([authority])
+      .rpc({ commitment: "confirmed" });
+  });
+
+  it("with_node_deposit_entry_index_should_fail", async () => {
+    await assertThrowsAnchorError('NodeDepositReservedEntryIndex', async () => {
+      await earlyUnlock(0, new anchor.BN(1e8));
+    });
+  });
+
+  it("with_insufficient_locked_tokens_should_fail", async () => {
+    await assertThrowsAnchorError('InsufficientLockedTokens', async () => {
+      await earlyUnlock(1, new anchor.BN(1e9).addn(1));
+    });
+  });
+
+  it("verify_early_unlock", async () => {
+    const amount = new anchor.BN(4e8);
+    const secsLeft = lockupTimeDurationSeconds(lockupDayily(180));
+    const penaltyBps = new anchor.BN(earlyUnlockPenaltyConfig.minPenaltyBps).add(
+      new anchor.BN(earlyUnlockPenaltyConfig.maxPenaltyBps - earlyUnlockPenaltyConfig.minPenaltyBps).mul(secsLeft).div(SECS_PER_YEAR)
+    );
+    const penalty = amount.mul(penaltyBps).divn(10_000);
+
+    const oldRegistrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    const oldTokenBalance = (await getTokenAccount(voterTokenAccount)).amount;
+    const oldRewardVaultBalance = (await getTokenAccount(rewardVault)).amount;
+
+    await earlyUnlock(1, amount);
+
+    const newTokenBalance = (await getTokenAccount(voterTokenAccount)).amount;
+    const newRewardVaultBalance = (await getTokenAccount(rewardVault)).amount;
+    assert.equal((newTokenBalance - oldTokenBalance).toString(), amount.sub(penalty).toString());
+    assert.equal((newRewardVaultBalance - oldRewardVaultBalance).toString(), penalty.toString());
+
+    // the rest stays locked up
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.deposits[1].isActive == 1);
+    assert.equal(voterData.deposits[1].amountDepositedNative.toString(), new anchor.BN(1e9).sub(amount).toString());
+    assert.isTrue(voterData.deposits[1].lockup.kind.kind.constant != undefined);
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), oldRegistrarData.permanentlyLockedAmount.sub(amount).toString());
+    assert.equal(registrarData.fundedRewardAmount.toString(), oldRegistrarData.fundedRewardAmount.add(penalty).toString());
+  });
+
+  it("verify_early_unlock_all", async () => {
+    await earlyUnlock(1, new anchor.BN(6e8));
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.deposits[1].isActive == 0);
+  });
+});
@@ -0,0 +1,84 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, CONNECTION, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, getTokenAccount, grant, newSigner, VSR_PROGRAM } from "../helper";
+import { getVoterWeightRecord } from "@solana/spl-governance";
+import { assert } from "chai";
+
+describe("grant!", () => {
+  const grantAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let realmAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+
+  before(async () => {
+    realmAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(realmAuthority);
+    [registrar] = await createRegistrar(realm, realmAuthority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), realmAuthority);
+  })
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const invalidRealmAuthority = await newSigner();
+    const grantee = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await grant(registrar, mint, invalidRealmAuthority, grantee.publicKey, 1, { daily: {} }, new anchor.BN(10), null, grantAmount, true);
+    });
+  });
+
+  it("with_constant_lockup_should_fail", async () => {
+    const grantee = await newSigner();
+    await assertThrowsAnchorError('InvalidLockupKind', async () => {
+      await grant(registrar, mint, realmAuthority, grantee.publicKey, 1, { constant: {} }, new anchor.BN(10), null, grantAmount, true);
+    });
+  });
+
+  it("with_node_deposit_entry_index_should_fail", async () => {
+    const grantee = await newSigner();
+    await assertThrowsAnchorError('NodeDepositReservedEntryIndex', async () => {
+      await grant(registrar, mint, realmAuthority, grantee.publicKey, 0, { daily: {} }, new anchor.BN(10), null, grantAmount, true);
+    });
+  });
+
+  it("grant_to_new_voter", async () => {
+    const grantee = await newSigner();
+    const [voter, voterWeightRecord, vault] = await grant(registrar, mint, realmAuthority, grantee.publicKey, 1, { monthly: {} }, new anchor.BN(12), null, grantAmount, true);
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.equal(voterData.voterAuthority.toBase58(), grantee.publicKey.toBase58());
+    assert.equal(voterData.registrar.toBase58(), registrar.toBase58());
+
+    const entry = voterData.deposits.at(1);
+    assert.isTrue(entry.isActive == 1);
+    assert.isTrue(entry.allowClawback == 1);
+    assert.isTrue(entry.lockup.kind.kind.monthly != undefined);
+    assert.equal(entry.lockup.kind.duration.periods.toNumber(), 12);
+    assert.equal(entry.amountDepositedNative.toString(), grantAmount.toString());
+    assert.equal(entry.amountInitiallyLockedNative.toString(), grantAmount.toString());
+
+    const vaultData = await getTokenAccount(vault);
+    assert.equal(vaultData.amount.toString(), grantAmount.toString());
+
+    const voterWeightRecordData = await getVoterWeightRecord(CONNECTION, voterWeightRecord);
+    assert.equal(voterWeightRecordData.account.governingTokenOwner.toBase58(), grantee.publicKey.toBase58());
+
+    // the same entry can not be granted twice
+    await assertThrowsAnchorError('ActiveDepositEntryIndex', async () => {
+      await grant(registrar, mint, realmAuthority, grantee.publicKey, 1, { monthly: {} }, new anchor.BN(12), null, grantAmount, true);
+    });
+  });
+
+  it("grant_to_existing_voter", async () => {
+    const [voterAuthority, voter] = await createVoter(realm, mint, registrar, realmAuthority);
+    await grant(registrar, mint, realmAuthority, voterAuthority.publicKey, 2, { daily: {} }, new anchor.BN(30), null, grantAmount, false);
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    const entry = voterData.deposits.at(2);
+    assert.isTrue(entry.isActive == 1);
+    assert.isTrue(entry.allowClawback == 0);
+    assert.isTrue(entry.lockup.kind.kind.daily != undefined);
+    assert.equal(entry.amountDepositedNative.toString(), grantAmount.toString());
+  });
+});
@@ -0,0 +1,77 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, registrarStatsAddress, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("log_registrar_info!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+  })
+
+  async function logRegistrarInfo(): Promise<any> {
+    const response = await VSR_PROGRAM.methods
+      .logRegistrarInfo()
+      .accounts({
+        registrar,
+        registrarStats: registrarStatsAddress(registrar),
+      })
+      .simulate()
+    return response.events[0].data;
+  }
+
+  it("create_registrar_stats", async () => {
+    await VSR_PROGRAM.methods
+      .createRegistrarStats()
+      .accounts({
+        registrar,
+        registrarStats: registrarStatsAddress(registrar),
+        payer: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    const stats = await VSR_PROGRAM.account.registrarStats.fetch(registrarStatsAddress(registrar));
+    assert.equal(stats.registrar.toBase58(), registrar.toBase58());
+    assert.equal(stats.voterCount.toNumber(), 0);
+    assert.equal(stats.claimedRewardAmount.toNumber(), 0);
+  });
+
+  it("log_registrar_info", async () => {
+    const [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    await createVoter(realm, mint, registrar, authority);
+
+    const depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount);
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    const registrarInfo = await logRegistrarInfo();
+    assert.equal(registrarInfo.voterCount.toNumber(), 2);
+    assert.equal(registrarInfo.depositedAmount.toString(), depositAmount.toString());
+    assert.equal(registrarInfo.permanentlyLockedAmount.toString(), depositAmount.toString());
+    assert.equal(registrarInfo.maxVestingLockedAmount.toNumber(), 0);
+    assert.equal(registrarInfo.activeNodeCount.toNumber(), 0);
+    // nothing has been claimed yet
+    assert.equal(registrarInfo.maxUnclaimedRewardAmount.toString(), registrarInfo.issuedRewardAmount.toString());
+  });
+});
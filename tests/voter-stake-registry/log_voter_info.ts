@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, fastup, lockupDayily, lockupMonthly, LockupTimeDuration, lockupTimeDurationSeconds, mintTokenToAccount, newSigner, newTokenAccount, VSR_PROGRAM } from "../helper";
+import { createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, fastup, lockupDayily, lockupMonthly, LockupTimeDuration, lockupTimeDurationSeconds, mintTokenToAccount, newSigner, newTokenAccount, VSR_PROGRAM, nodeAddress } from "../helper";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -37,6 +37,8 @@ describe("log_voter_info!", () => {
         vault: vault,
         depositToken: voterTokenAccount,
         depositAuthority: voterAuthority.publicKey,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
+        payer: voterAuthority.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID
       }).signers([voterAuthority])
       .rpc();
@@ -125,5 +127,6 @@ describe("log_voter_info!", () => {
     assert.isTrue(voterInfoData.depositEntries[2] != null)
     assert.isTrue(voterInfoData.depositEntries[3] != null)
     assert.isTrue(voterInfoData.depositEntries[4] != null)
+    assert.isFalse(voterInfoData.nodeNonCompliant)
   });
 });
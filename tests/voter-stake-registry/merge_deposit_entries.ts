@@ -0,0 +1,110 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, LockupTimeDuration, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("merge_deposit_entries!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    // create registrar
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+  })
+
+  async function deposit(depositEntryIndex: number, duration: LockupTimeDuration) {
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, depositAmount, duration)
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function mergeDepositEntries(depositEntryIndex: number, targetDepositEntryIndex: number): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .mergeDepositEntries(depositEntryIndex, targetDepositEntryIndex)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_args_should_fail", async () => {
+    await assertThrowsAnchorError('NodeDepositReservedEntryIndex', async () => {
+      await mergeDepositEntries(0, 1);
+    }, undefined, false);
+
+    await assertThrowsAnchorError('SameDepositEntryIndex', async () => {
+      await mergeDepositEntries(1, 1);
+    }, undefined, false);
+  });
+
+  it("with_inactive_deposit_entry_index_should_fail", async () => {
+    await deposit(1, lockupDayily(15));
+
+    await assertThrowsAnchorError('InactiveDepositEntry', async () => {
+      await mergeDepositEntries(1, 2);
+    }, undefined, false);
+  });
+
+  it("with_vesting_deposit_entry_should_fail", async () => {
+    await VSR_PROGRAM.methods
+      .ordinaryReleaseDeposit(1, 2, depositAmount.divn(2))
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    await assertThrowsAnchorError('NotOrdinaryDepositEntry', async () => {
+      await mergeDepositEntries(2, 1);
+    }, undefined, false);
+  });
+
+  it("verify_merge_deposit_entries", async () => {
+    await deposit(3, lockupDayily(30));
+
+    // merging into the shorter lockup keeps the longer one
+    await mergeDepositEntries(3, 1);
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.deposits.at(3).isActive == 0);
+
+    const mergedEntry = voterData.deposits.at(1);
+    const mergedAmount = depositAmount.add(depositAmount.divn(2));
+    assert.isTrue(mergedEntry.isActive == 1);
+    assert.equal(mergedEntry.amountDepositedNative.toString(), mergedAmount.toString());
+    assert.equal(mergedEntry.amountInitiallyLockedNative.toString(), mergedAmount.toString());
+    assert.isTrue(mergedEntry.lockup.kind.kind.constant != undefined);
+    assert.equal(mergedEntry.lockup.kind.duration.periods.toNumber(), 30);
+
+    // only the constant lockups are permanently locked
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), mergedAmount.toString());
+  });
+});
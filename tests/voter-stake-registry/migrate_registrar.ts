@@ -0,0 +1,61 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, newSigner, newTokenAccount, registrarStatsAddress, VSR_PROGRAM } from "../helper";
+import { assert } from "chai";
+
+describe("migrate_registrar!", () => {
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let vault: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar, , vault] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    await VSR_PROGRAM.methods
+      .createRegistrarStats()
+      .accounts({
+        registrar,
+        registrarStats: registrarStatsAddress(registrar),
+        payer: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+  })
+
+  async function migrateRegistrar(vault: web3.PublicKey) {
+    const payer = await newSigner();
+    await VSR_PROGRAM.methods
+      .migrateRegistrar()
+      .accounts({
+        registrar,
+        vault,
+        registrarStats: registrarStatsAddress(registrar),
+        payer: payer.publicKey,
+      }).signers([payer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_invalid_reward_vault_should_fail", async () => {
+    const otherVault = await newTokenAccount(mint, authority);
+    await assertThrowsAnchorError('InvalidRewardVault', async () => {
+      await migrateRegistrar(otherVault);
+    });
+  });
+
+  it("migrate_current_registrar_has_no_effect", async () => {
+    const before = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(before.version, 1);
+
+    await migrateRegistrar(vault);
+
+    const after = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(after.version, 1);
+    assert.equal(after.totalRewardWeight.toString(), before.totalRewardWeight.toString());
+    assert.equal(after.emissionConfig.totalRewardAmount.toString(), before.emissionConfig.totalRewardAmount.toString());
+    assert.equal(after.fundedRewardAmount.toString(), before.fundedRewardAmount.toString());
+  });
+});
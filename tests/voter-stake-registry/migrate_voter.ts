@@ -0,0 +1,52 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, newSigner, registrarStatsAddress, VSR_PROGRAM } from "../helper";
+import { assert } from "chai";
+
+describe("migrate_voter!", () => {
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voter: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [, voter] = await createVoter(realm, mint, registrar, authority);
+  })
+
+  async function migrateVoter(registrar: web3.PublicKey) {
+    const payer = await newSigner();
+    await VSR_PROGRAM.methods
+      .migrateVoter()
+      .accounts({
+        registrar,
+        voter,
+        registrarStats: registrarStatsAddress(registrar),
+        payer: payer.publicKey,
+      }).signers([payer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_voter_of_another_registrar_should_fail", async () => {
+    const [, , otherRealm] = await createRealm(authority);
+    const [otherRegistrar] = await createRegistrar(otherRealm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await migrateVoter(otherRegistrar);
+    });
+  });
+
+  it("migrate_current_voter_has_no_effect", async () => {
+    await migrateVoter(registrar);
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter);
+    assert.equal(voterData.version, 1);
+
+    // the voter was counted when it was created
+    const stats = await VSR_PROGRAM.account.registrarStats.fetch(registrarStatsAddress(registrar));
+    assert.equal(stats.voterCount.toNumber(), 1);
+  });
+});
@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { mintTokenToWallet, assertThrowsAnchorError, assertThrowsSendTransactionError, newSigner, VSR_PROGRAM, getTokenAccount, fastup, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, newTokenAccount, mintTokenToAccount, CONNECTION } from "../helper";
+import { mintTokenToWallet, assertThrowsAnchorError, assertThrowsSendTransactionError, newSigner, VSR_PROGRAM, getTokenAccount, fastup, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, newTokenAccount, mintTokenToAccount, CONNECTION, nodeAddress } from "../helper";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -52,6 +52,8 @@ describe("node_deposit!", () => {
           vault,
           depositToken,
           depositAuthority: voterAuthority.publicKey,
+          node: nodeAddress(invalidRegistrar, voterAuthority.publicKey),
+          payer: voterAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([voterAuthority])
         .rpc();
@@ -77,6 +79,8 @@ describe("node_deposit!", () => {
           vault: invalidVault,
           depositToken,
           depositAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          payer: voterAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([voterAuthority])
         .rpc();
@@ -100,6 +104,8 @@ describe("node_deposit!", () => {
           vault,
           depositToken: invalidDepositToken,
           depositAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          payer: voterAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([voterAuthority])
         .rpc();
@@ -120,6 +126,8 @@ describe("node_deposit!", () => {
           vault,
           depositToken,
           depositAuthority: invalidDepositAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          payer: invalidDepositAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([invalidDepositAuthority])
         .rpc();
@@ -142,6 +150,8 @@ describe("node_deposit!", () => {
           vault,
           depositToken,
           depositAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          payer: voterAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([voterAuthority])
         .rpc();
@@ -165,6 +175,8 @@ describe("node_deposit!", () => {
         vault,
         depositToken,
         depositAuthority: voterAuthority.publicKey,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
+        payer: voterAuthority.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID
       }).signers([voterAuthority])
       .rpc({commitment: "confirmed"});
@@ -218,6 +230,8 @@ describe("node_deposit!", () => {
           vault,
           depositToken,
           depositAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          payer: voterAuthority.publicKey,
           tokenProgram: TOKEN_PROGRAM_ID
         }).signers([voterAuthority])
         .rpc();
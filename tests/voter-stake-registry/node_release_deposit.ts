@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { assertThrowsAnchorError, newSigner, VSR_PROGRAM, fastup, SECS_PER_MONTH, lockupDayily, createRealm, createVoter, newTokenAccount, mintTokenToAccount, createRegistrar, defaultDepositConfig, defaultVotingConfig, CONNECTION, DepositConfig } from "../helper";
+import { assertThrowsAnchorError, newSigner, VSR_PROGRAM, fastup, SECS_PER_MONTH, lockupDayily, createRealm, createVoter, newTokenAccount, mintTokenToAccount, createRegistrar, defaultDepositConfig, defaultVotingConfig, CONNECTION, DepositConfig, fundRewards, nodeAddress } from "../helper";
 import { Account, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -27,7 +27,9 @@ describe("node_release_deposit!", () => {
     [mint, councilMint, realm] = await createRealm(authority);
     depositConfig = defaultDepositConfig();
     // create registrar
-    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), depositConfig, new anchor.BN(1e10), authority);
+    let registrarVault: web3.PublicKey;
+    [registrar, , registrarVault] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), depositConfig, new anchor.BN(1e10), authority);
+    await fundRewards(registrar, registrarVault, mint, authority, new anchor.BN(1e10));
     [voterAuthority, voter, voterWeightRecord, vault, tokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
     depositToken = await newTokenAccount(mint, voterAuthority);
     nodeSecurityDeposit = defaultDepositConfig().nodeSecurityDeposit;
@@ -56,6 +58,7 @@ describe("node_release_deposit!", () => {
           registrar: invalidRegistrar,
           voter,
           voterAuthority: voterAuthority.publicKey,
+          node: nodeAddress(invalidRegistrar, voterAuthority.publicKey),
         }).signers([voterAuthority])
         .rpc();
     },
@@ -77,6 +80,7 @@ describe("node_release_deposit!", () => {
           registrar,
           voter,
           voterAuthority: invalidVoterAuthority.publicKey,
+          node: nodeAddress(registrar, invalidVoterAuthority.publicKey),
         }).signers([invalidVoterAuthority])
         .rpc();
     },
@@ -96,6 +100,7 @@ describe("node_release_deposit!", () => {
           registrar,
           voter,
           voterAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
         }).signers([voterAuthority])
         .rpc();
     },
@@ -115,6 +120,8 @@ describe("node_release_deposit!", () => {
         vault,
         depositToken,
         depositAuthority: voterAuthority.publicKey,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
+        payer: voterAuthority.publicKey,
         tokenProgram: TOKEN_PROGRAM_ID
       }).signers([voterAuthority])
       .rpc();
@@ -142,6 +149,7 @@ describe("node_release_deposit!", () => {
           registrar,
           voter,
           voterAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
         }).signers([voterAuthority])
         .rpc();
     },
@@ -159,6 +167,7 @@ describe("node_release_deposit!", () => {
           registrar,
           voter,
           voterAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
         }).signers([voterAuthority])
         .rpc();
     },
@@ -175,6 +184,7 @@ describe("node_release_deposit!", () => {
           registrar,
           voter,
           voterAuthority: voterAuthority.publicKey,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
         }).signers([voterAuthority])
         .rpc();
     },
@@ -197,6 +207,7 @@ describe("node_release_deposit!", () => {
         registrar,
         voter,
         voterAuthority: voterAuthority.publicKey,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
       }).signers([voterAuthority])
       .rpc({commitment: "confirmed"});
     const tx = await CONNECTION.getTransaction(txId, {commitment: 'confirmed'});
@@ -222,5 +233,9 @@ describe("node_release_deposit!", () => {
     assert.equal(registrarData.permanentlyLockedAmount.toString(), prevPermanentlyLockedAmount.sub(depositConfig.nodeSecurityDeposit).toString());
     assert.equal(registrarData.rewardIndex.toString(), voterData.rewardIndex.toString())
     assert.equal(registrarData.rewardAccrualTs.toString(), registrarData.timeOffset.add(new anchor.BN(tx.blockTime.toString())).toString());
+
+    // the node is unregistered
+    assert.isTrue(registrarData.activeNodeCount.eqn(0));
+    assert.isNull(await CONNECTION.getAccountInfo(nodeAddress(registrar, voterAuthority.publicKey), "confirmed"));
   });
 });
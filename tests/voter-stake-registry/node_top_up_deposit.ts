@@ -0,0 +1,140 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, fastup, mintTokenToAccount, newSigner, newTokenAccount, nodeAddress, SECS_PER_DAY, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("node_top_up_deposit!", () => {
+  const depositConfig = defaultDepositConfig();
+  const raisedNodeSecurityDeposit = depositConfig.nodeSecurityDeposit.muln(2);
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+  let node: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), depositConfig, new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+    node = nodeAddress(registrar, voterAuthority.publicKey);
+  })
+
+  async function nodeTopUpDeposit(): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .nodeTopUpDeposit()
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        node,
+        payer: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function updateNodeCompliance(): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .updateNodeCompliance()
+      .accounts({
+        registrar,
+        voter,
+        node,
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function nodeNonCompliant(): Promise<boolean> {
+    const response = await VSR_PROGRAM.methods
+      .logVoterInfo()
+      .accounts({
+        registrar,
+        voter,
+        node,
+      })
+      .simulate();
+    return response.events[0].data.nodeNonCompliant as boolean;
+  }
+
+  it("without_node_deposit_should_fail", async () => {
+    await assertThrowsAnchorError('InactiveDepositEntry', async () => {
+      await nodeTopUpDeposit();
+    });
+  });
+
+  it("with_satisfied_node_security_deposit_should_fail", async () => {
+    await mintTokenToAccount(mint, authority, depositToken, depositConfig.nodeSecurityDeposit);
+    await VSR_PROGRAM.methods
+      .nodeDeposit()
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        node,
+        payer: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    await assertThrowsAnchorError('NodeSecurityDepositSatisfied', async () => {
+      await nodeTopUpDeposit();
+    });
+  });
+
+  it("verify_non_compliant_after_grace_period", async () => {
+    await VSR_PROGRAM.methods
+      .updateDepositConfig({ ...depositConfig, nodeSecurityDeposit: raisedNodeSecurityDeposit })
+      .accounts({
+        registrar,
+        realmAuthority: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    // the grace period of the node starts when it is found below the new requirement
+    await updateNodeCompliance();
+    let nodeData = await VSR_PROGRAM.account.node.fetch(node, "confirmed");
+    assert.isTrue(nodeData.gracePeriodStartTs.gtn(0));
+    assert.equal(nodeData.nonCompliant, 0);
+    assert.isFalse(await nodeNonCompliant());
+
+    await fastup(registrar, authority, depositConfig.nodeSecurityDepositGracePeriodSecs.add(SECS_PER_DAY), "confirmed");
+    assert.isTrue(await nodeNonCompliant());
+    await updateNodeCompliance();
+    nodeData = await VSR_PROGRAM.account.node.fetch(node, "confirmed");
+    assert.equal(nodeData.nonCompliant, 1);
+  });
+
+  it("verify_node_top_up_deposit", async () => {
+    const amount = raisedNodeSecurityDeposit.sub(depositConfig.nodeSecurityDeposit);
+    await mintTokenToAccount(mint, authority, depositToken, amount);
+    await nodeTopUpDeposit();
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.equal(voterData.deposits[0].amountDepositedNative.toString(), raisedNodeSecurityDeposit.toString());
+    assert.isTrue(voterData.deposits[0].lockup.kind.kind.constant != undefined);
+
+    const nodeData = await VSR_PROGRAM.account.node.fetch(node, "confirmed");
+    assert.equal(nodeData.nonCompliant, 0);
+    assert.isTrue(nodeData.gracePeriodStartTs.eqn(0));
+    assert.isTrue(nodeData.status.active != undefined);
+    assert.isFalse(await nodeNonCompliant());
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), raisedNodeSecurityDeposit.toString());
+    assert.equal(registrarData.activeNodeCount.toNumber(), 1);
+  });
+});
@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { mintTokenToWallet, assertThrowsAnchorError, assertThrowsSendTransactionError, createRealm, newSigner, VSR_PROGRAM, getTokenAccount, fastup, lockupDayily, lockupMonthly, defaultDepositConfig, createRegistrar, defaultVotingConfig, newTokenAccount, mintTokenToAccount, createVoter, CONNECTION } from "../helper";
+import { mintTokenToWallet, assertThrowsAnchorError, assertThrowsSendTransactionError, createRealm, newSigner, VSR_PROGRAM, getTokenAccount, fastup, lockupDayily, lockupMonthly, lockupWeekly, lockupYearly, newLockupTimeDuration, defaultDepositConfig, createRegistrar, defaultVotingConfig, newTokenAccount, mintTokenToAccount, createVoter, CONNECTION } from "../helper";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -328,4 +328,118 @@ describe("ordinary_deposit!", () => {
       (anchorErr) => { },
       false);
   });
+
+  it("cliff_deposit_and_withdraw_should_work", async () => {
+    const depositEntryIndex = 3;
+    const depositAmount = new anchor.BN(500e6); // 500
+    await mintTokenToAccount(mint, authority, voterTokenAccount, depositAmount);
+
+    async function deposit(duration, cliff: boolean) {
+      const methods = VSR_PROGRAM.methods;
+      await (cliff ? methods.ordinaryCliffDeposit : methods.ordinaryDeposit)(depositEntryIndex, depositAmount, duration)
+        .accounts({
+          registrar,
+          voter,
+          vault,
+          depositToken: voterTokenAccount,
+          depositAuthority: voterAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID
+        }).signers([voterAuthority])
+        .rpc({commitment: "confirmed"});
+    }
+
+    async function withdraw(amount: anchor.BN) {
+      await VSR_PROGRAM.methods
+        .withdraw(depositEntryIndex, amount)
+        .accounts({
+          registrar,
+          voter,
+          voterAuthority: voterAuthority.publicKey,
+          tokenOwnerRecord,
+          voterWeightRecord,
+          vault,
+          destination: voterTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID
+        }).signers([voterAuthority])
+        .rpc({commitment: "confirmed"});
+    }
+
+    const oldRegistrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    await deposit(lockupDayily(15), true);
+
+    let voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    let depositEntry = voterData.deposits.at(depositEntryIndex);
+    assert.isTrue(depositEntry.isActive == 1)
+    assert.isTrue(depositEntry.amountDepositedNative.eq(depositAmount))
+    assert.isTrue(depositEntry.lockup.kind.kind.cliff != undefined) // assert lockup kind is cliff
+    assert.equal(depositEntry.lockup.kind.duration.periods.toNumber(), 15)
+
+    // cliff lockups are not permanently locked
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), oldRegistrarData.permanentlyLockedAmount.toString());
+
+    // the kind and the duration of an active cliff deposit can not change
+    await assertThrowsAnchorError('InvalidLockupKind', async () => {
+      await deposit(lockupDayily(15), false);
+    });
+    await assertThrowsAnchorError('InvalidLockupDuration', async () => {
+      await deposit(lockupDayily(16), true);
+    });
+
+    // nothing unlocks before the end of the cliff
+    await fastup(registrar, authority, new anchor.BN(86400 * 14), "confirmed");
+    await assertThrowsAnchorError('InsufficientUnlockedTokens', async () => {
+      await withdraw(new anchor.BN(1));
+    });
+
+    // everything unlocks at the end
+    await fastup(registrar, authority, new anchor.BN(86400), "confirmed");
+    await withdraw(depositAmount);
+
+    voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    depositEntry = voterData.deposits.at(depositEntryIndex);
+    assert.isTrue(depositEntry.isActive == 0)
+  });
+
+  it("week_year_and_calendar_durations_should_work", async () => {
+    const depositAmount = new anchor.BN(100e6); // 100
+    await mintTokenToAccount(mint, authority, voterTokenAccount, depositAmount.muln(3));
+
+    async function deposit(depositEntryIndex: number, duration) {
+      await VSR_PROGRAM.methods
+        .ordinaryDeposit(depositEntryIndex, depositAmount, duration)
+        .accounts({
+          registrar,
+          voter,
+          vault,
+          depositToken: voterTokenAccount,
+          depositAuthority: voterAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID
+        }).signers([voterAuthority])
+        .rpc({commitment: "confirmed"});
+    }
+
+    // one week is shorter than the min duration of 15 days
+    await assertThrowsAnchorError('InvalidLockupDuration', async () => {
+      await deposit(4, lockupWeekly(2));
+    });
+    // calendar mode is only supported by months and years
+    await assertThrowsAnchorError('InvalidLockupDuration', async () => {
+      await deposit(4, newLockupTimeDuration(new anchor.BN(30), 'day', true));
+    });
+
+    await deposit(4, lockupWeekly(3));
+    await deposit(5, lockupYearly(1));
+    await deposit(6, newLockupTimeDuration(new anchor.BN(2), 'month', true));
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    const weekly = voterData.deposits.at(4);
+    assert.isTrue((weekly.lockup.kind.duration.unit as any).week != undefined)
+    assert.equal(weekly.lockup.kind.duration.periods.toNumber(), 3)
+    const yearly = voterData.deposits.at(5);
+    assert.isTrue((yearly.lockup.kind.duration.unit as any).year != undefined)
+    const calendar = voterData.deposits.at(6);
+    assert.isTrue((calendar.lockup.kind.duration.unit as any).month != undefined)
+    assert.equal(calendar.lockup.kind.duration.calendarMode, 1)
+  });
 });
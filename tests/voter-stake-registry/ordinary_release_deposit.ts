@@ -232,6 +232,7 @@ describe("ordinary_release_deposit!", () => {
     assert.isTrue(targetDepositEntry.amountInitiallyLockedNative.eq(releaseAmount))
     assert.isTrue(targetDepositEntry.lockup.kind.kind.daily != undefined) // assert lockup kind is daily
     assert.equal(targetDepositEntry.lockup.kind.duration.periods.toNumber(), 15) // assert periods of lockup time duration is 15
+    assert.equal(targetDepositEntry.lockup.kind.cliffPeriods, 0) // assert no vesting cliff by default
 
     // verify registrar data
     registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
@@ -284,4 +285,56 @@ describe("ordinary_release_deposit!", () => {
     );
 
   });
+
+  it("verify_ordinary_release_deposit_with_cliff", async () => {
+    const depositEntryIndex = 7;
+    const targetDepositEntryIndex = 8;
+    await deposit(depositEntryIndex, lockupDayily(15));
+
+    await VSR_PROGRAM.methods.updateReleaseCliffConfig({
+      ordinaryReleaseCliffPeriods: 5,
+      nodeReleaseCliffPeriods: 0,
+    }).accounts({
+      registrar,
+      realmAuthority: authority.publicKey,
+    }).signers([authority])
+      .rpc();
+
+    await VSR_PROGRAM.methods
+      .ordinaryReleaseDeposit(depositEntryIndex, targetDepositEntryIndex, depositAmount)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc();
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter);
+    const targetDepositEntry = voterData.deposits.at(targetDepositEntryIndex);
+    assert.isTrue(targetDepositEntry.isActive == 1)
+    assert.isTrue(targetDepositEntry.amountInitiallyLockedNative.eq(depositAmount))
+    assert.isTrue(targetDepositEntry.lockup.kind.kind.daily != undefined) // assert lockup kind is daily
+    assert.equal(targetDepositEntry.lockup.kind.duration.periods.toNumber(), 15)
+    assert.equal(targetDepositEntry.lockup.kind.cliffPeriods, 5) // assert nothing vests during the first 5 days
+
+    // nothing is withdrawable before the cliff has passed
+    await assertThrowsAnchorError('InsufficientUnlockedTokens', async () => {
+      await VSR_PROGRAM.methods
+        .withdraw(targetDepositEntryIndex, new anchor.BN(1))
+        .accounts({
+          registrar,
+          voter,
+          voterAuthority: voterAuthority.publicKey,
+          tokenOwnerRecord,
+          voterWeightRecord,
+          vault,
+          destination: depositToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).signers([voterAuthority])
+        .rpc();
+    },
+      undefined,
+      false
+    );
+  });
 });
@@ -0,0 +1,98 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, CONNECTION, grant } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("resize_voter!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+  const VOTER_ACCOUNT_SIZE = 8 + 1568;
+  const DEPOSIT_ENTRY_SIZE = 88;
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount);
+  })
+
+  async function resizeVoter(depositEntryCount: number): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .resizeVoter(depositEntryCount)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        payer: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function voterAccountSize(): Promise<number> {
+    return (await CONNECTION.getAccountInfo(voter, "confirmed")).data.length;
+  }
+
+  it("with_too_few_deposit_entries_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidDepositEntryCount', async () => {
+      await resizeVoter(15);
+    });
+  });
+
+  it("with_too_many_deposit_entries_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidDepositEntryCount', async () => {
+      await resizeVoter(65);
+    });
+  });
+
+  it("verify_resize_voter", async () => {
+    assert.equal(await voterAccountSize(), VOTER_ACCOUNT_SIZE);
+
+    await resizeVoter(20);
+    assert.equal(await voterAccountSize(), VOTER_ACCOUNT_SIZE + 4 * DEPOSIT_ENTRY_SIZE);
+
+    // the extra deposit entries can be used like any other
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(19, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    // active deposit entries can not be removed
+    await assertThrowsAnchorError('ActiveDepositEntryIndex', async () => {
+      await resizeVoter(19);
+    });
+
+    // the 16 deposit entries of the voter itself are always kept
+    await resizeVoter(24);
+    assert.equal(await voterAccountSize(), VOTER_ACCOUNT_SIZE + 8 * DEPOSIT_ENTRY_SIZE);
+    await resizeVoter(20);
+    assert.equal(await voterAccountSize(), VOTER_ACCOUNT_SIZE + 4 * DEPOSIT_ENTRY_SIZE);
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), depositAmount.toString());
+  });
+
+  it("grant_to_resized_voter", async () => {
+    await grant(registrar, mint, authority, voterAuthority.publicKey, 18, { daily: {} }, new anchor.BN(10), null, depositAmount, false);
+    assert.equal(await voterAccountSize(), VOTER_ACCOUNT_SIZE + 4 * DEPOSIT_ENTRY_SIZE);
+  });
+});
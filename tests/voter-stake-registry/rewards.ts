@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, LockupTimeDuration, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, CONNECTION, CIRCUIT_BREAKER_PROGRAM, fastup, SECS_PER_DAY, assertThrowsSendTransactionError, getTokenAccount, EXP_SCALE, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR, SECS_PER_YEAR, TOTAL_REWARD_AMOUNT } from "../helper";
+import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, LockupTimeDuration, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, CONNECTION, CIRCUIT_BREAKER_PROGRAM, fastup, SECS_PER_DAY, assertThrowsSendTransactionError, getTokenAccount, EXP_SCALE, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR, SECS_PER_YEAR, TOTAL_REWARD_AMOUNT, fundRewards, registrarStatsAddress } from "../helper";
 import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { assert } from "chai";
 
@@ -72,6 +72,7 @@ describe("rewards!", () => {
                 .accounts({
                     registrar: invalidRegistrar,
                     voter,
+                    registrarStats: registrarStatsAddress(registrar),
                     voterAuthority: voterAuthority.publicKey,
                     destination: depositToken,
                     vault: registrarVault,
@@ -98,6 +99,7 @@ describe("rewards!", () => {
                 .accounts({
                     registrar: registrar,
                     voter,
+                    registrarStats: registrarStatsAddress(registrar),
                     voterAuthority: invalidVoterAuthority.publicKey,
                     destination: depositToken,
                     vault: registrarVault,
@@ -115,22 +117,21 @@ describe("rewards!", () => {
         );
     });
 
-    it("with_insufficient_funds_in_vault_should_fail", async () => {
+    it("without_funded_rewards_should_fail", async () => {
         const depositEntryIndex = 3;
         await deposit(depositEntryIndex, lockupDayily(15));
 
-        let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
-
         // fastup 1 day
         await fastup(registrar, authority, SECS_PER_DAY, "confirmed");
 
-        // 0x1 represents TokenError::InsufficientFunds
-        await assertThrowsSendTransactionError('custom program error: 0x1', async () => {
+        // no rewards are issued beyond the funded amount
+        await assertThrowsAnchorError('InsufficientClaimableRewards', async () => {
             await VSR_PROGRAM.methods
-                .claimReward(null)
+                .claimReward(new anchor.BN(1))
                 .accounts({
                     registrar,
                     voter,
+                    registrarStats: registrarStatsAddress(registrar),
                     voterAuthority: voterAuthority.publicKey,
                     destination: depositToken,
                     vault: registrarVault,
@@ -145,11 +146,14 @@ describe("rewards!", () => {
     });
 
     it("verify_claim_reward", async () => {
-        // deposit tokens to vault
-        await mintTokenToAccount(mint, authority, registrarVault, new anchor.BN(1e10));
+        // fund rewards
+        await fundRewards(registrar, registrarVault, mint, authority, new anchor.BN(1e10));
 
-        const destinationTokenAccount = await newTokenAccount(mint, await newSigner());
         let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+        assert.isTrue(registrarData.issuedRewardAmount.eqn(0));
+        assert.equal(registrarData.fundedRewardAmount.toString(), new anchor.BN(1e10).toString());
+
+        const destinationTokenAccount = await newTokenAccount(mint, await newSigner());
         let prevRewardAccrualTs = registrarData.rewardAccrualTs;
         let prevRewardIndex = registrarData.rewardIndex;
 
@@ -168,6 +172,9 @@ describe("rewards!", () => {
 
         const voterInfoData = logVoterInfoResp.events[0].data;
         const estimatedRewards = voterInfoData.rewardAmount as anchor.BN;
+        assert.isTrue((voterInfoData.rewardRunwayAmount as anchor.BN).lt(new anchor.BN(1e10)));
+        assert.isTrue((voterInfoData.rewardRunwayAmount as anchor.BN).gt(estimatedRewards));
+        assert.isTrue((voterInfoData.rewardRunwaySecs as anchor.BN).gt(SECS_PER_DAY));
         const claimAmount = estimatedRewards.subn(1);
 
         let txId = await VSR_PROGRAM.methods
@@ -175,6 +182,7 @@ describe("rewards!", () => {
             .accounts({
                 registrar,
                 voter,
+                registrarStats: registrarStatsAddress(registrar),
                 voterAuthority: voterAuthority.publicKey,
                 destination: destinationTokenAccount,
                 vault: registrarVault,
@@ -194,7 +202,7 @@ describe("rewards!", () => {
         assert.equal(registrarData.rewardIndex.toString(), voterData.rewardIndex.toString())
 
         let rewardIndexDelta = registrarData.currentRewardAmountPerSecond.mul(registrarData.rewardAccrualTs.sub(prevRewardAccrualTs))
-            .div(anchor.BN.max(registrarData.permanentlyLockedAmount, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR))
+            .div(anchor.BN.max(registrarData.totalRewardWeight, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR))
         assert.equal(registrarData.rewardIndex.toString(), prevRewardIndex.add(rewardIndexDelta).toString());
 
         let destinationTokenAccountData = await getTokenAccount(destinationTokenAccount);
@@ -206,6 +214,7 @@ describe("rewards!", () => {
             .accounts({
                 registrar,
                 voter,
+                registrarStats: registrarStatsAddress(registrar),
                 voterAuthority: voterAuthority.publicKey,
                 destination: destinationTokenAccount,
                 vault: registrarVault,
@@ -236,6 +245,7 @@ describe("rewards!", () => {
             .accounts({
                 registrar,
                 voter,
+                registrarStats: registrarStatsAddress(registrar),
                 voterAuthority: voterAuthority.publicKey,
                 destination: destinationTokenAccount,
                 vault: registrarVault,
@@ -255,7 +265,7 @@ describe("rewards!", () => {
         assert.equal(registrarData.rewardIndex.toString(), voterData.rewardIndex.toString())
 
         let rewardIndexDelta = registrarData.currentRewardAmountPerSecond.mul(registrarData.rewardAccrualTs.sub(prevRewardAccrualTs))
-            .div(anchor.BN.max(registrarData.permanentlyLockedAmount, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR))
+            .div(anchor.BN.max(registrarData.totalRewardWeight, FULL_REWARD_PERMANENTLY_LOCKED_FLOOR))
         assert.equal(registrarData.rewardIndex.toString(), prevRewardIndex.add(rewardIndexDelta).toString());
 
         let destinationTokenAccountData = await getTokenAccount(destinationTokenAccount);
@@ -290,6 +300,7 @@ describe("rewards!", () => {
                 .accounts({
                     registrar,
                     voter,
+                    registrarStats: registrarStatsAddress(registrar),
                     voterAuthority: voterAuthority.publicKey,
                     destination: destinationTokenAccount,
                     vault: registrarVault,
@@ -0,0 +1,127 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, registrarConfigAddress, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { getVoterWeightRecord } from "@solana/spl-governance";
+import { assert } from "chai";
+
+const PAUSE_DEPOSITS = 1 << 0;
+const PAUSE_RELEASES = 1 << 1;
+const PAUSE_WITHDRAWS = 1 << 2;
+const PAUSE_CLAIMS = 1 << 3;
+
+describe("set_paused!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let guardian: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let registrarConfig: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    guardian = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    registrarConfig = registrarConfigAddress(registrar);
+    [voterAuthority, voter, voterWeightRecord, vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+
+    await VSR_PROGRAM.methods
+      .updateGuardian(guardian.publicKey)
+      .accounts({
+        registrar,
+        registrarConfig,
+        realmAuthority: authority.publicKey,
+        payer: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+  })
+
+  async function setPaused(paused: number, signer: web3.Keypair, withRegistrarConfig: boolean = true) {
+    await VSR_PROGRAM.methods
+      .setPaused(paused)
+      .accounts({
+        registrar,
+        registrarConfig: withRegistrarConfig ? registrarConfig : null,
+        authority: signer.publicKey,
+      }).signers([signer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function deposit() {
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_invalid_authority_should_fail", async () => {
+    const fakeGuardian = await newSigner();
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await setPaused(PAUSE_DEPOSITS, fakeGuardian);
+    });
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await setPaused(PAUSE_DEPOSITS, guardian, false);
+    });
+  });
+
+  it("with_invalid_pause_flags_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidPauseFlags', async () => {
+      await setPaused(1 << 4, authority);
+    });
+  });
+
+  it("verify_pause_and_unpause", async () => {
+    await deposit();
+
+    await setPaused(PAUSE_DEPOSITS | PAUSE_CLAIMS, guardian);
+    let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.paused, PAUSE_DEPOSITS | PAUSE_CLAIMS);
+
+    await assertThrowsAnchorError('DepositsPaused', async () => {
+      await deposit();
+    });
+
+    // governance can still vote
+    await VSR_PROGRAM.methods
+      .updateVoterWeightRecord(null, null)
+      .accounts({
+        registrar,
+        voter,
+        registrarConfig: null,
+        voterWeightRecord,
+      })
+      .rpc({ commitment: "confirmed" });
+    const record = (await getVoterWeightRecord(VSR_PROGRAM.provider.connection, voterWeightRecord)).account;
+    assert.equal(record.voterWeight.toString(), depositAmount.toString());
+
+    // the guardian can only pause further
+    await setPaused(PAUSE_DEPOSITS | PAUSE_RELEASES | PAUSE_WITHDRAWS | PAUSE_CLAIMS, guardian);
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await setPaused(PAUSE_RELEASES | PAUSE_WITHDRAWS | PAUSE_CLAIMS, guardian);
+    });
+
+    await setPaused(0, authority, false);
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.paused, 0);
+    await deposit();
+  });
+});
@@ -0,0 +1,184 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, newSigner, VSR_PROGRAM, createRealm, createVoter, newTokenAccount, mintTokenToAccount, createRegistrar, defaultDepositConfig, defaultVotingConfig, getTokenAccount, getMint, nodeAddress } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("slash_node_deposit!", () => {
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let registrarConfig: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+  let treasury: web3.PublicKey;
+  let slasher: web3.Keypair;
+
+  const nodeSecurityDeposit = defaultDepositConfig().nodeSecurityDeposit;
+
+  before(async () => {
+    authority = await newSigner();
+    slasher = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [registrarConfig] = web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("registrar-config")], VSR_PROGRAM.programId);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+    treasury = await newTokenAccount(mint, authority);
+
+    await nodeDeposit();
+  })
+
+  async function nodeDeposit() {
+    await mintTokenToAccount(mint, authority, depositToken, nodeSecurityDeposit);
+    await VSR_PROGRAM.methods
+      .nodeDeposit()
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
+        payer: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function slashNodeDeposit(signer: web3.Keypair, amount: anchor.BN, targetDepositEntryIndex: number, withTreasury: boolean, withRegistrarConfig: boolean): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .slashNodeDeposit(amount, targetDepositEntryIndex)
+      .accounts({
+        registrar,
+        registrarConfig: withRegistrarConfig ? registrarConfig : null,
+        voter,
+        vault,
+        governingTokenMint: mint,
+        treasury: withTreasury ? treasury : null,
+        node: nodeAddress(registrar, voterAuthority.publicKey),
+        slasher: signer.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([signer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function setPaused(paused: number) {
+    await VSR_PROGRAM.methods
+      .setPaused(paused)
+      .accounts({
+        registrar,
+        registrarConfig: null,
+        authority: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_unauthorized_slasher_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await slashNodeDeposit(slasher, nodeSecurityDeposit, 1, true, false);
+    });
+  });
+
+  it("with_too_large_amount_should_fail", async () => {
+    await assertThrowsAnchorError('InsufficientLockedTokens', async () => {
+      await slashNodeDeposit(authority, nodeSecurityDeposit.addn(1), 1, true, false);
+    });
+  });
+
+  it("while_releases_paused_should_fail", async () => {
+    await setPaused(1 << 1);
+    await assertThrowsAnchorError('ReleasesPaused', async () => {
+      await slashNodeDeposit(authority, nodeSecurityDeposit.divn(4), 1, true, false);
+    });
+    await setPaused(0);
+  });
+
+  it("verify_slash_to_treasury_by_realm_authority", async () => {
+    const amount = nodeSecurityDeposit.divn(4);
+    await slashNodeDeposit(authority, amount, 1, true, false);
+
+    const treasuryAmount = (await getTokenAccount(treasury)).amount.toString();
+    assert.equal(treasuryAmount, amount.toString());
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.deposits.at(0).isActive == 0);
+    const releasedEntry = voterData.deposits.at(1);
+    assert.isTrue(releasedEntry.isActive == 1);
+    assert.equal(releasedEntry.amountDepositedNative.toString(), nodeSecurityDeposit.sub(amount).toString());
+    assert.isTrue(releasedEntry.lockup.kind.kind.constant == undefined);
+
+    // the node deposit is no longer permanently locked
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.isTrue(registrarData.permanentlyLockedAmount.eqn(0));
+  });
+
+  it("verify_burn_by_configured_slasher", async () => {
+    await VSR_PROGRAM.methods
+      .updateSlasher(slasher.publicKey)
+      .accounts({
+        registrar,
+        registrarConfig,
+        realmAuthority: authority.publicKey,
+        payer: authority.publicKey,
+      }).signers([authority])
+      .rpc({ commitment: "confirmed" });
+
+    await nodeDeposit();
+
+    // the slasher can not choose where the slashed tokens go
+    const slasherTreasury = await newTokenAccount(mint, slasher);
+    await assertThrowsAnchorError('ConstraintTokenOwner', async () => {
+      await VSR_PROGRAM.methods
+        .slashNodeDeposit(nodeSecurityDeposit, 0)
+        .accounts({
+          registrar,
+          registrarConfig,
+          voter,
+          vault,
+          governingTokenMint: mint,
+          treasury: slasherTreasury,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          slasher: slasher.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        }).signers([slasher])
+        .rpc();
+    });
+
+    const prevSupply = new anchor.BN((await getMint(mint)).supply.toString());
+
+    // slashing everything leaves nothing to release
+    await slashNodeDeposit(slasher, nodeSecurityDeposit, 0, false, true);
+
+    const supply = new anchor.BN((await getMint(mint)).supply.toString());
+    assert.equal(prevSupply.sub(supply).toString(), nodeSecurityDeposit.toString());
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData.deposits.at(0).isActive == 0);
+
+    // the node stays jailed
+    const nodeData = await VSR_PROGRAM.account.node.fetch(nodeAddress(registrar, voterAuthority.publicKey), "confirmed");
+    assert.isTrue(nodeData.status.jailed != undefined);
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.isTrue(registrarData.activeNodeCount.eqn(0));
+  });
+
+  it("activate_node_without_node_deposit_should_fail", async () => {
+    await assertThrowsAnchorError('InactiveDepositEntry', async () => {
+      await VSR_PROGRAM.methods
+        .setNodeStatus({ active: {} })
+        .accounts({
+          registrar,
+          node: nodeAddress(registrar, voterAuthority.publicKey),
+          voter,
+          authority: authority.publicKey,
+        }).signers([authority])
+        .rpc();
+    });
+  });
+});
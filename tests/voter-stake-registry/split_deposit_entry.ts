@@ -0,0 +1,114 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, newSigner, VSR_PROGRAM, lockupDayily, LockupTimeDuration, newTokenAccount, mintTokenToAccount, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("split_deposit_entry!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    // create registrar
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+  })
+
+  async function deposit(depositEntryIndex: number, duration: LockupTimeDuration) {
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, depositAmount, duration)
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function splitDepositEntry(depositEntryIndex: number, targetDepositEntryIndex: number, amount: anchor.BN): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .splitDepositEntry(depositEntryIndex, targetDepositEntryIndex, amount)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_args_should_fail", async () => {
+    await assertThrowsAnchorError('ZeroAmount', async () => {
+      await splitDepositEntry(1, 2, new anchor.BN(0));
+    }, undefined, false);
+
+    await assertThrowsAnchorError('NodeDepositReservedEntryIndex', async () => {
+      await splitDepositEntry(1, 0, depositAmount);
+    }, undefined, false);
+
+    await assertThrowsAnchorError('SameDepositEntryIndex', async () => {
+      await splitDepositEntry(1, 1, depositAmount);
+    }, undefined, false);
+  });
+
+  it("with_inactive_deposit_entry_index_should_fail", async () => {
+    await assertThrowsAnchorError('InactiveDepositEntry', async () => {
+      await splitDepositEntry(1, 2, depositAmount);
+    }, undefined, false);
+  });
+
+  it("with_insufficient_deposit_amount_should_fail", async () => {
+    await deposit(1, lockupDayily(15));
+
+    await assertThrowsAnchorError('InsufficientLockedTokens', async () => {
+      await splitDepositEntry(1, 2, depositAmount.addn(1));
+    }, undefined, false);
+  });
+
+  it("with_active_target_entry_index_should_fail", async () => {
+    await deposit(2, lockupDayily(15));
+
+    await assertThrowsAnchorError('ActiveDepositEntryIndex', async () => {
+      await splitDepositEntry(1, 2, depositAmount);
+    }, undefined, false);
+  });
+
+  it("verify_split_deposit_entry", async () => {
+    const amount = depositAmount.divn(4);
+    await splitDepositEntry(1, 3, amount);
+
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    const sourceEntry = voterData.deposits.at(1);
+    const targetEntry = voterData.deposits.at(3);
+    assert.equal(sourceEntry.amountDepositedNative.toString(), depositAmount.sub(amount).toString());
+    assert.equal(targetEntry.amountDepositedNative.toString(), amount.toString());
+    assert.isTrue(targetEntry.lockup.kind.kind.constant != undefined);
+    assert.equal(targetEntry.lockup.kind.duration.periods.toNumber(), sourceEntry.lockup.kind.duration.periods.toNumber());
+
+    // splitting the whole amount leaves the source deposit entry inactive
+    await splitDepositEntry(2, 4, depositAmount);
+    const voterData2 = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.isTrue(voterData2.deposits.at(2).isActive == 0);
+    assert.equal(voterData2.deposits.at(4).amountDepositedNative.toString(), depositAmount.toString());
+
+    // the permanently locked amount is unchanged
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.permanentlyLockedAmount.toString(), depositAmount.muln(2).toString());
+  });
+});
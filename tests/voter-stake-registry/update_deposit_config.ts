@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, lockupDayily, lockupMonthly, newSigner, VSR_PROGRAM } from "../helper";
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, lockupDayily, lockupMonthly, newLockupTimeDuration, newSigner, VSR_PROGRAM } from "../helper";
 import { assert } from "chai";
 
 describe("update_deposit_config!", () => {
@@ -23,6 +23,7 @@ describe("update_deposit_config!", () => {
       ordinaryDepositMinLockupDuration: lockupDayily(30),
       nodeDepositLockupDuration: lockupMonthly(12),
       nodeSecurityDeposit: new anchor.BN(1000 * (1e6)),
+      nodeSecurityDepositGracePeriodSecs: new anchor.BN(86400),
     }
 
     const invalidRealmAuthority = await newSigner();
@@ -37,11 +38,32 @@ describe("update_deposit_config!", () => {
     })
   });
 
+  it("with_invalid_lockup_duration_should_fail", async () => {
+    const newDepositConfig = {
+      ordinaryDepositMinLockupDuration: lockupDayily(30),
+      // calendar mode is only supported by months and years
+      nodeDepositLockupDuration: newLockupTimeDuration(new anchor.BN(12), 'week', true),
+      nodeSecurityDeposit: new anchor.BN(1000 * (1e6)),
+      nodeSecurityDepositGracePeriodSecs: new anchor.BN(86400),
+    }
+
+    await assertThrowsAnchorError('InvalidLockupDuration', async () => {
+      await VSR_PROGRAM.methods.updateDepositConfig(
+        newDepositConfig,
+      ).accounts({
+        registrar,
+        realmAuthority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+        .rpc()
+    })
+  });
+
   it("verify_update_deposit_config", async () => {
     const newDepositConfig = {
       ordinaryDepositMinLockupDuration: lockupDayily(30),
       nodeDepositLockupDuration: lockupMonthly(12),
       nodeSecurityDeposit: new anchor.BN(1000 * (1e6)),
+      nodeSecurityDepositGracePeriodSecs: new anchor.BN(86400),
     }
 
     await VSR_PROGRAM.methods.updateDepositConfig(
@@ -58,6 +80,7 @@ describe("update_deposit_config!", () => {
     assert.equal(registrarData.depositConfig.nodeDepositLockupDuration.periods.toNumber(), newDepositConfig.nodeDepositLockupDuration.periods.toNumber())
     assert.isTrue(registrarData.depositConfig.nodeDepositLockupDuration.unit.month != undefined)
     assert.equal(registrarData.depositConfig.nodeSecurityDeposit.toNumber(), newDepositConfig.nodeSecurityDeposit.toNumber())
+    assert.equal(registrarData.depositConfig.nodeSecurityDepositGracePeriodSecs.toNumber(), newDepositConfig.nodeSecurityDepositGracePeriodSecs.toNumber())
   });
 });
 
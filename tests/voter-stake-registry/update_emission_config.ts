@@ -0,0 +1,82 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultEmissionConfig, defaultVotingConfig, EXP_SCALE, newSigner, SECS_PER_DAY, TOTAL_REWARD_AMOUNT, VSR_PROGRAM } from "../helper";
+import { assert } from "chai";
+
+describe("update_emission_config!", () => {
+  let realmAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+
+  before(async () => {
+    realmAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(realmAuthority);
+
+    [registrar] = await createRegistrar(realm, realmAuthority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), realmAuthority);
+  })
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const invalidRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await VSR_PROGRAM.methods.updateEmissionConfig(
+        defaultEmissionConfig(),
+      ).accounts({
+        registrar,
+        realmAuthority: invalidRealmAuthority.publicKey,
+      }).signers([invalidRealmAuthority])
+        .rpc()
+    })
+  });
+
+  it("with_invalid_emission_rate_should_fail", async () => {
+    const newEmissionConfig = defaultEmissionConfig();
+    newEmissionConfig.emissionRateBps = 10001;
+
+    await assertThrowsAnchorError('InvalidEmissionConfig', async () => {
+      await VSR_PROGRAM.methods.updateEmissionConfig(
+        newEmissionConfig,
+      ).accounts({
+        registrar,
+        realmAuthority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+        .rpc()
+    })
+  });
+
+  it("verify_update_emission_config", async () => {
+    const newEmissionConfig = {
+      totalRewardAmount: TOTAL_REWARD_AMOUNT.divn(2),
+      fullRewardPermanentlyLockedFloor: new anchor.BN("100000000000000"),
+      rotationSecs: SECS_PER_DAY.muln(30).toNumber(),
+      emissionRateBps: 500,
+      reserved: [0, 0],
+    };
+
+    await VSR_PROGRAM.methods.updateEmissionConfig(
+      newEmissionConfig,
+    ).accounts({
+      registrar,
+      realmAuthority: realmAuthority.publicKey,
+    }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" })
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.emissionConfig.totalRewardAmount.toString(), newEmissionConfig.totalRewardAmount.toString())
+    assert.equal(registrarData.emissionConfig.fullRewardPermanentlyLockedFloor.toString(), newEmissionConfig.fullRewardPermanentlyLockedFloor.toString())
+    assert.equal(registrarData.emissionConfig.rotationSecs, newEmissionConfig.rotationSecs)
+    assert.equal(registrarData.emissionConfig.emissionRateBps, newEmissionConfig.emissionRateBps)
+
+    // rewards are accrued and the reward amount per second is rotated immediately
+    assert.equal(registrarData.lastRewardAmountPerSecondRotatedTs.toString(), registrarData.rewardAccrualTs.toString());
+    const expectCurrentRewardAmountPerSecond = newEmissionConfig.totalRewardAmount
+      .sub(registrarData.issuedRewardAmount)
+      .muln(5)
+      .divn(100)
+      .mul(EXP_SCALE)
+      .divn(newEmissionConfig.rotationSecs);
+    assert.equal(registrarData.currentRewardAmountPerSecond.toString(), expectCurrentRewardAmountPerSecond.toString());
+  });
+});
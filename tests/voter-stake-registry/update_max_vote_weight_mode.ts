@@ -0,0 +1,140 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, getMint, lockupDayily, mintTokenToAccount, mintTokenToWallet, newSigner, newTokenAccount, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { getMaxVoterWeightRecord } from "@solana/spl-governance";
+import { assert } from "chai";
+
+describe("update_max_vote_weight_mode!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let maxVoterWeightRecord: web3.PublicKey;
+  let excludedSupply: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let treasury: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    // the default voting config gives one vote weight per token
+    [registrar, , , , maxVoterWeightRecord] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [excludedSupply] = web3.PublicKey.findProgramAddressSync([registrar.toBytes(), Buffer.from("excluded-supply")], VSR_PROGRAM.programId);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+
+    const depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount.muln(2));
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    treasury = await mintTokenToWallet(mint, authority, web3.Keypair.generate().publicKey, depositAmount.muln(5));
+  })
+
+  async function updateMaxVoteWeightMode(mode: any, excludedTokenAccounts: web3.PublicKey[], realmAuthority: web3.Keypair = authority) {
+    await VSR_PROGRAM.methods
+      .updateMaxVoteWeightMode(mode, excludedTokenAccounts)
+      .accounts({
+        registrar,
+        excludedSupply,
+        realmAuthority: realmAuthority.publicKey,
+        payer: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function updateMaxVoteWeight(excludedTokenAccounts: web3.PublicKey[] = [], withExcludedSupply: boolean = true): Promise<anchor.BN> {
+    await VSR_PROGRAM.methods.updateMaxVoteWeight()
+      .accounts({
+        registrar,
+        governingTokenMint: mint,
+        excludedSupply: withExcludedSupply ? excludedSupply : null,
+        maxVoterWeightRecord,
+      })
+      .remainingAccounts(excludedTokenAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false })))
+      .rpc({ commitment: "confirmed" });
+    const record = await getMaxVoterWeightRecord(anchor.getProvider().connection, maxVoterWeightRecord);
+    return record.account.maxVoterWeight;
+  }
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const fakeRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await updateMaxVoteWeightMode({ deposited: {} }, [], fakeRealmAuthority);
+    });
+  });
+
+  it("verify_deposited_mode", async () => {
+    const supply = new anchor.BN((await getMint(mint)).supply.toString());
+    assert.equal((await updateMaxVoteWeight([], false)).toString(), supply.toString());
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.totalDepositedAmount.toString(), depositAmount.toString());
+    assert.equal(registrarData.totalLockedAmount.toString(), depositAmount.toString());
+
+    await updateMaxVoteWeightMode({ deposited: {} }, []);
+    assert.equal((await updateMaxVoteWeight([], false)).toString(), depositAmount.toString());
+  });
+
+  it("deposited_mode_with_uncounted_voters_should_fail", async () => {
+    async function updateUncountedVoterCount(count: number) {
+      await VSR_PROGRAM.methods
+        .updateUncountedVoterCount(new anchor.BN(count))
+        .accounts({
+          registrar,
+          realmAuthority: authority.publicKey,
+        }).signers([authority])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    await updateMaxVoteWeightMode({ supply: {} }, []);
+    await updateUncountedVoterCount(1);
+    await assertThrowsAnchorError('UncountedVoters', async () => {
+      await updateMaxVoteWeightMode({ deposited: {} }, []);
+    });
+
+    await updateUncountedVoterCount(0);
+    await updateMaxVoteWeightMode({ deposited: {} }, []);
+  });
+
+  it("verify_circulating_supply_mode", async () => {
+    await updateMaxVoteWeightMode({ circulatingSupply: {} }, [treasury]);
+    const excludedSupplyData = await VSR_PROGRAM.account.excludedSupply.fetch(excludedSupply);
+    assert.equal(excludedSupplyData.tokenAccountCount, 1);
+    assert.equal(excludedSupplyData.tokenAccounts[0].toBase58(), treasury.toBase58());
+
+    await assertThrowsAnchorError('ExcludedSupplyRequired', async () => {
+      await updateMaxVoteWeight([treasury], false);
+    });
+    await assertThrowsAnchorError('InvalidExcludedTokenAccounts', async () => {
+      await updateMaxVoteWeight([]);
+    });
+    await assertThrowsAnchorError('InvalidExcludedTokenAccounts', async () => {
+      await updateMaxVoteWeight([vault]);
+    });
+
+    const supply = new anchor.BN((await getMint(mint)).supply.toString());
+    assert.equal((await updateMaxVoteWeight([treasury])).toString(), supply.sub(depositAmount.muln(5)).toString());
+  });
+
+  it("with_duplicate_excluded_token_accounts_should_fail", async () => {
+    await assertThrowsAnchorError('DuplicateExcludedTokenAccount', async () => {
+      await updateMaxVoteWeightMode({ circulatingSupply: {} }, [treasury, treasury]);
+    });
+  });
+});
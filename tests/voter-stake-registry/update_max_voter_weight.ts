@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { CIRCUIT_BREAKER_PROGRAM, createRealm, defaultDepositConfig, defaultVotingConfig, DepositConfig, GOV_PROGRAM_ID, mintTokenToWallet, newSigner, SECS_PER_DAY, VotingConfig, VSR_PROGRAM } from "../helper";
+import { CIRCUIT_BREAKER_PROGRAM, createRealm, defaultDepositConfig, defaultEmissionConfig, defaultVotingConfig, DepositConfig, EmissionConfig, GOV_PROGRAM_ID, linearVoteWeightCurve, mintTokenToWallet, newSigner, SECS_PER_DAY, VotingConfig, VSR_PROGRAM } from "../helper";
 import { assert } from "chai";
 import { getAssociatedTokenAddressSync } from "@solana/spl-token";
 import { getMaxVoterWeightRecord } from "@solana/spl-governance";
@@ -20,6 +20,7 @@ async function createRegistrar(
   votingConfig?: VotingConfig,
   depositConfig?: DepositConfig,
   circuit_breaker_threshold?: anchor.BN,
+  emissionConfig?: EmissionConfig,
 ) {
   if (votingConfig == undefined) {
     votingConfig = defaultVotingConfig();
@@ -33,6 +34,10 @@ async function createRegistrar(
     circuit_breaker_threshold = new anchor.BN(1e10);
   }
 
+  if (emissionConfig == undefined) {
+    emissionConfig = defaultEmissionConfig();
+  }
+
   const circuit_breaker_config = {
     windowSizeSeconds: SECS_PER_DAY,
     thresholdType: { absolute: {} },
@@ -44,6 +49,7 @@ async function createRegistrar(
     maxVoterWeightRecordBump,
     votingConfig,
     depositConfig,
+    emissionConfig,
     circuit_breaker_config
   ).accounts({
     registrar,
@@ -80,6 +86,7 @@ describe("update_max_voter_weight!", () => {
       baselineVoteWeightScaledFactor: new anchor.BN(11e8), // 1.1
       maxExtraLockupVoteWeightScaledFactor: new anchor.BN(0),
       lockupSaturationSecs: new anchor.BN(86400),
+      voteWeightCurve: linearVoteWeightCurve(),
     };
 
     await createRegistrar(bump, maxVoterWeightRecordBump, registrar, vault, circuitBreaker, maxVoterWeightRecord, realm, mint, authority, authority, votingConfig);
@@ -0,0 +1,128 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, newSigner, VSR_PROGRAM, createRealm, createVoter, newTokenAccount, mintTokenToAccount, createRegistrar, defaultDepositConfig, defaultVotingConfig, nodeAddress } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("update_node!", () => {
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+  let node: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+    node = nodeAddress(registrar, voterAuthority.publicKey);
+  })
+
+  async function updateNode(operator: web3.PublicKey, endpoint: string): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .updateNode(operator, endpoint)
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        node,
+        payer: voterAuthority.publicKey,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function setNodeStatus(signer: web3.Keypair, status: { active: {} } | { exiting: {} } | { jailed: {} }): Promise<string> {
+    return await VSR_PROGRAM.methods
+      .setNodeStatus(status)
+      .accounts({
+        registrar,
+        node,
+        voter,
+        authority: signer.publicKey,
+      }).signers([signer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function activeNodeCount(): Promise<number> {
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    return registrarData.activeNodeCount.toNumber();
+  }
+
+  it("without_node_deposit_should_fail", async () => {
+    await assertThrowsAnchorError('InactiveDepositEntry', async () => {
+      await updateNode(voterAuthority.publicKey, "node-1");
+    });
+  });
+
+  it("verify_node_registered_on_node_deposit", async () => {
+    await mintTokenToAccount(mint, authority, depositToken, defaultDepositConfig().nodeSecurityDeposit);
+    await VSR_PROGRAM.methods
+      .nodeDeposit()
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        node,
+        payer: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+
+    const nodeData = await VSR_PROGRAM.account.node.fetch(node, "confirmed");
+    assert.equal(nodeData.registrar.toBase58(), registrar.toBase58());
+    assert.equal(nodeData.voterAuthority.toBase58(), voterAuthority.publicKey.toBase58());
+    assert.equal(nodeData.operator.toBase58(), voterAuthority.publicKey.toBase58());
+    assert.isTrue(nodeData.status.active != undefined);
+    assert.isTrue(nodeData.registrationTs.gtn(0));
+    assert.equal(await activeNodeCount(), 1);
+  });
+
+  it("with_too_long_endpoint_should_fail", async () => {
+    await assertThrowsAnchorError('InvalidNodeEndpoint', async () => {
+      await updateNode(voterAuthority.publicKey, "x".repeat(65));
+    });
+  });
+
+  it("verify_update_node", async () => {
+    const operator = web3.Keypair.generate().publicKey;
+    await updateNode(operator, "https://node-1.example.com");
+
+    const nodeData = await VSR_PROGRAM.account.node.fetch(node, "confirmed");
+    assert.equal(nodeData.operator.toBase58(), operator.toBase58());
+    assert.equal(Buffer.from(nodeData.endpoint).toString("utf8").replace(/\0+$/, ""), "https://node-1.example.com");
+  });
+
+  it("verify_set_node_status", async () => {
+    // the voter authority may announce to exit
+    await setNodeStatus(voterAuthority, { exiting: {} });
+    assert.equal(await activeNodeCount(), 0);
+
+    // but not jail its node
+    await assertThrowsAnchorError('InvalidNodeStatus', async () => {
+      await setNodeStatus(voterAuthority, { jailed: {} });
+    });
+
+    const stranger = await newSigner();
+    await assertThrowsAnchorError('InvalidAuthority', async () => {
+      await setNodeStatus(stranger, { active: {} });
+    });
+
+    // only the realm authority releases a jailed node
+    await setNodeStatus(authority, { jailed: {} });
+    await assertThrowsAnchorError('InvalidNodeStatus', async () => {
+      await setNodeStatus(voterAuthority, { active: {} });
+    });
+    await setNodeStatus(authority, { active: {} });
+    assert.equal(await activeNodeCount(), 1);
+  });
+});
@@ -0,0 +1,65 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, CIRCUIT_BREAKER_PROGRAM, CONNECTION, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, GOV_PROGRAM_ID, newSigner, VSR_PROGRAM } from "../helper";
+import { SetRealmAuthorityAction, withSetRealmAuthority } from "@solana/spl-governance";
+import { assert } from "chai";
+
+describe("update_registrar_authority!", () => {
+  let authority: web3.Keypair;
+  let newAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let circuitBreaker: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    newAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar, , vault, circuitBreaker] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+  })
+
+  async function updateRegistrarAuthority(realmAuthority: web3.Keypair) {
+    await VSR_PROGRAM.methods
+      .updateRegistrarAuthority()
+      .accounts({
+        registrar,
+        realm,
+        vault,
+        circuitBreaker,
+        realmAuthority: realmAuthority.publicKey,
+        circuitBreakerProgram: CIRCUIT_BREAKER_PROGRAM.programId,
+      }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const fakeRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await updateRegistrarAuthority(fakeRealmAuthority);
+    });
+  });
+
+  it("verify_update_registrar_authority", async () => {
+    // move the realm to a new authority
+    const instructions: web3.TransactionInstruction[] = [];
+    withSetRealmAuthority(instructions, GOV_PROGRAM_ID, 3, realm, authority.publicKey, newAuthority.publicKey, SetRealmAuthorityAction.SetUnchecked);
+    await web3.sendAndConfirmTransaction(CONNECTION, new web3.Transaction().add(...instructions), [authority]);
+
+    await updateRegistrarAuthority(authority);
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.realmAuthority.toBase58(), newAuthority.publicKey.toBase58());
+    const circuitBreakerData = await CIRCUIT_BREAKER_PROGRAM.account.accountWindowedCircuitBreakerV0.fetch(circuitBreaker);
+    assert.equal(circuitBreakerData.authority.toBase58(), newAuthority.publicKey.toBase58());
+
+    // the previous authority is stranded now
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await updateRegistrarAuthority(authority);
+    });
+    await updateRegistrarAuthority(newAuthority);
+  });
+});
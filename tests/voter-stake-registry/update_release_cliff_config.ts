@@ -0,0 +1,53 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, newSigner, VSR_PROGRAM } from "../helper";
+import { assert } from "chai";
+
+describe("update_release_cliff_config!", () => {
+  let realmAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+
+  before(async () => {
+    realmAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(realmAuthority);
+
+    [registrar] = await createRegistrar(realm, realmAuthority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), realmAuthority);
+  })
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const invalidRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await VSR_PROGRAM.methods.updateReleaseCliffConfig({
+        ordinaryReleaseCliffPeriods: 5,
+        nodeReleaseCliffPeriods: 2,
+      }).accounts({
+        registrar,
+        realmAuthority: invalidRealmAuthority.publicKey,
+      }).signers([invalidRealmAuthority])
+        .rpc()
+    })
+  });
+
+  it("verify_update_release_cliff_config", async () => {
+    let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.releaseCliffConfig.ordinaryReleaseCliffPeriods, 0);
+    assert.equal(registrarData.releaseCliffConfig.nodeReleaseCliffPeriods, 0);
+
+    await VSR_PROGRAM.methods.updateReleaseCliffConfig({
+      ordinaryReleaseCliffPeriods: 5,
+      nodeReleaseCliffPeriods: 2,
+    }).accounts({
+      registrar,
+      realmAuthority: realmAuthority.publicKey,
+    }).signers([realmAuthority])
+      .rpc()
+
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.releaseCliffConfig.ordinaryReleaseCliffPeriods, 5);
+    assert.equal(registrarData.releaseCliffConfig.nodeReleaseCliffPeriods, 2);
+  });
+});
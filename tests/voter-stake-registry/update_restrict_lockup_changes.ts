@@ -0,0 +1,106 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, LockupTimeDuration, mintTokenToAccount, newSigner, newTokenAccount, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("update_restrict_lockup_changes!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+  let tokenOwnerRecord: web3.PublicKey;
+  let depositToken: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault, tokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    depositToken = await newTokenAccount(mint, voterAuthority);
+  })
+
+  async function updateRestrictLockupChanges(restrictLockupChanges: boolean, realmAuthority: web3.Keypair = authority) {
+    await VSR_PROGRAM.methods
+      .updateRestrictLockupChanges(restrictLockupChanges)
+      .accounts({
+        registrar,
+        realmAuthority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function deposit(depositEntryIndex: number, duration: LockupTimeDuration, tokenOwnerRecord: web3.PublicKey | null = null) {
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(depositEntryIndex, depositAmount, duration)
+      .accounts({
+        registrar,
+        voter,
+        tokenOwnerRecord,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function releaseDeposit(depositEntryIndex: number, targetDepositEntryIndex: number, tokenOwnerRecord: web3.PublicKey | null) {
+    await VSR_PROGRAM.methods
+      .ordinaryReleaseDeposit(depositEntryIndex, targetDepositEntryIndex, depositAmount.divn(2))
+      .accounts({
+        registrar,
+        voter,
+        voterAuthority: voterAuthority.publicKey,
+        tokenOwnerRecord,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const fakeRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await updateRestrictLockupChanges(true, fakeRealmAuthority);
+    });
+  });
+
+  it("verify_restrict_lockup_changes", async () => {
+    await deposit(1, lockupDayily(15));
+
+    await updateRestrictLockupChanges(true);
+    let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.restrictLockupChanges, 1);
+
+    await assertThrowsAnchorError('TokenOwnerRecordRequired', async () => {
+      await releaseDeposit(1, 2, null);
+    });
+
+    const [, , , , otherTokenOwnerRecord] = await createVoter(realm, mint, registrar, authority);
+    await assertThrowsAnchorError('InvalidTokenOwnerRecord', async () => {
+      await releaseDeposit(1, 2, otherTokenOwnerRecord);
+    });
+
+    // the voter is not engaged in any vote
+    await releaseDeposit(1, 2, tokenOwnerRecord);
+
+    // deposits that keep the duration are not restricted
+    await deposit(1, lockupDayily(15));
+    await assertThrowsAnchorError('TokenOwnerRecordRequired', async () => {
+      await deposit(1, lockupDayily(30));
+    });
+    await deposit(1, lockupDayily(30), tokenOwnerRecord);
+
+    await updateRestrictLockupChanges(false);
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.restrictLockupChanges, 0);
+    await releaseDeposit(1, 3, null);
+  });
+});
@@ -0,0 +1,105 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, RewardBoostConfig, SECS_PER_DAY, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("update_reward_boost_config!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let realmAuthority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+
+  before(async () => {
+    realmAuthority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(realmAuthority);
+
+    [registrar] = await createRegistrar(realm, realmAuthority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), realmAuthority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, realmAuthority);
+
+    const depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, realmAuthority, depositToken, depositAmount);
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  })
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const invalidRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await VSR_PROGRAM.methods.updateRewardBoostConfig({
+        maxExtraLockupRewardWeightScaledFactor: new anchor.BN(1e9),
+        lockupSaturationSecs: SECS_PER_DAY.muln(30),
+      }).accounts({
+        registrar,
+        realmAuthority: invalidRealmAuthority.publicKey,
+      }).signers([invalidRealmAuthority])
+        .rpc()
+    })
+  });
+
+  it("with_zero_lockup_saturation_secs_should_fail", async () => {
+    await assertThrowsAnchorError('LockupSaturationMustBePositive', async () => {
+      await VSR_PROGRAM.methods.updateRewardBoostConfig({
+        maxExtraLockupRewardWeightScaledFactor: new anchor.BN(1e9),
+        lockupSaturationSecs: new anchor.BN(0),
+      }).accounts({
+        registrar,
+        realmAuthority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+        .rpc()
+    })
+  });
+
+  it("verify_update_reward_boost_config", async () => {
+    // without boost, the reward weight is the locked amount
+    let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.totalRewardWeight.toString(), depositAmount.toString());
+
+    const rewardBoostConfig: RewardBoostConfig = {
+      maxExtraLockupRewardWeightScaledFactor: new anchor.BN(1e9),
+      lockupSaturationSecs: SECS_PER_DAY.muln(30),
+    };
+    await VSR_PROGRAM.methods.updateRewardBoostConfig(
+      rewardBoostConfig,
+    ).accounts({
+      registrar,
+      realmAuthority: realmAuthority.publicKey,
+    }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" })
+
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.rewardBoostConfig.maxExtraLockupRewardWeightScaledFactor.toString(), rewardBoostConfig.maxExtraLockupRewardWeightScaledFactor.toString());
+    assert.equal(registrarData.rewardBoostConfig.lockupSaturationSecs.toString(), rewardBoostConfig.lockupSaturationSecs.toString());
+    // reward weights follow once refreshed
+    assert.equal(registrarData.totalRewardWeight.toString(), depositAmount.toString());
+
+    await VSR_PROGRAM.methods.refreshRewardWeight()
+      .accounts({
+        registrar,
+        voter,
+      }).rpc({ commitment: "confirmed" });
+
+    // a constant lockup of 15 days is boosted by half of the maximum
+    const expectRewardWeight = depositAmount.add(depositAmount.divn(2));
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar, "confirmed");
+    assert.equal(registrarData.totalRewardWeight.toString(), expectRewardWeight.toString());
+    const voterData = await VSR_PROGRAM.account.voter.fetch(voter, "confirmed");
+    assert.equal(voterData.rewardWeight.toString(), expectRewardWeight.toString());
+  });
+});
@@ -0,0 +1,121 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, newVoterWeightActionRequirement, registrarConfigAddress, SECS_PER_DAY, VoterWeightAction, VoterWeightActionRequirement, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { getVoterWeightRecord, VoterWeightAction as GovVoterWeightAction } from "@solana/spl-governance";
+import { assert } from "chai";
+
+describe("update_voter_weight_action_requirement!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let registrarConfig: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let voterWeightRecord: web3.PublicKey;
+  let vault: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    registrarConfig = registrarConfigAddress(registrar);
+    [voterAuthority, voter, voterWeightRecord, vault] = await createVoter(realm, mint, registrar, authority);
+
+    const depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount)
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  })
+
+  async function updateRequirement(action: VoterWeightAction, requirement: VoterWeightActionRequirement, realmAuthority: web3.Keypair = authority) {
+    await VSR_PROGRAM.methods
+      .updateVoterWeightActionRequirement(action, requirement)
+      .accounts({
+        registrar,
+        registrarConfig,
+        realmAuthority: realmAuthority.publicKey,
+        payer: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function updateVoterWeightRecord(action: VoterWeightAction | null, target: web3.PublicKey | null, withRegistrarConfig: boolean = true) {
+    await VSR_PROGRAM.methods
+      .updateVoterWeightRecord(action, target)
+      .accounts({
+        registrar,
+        voter,
+        registrarConfig: withRegistrarConfig ? registrarConfig : null,
+        voterWeightRecord,
+      })
+      .rpc({ commitment: "confirmed" });
+  }
+
+  it("with_incorrect_realm_authority_should_fail", async () => {
+    const fakeRealmAuthority = await newSigner();
+    await assertThrowsAnchorError('ConstraintHasOne', async () => {
+      await updateRequirement({ createProposal: {} }, newVoterWeightActionRequirement(depositAmount), fakeRealmAuthority);
+    });
+  });
+
+  it("verify_voter_weight_action_requirements", async () => {
+    // proposals may only be created with tokens locked for at least 30 days
+    await updateRequirement({ createProposal: {} }, newVoterWeightActionRequirement(depositAmount, SECS_PER_DAY.muln(30).toNumber()));
+    // governances may only be created by nodes
+    await updateRequirement({ createGovernance: {} }, newVoterWeightActionRequirement(new anchor.BN(0), 0, true));
+
+    const config = await VSR_PROGRAM.account.registrarConfig.fetch(registrarConfig);
+    assert.equal(config.voterWeightActionRequirements[3].minVoterWeight.toString(), depositAmount.toString());
+    assert.equal(config.voterWeightActionRequirements[3].minLockupSecs, SECS_PER_DAY.muln(30).toNumber());
+    assert.equal(config.voterWeightActionRequirements[2].requireNodeDeposit, 1);
+    let registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.hasVoterWeightActionRequirements, 1);
+
+    await assertThrowsAnchorError('VoterWeightActionRequired', async () => {
+      await updateVoterWeightRecord(null, null);
+    });
+    await assertThrowsAnchorError('RegistrarConfigRequired', async () => {
+      await updateVoterWeightRecord({ castVote: {} }, null, false);
+    });
+    await assertThrowsAnchorError('VoterWeightActionRequirementNotMet', async () => {
+      await updateVoterWeightRecord({ createProposal: {} }, null);
+    });
+    await assertThrowsAnchorError('VoterWeightActionRequirementNotMet', async () => {
+      await updateVoterWeightRecord({ createGovernance: {} }, null);
+    });
+
+    // actions without requirements are not restricted
+    const proposal = web3.Keypair.generate().publicKey;
+    await updateVoterWeightRecord({ castVote: {} }, proposal);
+    let record = (await getVoterWeightRecord(VSR_PROGRAM.provider.connection, voterWeightRecord)).account;
+    assert.equal(record.voterWeight.toString(), depositAmount.toString());
+    assert.equal(record.weightAction, GovVoterWeightAction.CastVote);
+    assert.equal(record.weightActionTarget.toBase58(), proposal.toBase58());
+
+    // removing all requirements lifts the restrictions
+    await updateRequirement({ createProposal: {} }, newVoterWeightActionRequirement(new anchor.BN(0)));
+    await updateRequirement({ createGovernance: {} }, newVoterWeightActionRequirement(new anchor.BN(0)));
+    registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    assert.equal(registrarData.hasVoterWeightActionRequirements, 0);
+
+    await updateVoterWeightRecord(null, null, false);
+    record = (await getVoterWeightRecord(VSR_PROGRAM.provider.connection, voterWeightRecord)).account;
+    assert.isUndefined(record.weightAction ?? undefined);
+    assert.isUndefined(record.weightActionTarget ?? undefined);
+  });
+});
@@ -1,7 +1,7 @@
 import * as anchor from "@coral-xyz/anchor";
 import { web3 } from "@coral-xyz/anchor";
 
-import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, newSigner, VSR_PROGRAM } from "../helper";
+import { assertThrowsAnchorError, createRealm, createRegistrar, defaultDepositConfig, defaultVotingConfig, linearVoteWeightCurve, newSigner, piecewiseLinearVoteWeightCurve, VSR_PROGRAM } from "../helper";
 import { assert } from "chai";
 
 describe("update_voting_config!", () => {
@@ -23,6 +23,7 @@ describe("update_voting_config!", () => {
       baselineVoteWeightScaledFactor: new anchor.BN(5e8),
       maxExtraLockupVoteWeightScaledFactor: new anchor.BN(1e8),
       lockupSaturationSecs: new anchor.BN(86400 * 30),
+      voteWeightCurve: linearVoteWeightCurve(),
     }
 
     await assertThrowsAnchorError('ConstraintHasOne', async () => {
@@ -42,6 +43,7 @@ describe("update_voting_config!", () => {
       baselineVoteWeightScaledFactor: new anchor.BN(5e8),
       maxExtraLockupVoteWeightScaledFactor: new anchor.BN(1e8),
       lockupSaturationSecs: new anchor.BN(86400 * 30),
+      voteWeightCurve: linearVoteWeightCurve(),
     }
 
     const invalidRealmAuthority = await newSigner();
@@ -62,6 +64,7 @@ describe("update_voting_config!", () => {
       baselineVoteWeightScaledFactor: new anchor.BN(5e8),
       maxExtraLockupVoteWeightScaledFactor: new anchor.BN(1e8),
       lockupSaturationSecs: new anchor.BN(86400 * 30),
+      voteWeightCurve: linearVoteWeightCurve(),
     }
 
     await VSR_PROGRAM.methods.updateVotingConfig(
@@ -78,6 +81,59 @@ describe("update_voting_config!", () => {
     assert.equal(registrarData.votingConfig.maxExtraLockupVoteWeightScaledFactor.toNumber(), newVotingConfig.maxExtraLockupVoteWeightScaledFactor.toNumber())
     assert.equal(registrarData.votingConfig.lockupSaturationSecs.toNumber(), newVotingConfig.lockupSaturationSecs.toNumber())
   });
-});
 
+  it("with_invalid_vote_weight_curve_should_fail", async () => {
+    // weight must not decrease
+    const newVotingConfig = {
+      baselineVoteWeightScaledFactor: new anchor.BN(5e8),
+      maxExtraLockupVoteWeightScaledFactor: new anchor.BN(1e8),
+      lockupSaturationSecs: new anchor.BN(86400 * 30),
+      voteWeightCurve: piecewiseLinearVoteWeightCurve([
+        { lockupBps: 2500, weightBps: 6000 },
+        { lockupBps: 5000, weightBps: 5000 },
+      ]),
+    }
+
+    await assertThrowsAnchorError('InvalidVoteWeightCurve', async () => {
+      await VSR_PROGRAM.methods.updateVotingConfig(
+        newVotingConfig,
+      ).accounts({
+        registrar,
+        governingTokenMint: mint,
+        realmAuthority: realmAuthority.publicKey,
+      }).signers([realmAuthority])
+        .rpc()
+    })
+  });
+
+  it("verify_update_voting_config_with_piecewise_linear_curve", async () => {
+    const newVotingConfig = {
+      baselineVoteWeightScaledFactor: new anchor.BN(5e8),
+      maxExtraLockupVoteWeightScaledFactor: new anchor.BN(1e8),
+      lockupSaturationSecs: new anchor.BN(86400 * 30),
+      voteWeightCurve: piecewiseLinearVoteWeightCurve([
+        { lockupBps: 2500, weightBps: 5000 },
+        { lockupBps: 5000, weightBps: 7500 },
+      ]),
+    }
 
+    await VSR_PROGRAM.methods.updateVotingConfig(
+      newVotingConfig,
+    ).accounts({
+      registrar,
+      governingTokenMint: mint,
+      realmAuthority: realmAuthority.publicKey,
+    }).signers([realmAuthority])
+      .rpc()
+
+    const registrarData = await VSR_PROGRAM.account.registrar.fetch(registrar);
+    const curve = registrarData.votingConfig.voteWeightCurve;
+    assert.isDefined(curve.kind.piecewiseLinear);
+    assert.equal(curve.pointCount, 2);
+    assert.equal(curve.points[0].lockupBps, 2500);
+    assert.equal(curve.points[0].weightBps, 5000);
+    assert.equal(curve.points[1].lockupBps, 5000);
+    assert.equal(curve.points[1].weightBps, 7500);
+    assert.equal(curve.points[2].lockupBps, 0);
+  });
+});
@@ -0,0 +1,160 @@
+import * as anchor from "@coral-xyz/anchor";
+import { web3 } from "@coral-xyz/anchor";
+
+import { assertThrowsAnchorError, createRealm, createRegistrar, createVoter, defaultDepositConfig, defaultVotingConfig, lockupDayily, mintTokenToAccount, newSigner, newTokenAccount, nodeAddress, registrarStatsAddress, VSR_PROGRAM } from "../helper";
+import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("view_voter_info!", () => {
+  const depositAmount = new anchor.BN(1e9); // 1000 tokens
+  const VOTER_VIEW_DEPOSIT_ENTRY_COUNT = 8;
+  const MAX_VOTER_DEPOSIT_ENTRY_COUNT = 64;
+
+  let authority: web3.Keypair;
+  let mint: web3.PublicKey;
+  let councilMint: web3.PublicKey;
+  let realm: web3.PublicKey;
+  let registrar: web3.PublicKey;
+  let voterAuthority: web3.Keypair;
+  let voter: web3.PublicKey;
+  let vault: web3.PublicKey;
+
+  before(async () => {
+    authority = await newSigner();
+    [mint, councilMint, realm] = await createRealm(authority);
+    [registrar] = await createRegistrar(realm, authority, mint, defaultVotingConfig(), defaultDepositConfig(), new anchor.BN(1e10), authority);
+    [voterAuthority, voter, , vault] = await createVoter(realm, mint, registrar, authority);
+
+    const depositToken = await newTokenAccount(mint, voterAuthority);
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount);
+    await VSR_PROGRAM.methods
+      .ordinaryDeposit(1, depositAmount, lockupDayily(15))
+      .accounts({
+        registrar,
+        voter,
+        vault,
+        depositToken,
+        depositAuthority: voterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([voterAuthority])
+      .rpc({ commitment: "confirmed" });
+  })
+
+  it("view_voter_info", async () => {
+    const voterView = await VSR_PROGRAM.methods
+      .viewVoterInfo(0)
+      .accounts({
+        registrar,
+        voter,
+      })
+      .view();
+    assert.equal(voterView.depositEntryCount, 16);
+    assert.equal(voterView.depositEntries.length, VOTER_VIEW_DEPOSIT_ENTRY_COUNT);
+    assert.equal(voterView.votingPower.toString(), depositAmount.toString());
+    assert.equal(voterView.rewardWeight.toString(), depositAmount.toString());
+    assert.isNull(voterView.depositEntries[0]);
+    assert.equal(voterView.depositEntries[1].amountLocked.toString(), depositAmount.toString());
+    assert.isFalse(voterView.nodeNonCompliant);
+
+    // the same values as log_voter_info
+    const response = await VSR_PROGRAM.methods
+      .logVoterInfo()
+      .accounts({
+        registrar,
+        voter,
+      })
+      .simulate();
+    const voterInfo = response.events[0].data;
+    assert.equal(voterView.votingPower.toString(), voterInfo.votingPower.toString());
+    assert.equal(voterView.votingPowerBaseline.toString(), voterInfo.votingPowerBaseline.toString());
+    assert.equal(voterView.rewardWeight.toString(), voterInfo.rewardWeight.toString());
+  });
+
+  it("view_registrar_info", async () => {
+    const registrarView = await VSR_PROGRAM.methods
+      .viewRegistrarInfo()
+      .accounts({
+        registrar,
+        registrarStats: registrarStatsAddress(registrar),
+      })
+      .view();
+    assert.equal(registrarView.voterCount.toNumber(), 1);
+    assert.equal(registrarView.depositedAmount.toString(), depositAmount.toString());
+    assert.equal(registrarView.permanentlyLockedAmount.toString(), depositAmount.toString());
+  });
+
+  it("with_out_of_bounds_deposit_entry_index_should_fail", async () => {
+    await assertThrowsAnchorError('OutOfBoundsDepositEntryIndex', async () => {
+      await VSR_PROGRAM.methods
+        .viewVoterInfo(16)
+        .accounts({
+          registrar,
+          voter,
+        })
+        .rpc();
+    });
+  });
+
+  it("view_voter_info_of_full_voter", async () => {
+    const [fullVoterAuthority, fullVoter, , fullVault] = await createVoter(realm, mint, registrar, authority);
+    const depositToken = await newTokenAccount(mint, fullVoterAuthority);
+    const nodeSecurityDeposit = defaultDepositConfig().nodeSecurityDeposit;
+    await mintTokenToAccount(mint, authority, depositToken, depositAmount.add(nodeSecurityDeposit));
+
+    await VSR_PROGRAM.methods
+      .resizeVoter(MAX_VOTER_DEPOSIT_ENTRY_COUNT)
+      .accounts({
+        registrar,
+        voter: fullVoter,
+        voterAuthority: fullVoterAuthority.publicKey,
+        payer: fullVoterAuthority.publicKey,
+      }).signers([fullVoterAuthority])
+      .rpc({ commitment: "confirmed" });
+    await VSR_PROGRAM.methods
+      .nodeDeposit()
+      .accounts({
+        registrar,
+        voter: fullVoter,
+        vault: fullVault,
+        depositToken,
+        depositAuthority: fullVoterAuthority.publicKey,
+        node: nodeAddress(registrar, fullVoterAuthority.publicKey),
+        payer: fullVoterAuthority.publicKey,
+        tokenProgram: TOKEN_PROGRAM_ID
+      }).signers([fullVoterAuthority])
+      .rpc({ commitment: "confirmed" });
+    const entryAmount = depositAmount.divn(MAX_VOTER_DEPOSIT_ENTRY_COUNT);
+    for (let i = 1; i < MAX_VOTER_DEPOSIT_ENTRY_COUNT; i++) {
+      await VSR_PROGRAM.methods
+        .ordinaryDeposit(i, entryAmount, lockupDayily(15))
+        .accounts({
+          registrar,
+          voter: fullVoter,
+          vault: fullVault,
+          depositToken,
+          depositAuthority: fullVoterAuthority.publicKey,
+          tokenProgram: TOKEN_PROGRAM_ID
+        }).signers([fullVoterAuthority])
+        .rpc({ commitment: "confirmed" });
+    }
+
+    // every deposit entry is returned, one page at a time
+    let amountLocked = new anchor.BN(0);
+    for (let first = 0; first < MAX_VOTER_DEPOSIT_ENTRY_COUNT; first += VOTER_VIEW_DEPOSIT_ENTRY_COUNT) {
+      const voterView = await VSR_PROGRAM.methods
+        .viewVoterInfo(first)
+        .accounts({
+          registrar,
+          voter: fullVoter,
+        })
+        .view({ commitment: "confirmed" });
+      assert.equal(voterView.depositEntryCount, MAX_VOTER_DEPOSIT_ENTRY_COUNT);
+      assert.equal(voterView.depositEntries.length, VOTER_VIEW_DEPOSIT_ENTRY_COUNT);
+      for (const entry of voterView.depositEntries) {
+        assert.isNotNull(entry);
+        amountLocked = amountLocked.add(entry.amountLocked);
+      }
+    }
+    assert.equal(amountLocked.toString(), entryAmount.muln(MAX_VOTER_DEPOSIT_ENTRY_COUNT - 1).add(nodeSecurityDeposit).toString());
+  });
+});